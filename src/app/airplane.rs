@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use crate::dbus::{BluetoothManager, WifiBackend};
+
+/// Radios that were on when airplane mode was switched on. Kept on disk so the header
+/// button and `orbit airplane` agree on what to restore.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+struct SavedRadios {
+    wifi: bool,
+    bluetooth: bool,
+}
+
+/// `$XDG_STATE_HOME/orbit/airplane.toml`, under `~/.local/state` when it is unset.
+/// Relative `XDG_STATE_HOME` values are ignored, as the XDG spec requires.
+fn state_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state")))?;
+    Some(base.join("orbit").join("airplane.toml"))
+}
+
+fn load_saved() -> Option<SavedRadios> {
+    let content = std::fs::read_to_string(state_path()?).ok()?;
+    toml::from_str(&content).ok()
+}
+
+fn store_saved(radios: SavedRadios) {
+    let path = match state_path() {
+        Some(p) => p,
+        None => return,
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let result = toml::to_string(&radios)
+        .map_err(|e| e.to_string())
+        .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to save airplane mode state: {}", e);
+    }
+}
+
+/// Airplane mode is on from `enable` until the matching `disable`, as long as both radios
+/// stay off. One turned back on some other way (e.g. `nmcli radio wifi on`) ends it.
+pub fn is_enabled(wifi_on: bool, bluetooth_on: bool) -> bool {
+    !wifi_on && !bluetooth_on && state_path().is_some_and(|p| p.exists())
+}
+
+/// Turns WiFi and Bluetooth off, remembering which of them were on. The record is only
+/// written once both are off, so a failed attempt doesn't count as airplane mode.
+pub async fn enable(nm: Option<&dyn WifiBackend>, bt: Option<&BluetoothManager>) -> zbus::Result<()> {
+    let radios = SavedRadios {
+        wifi: match nm {
+            Some(nm) => nm.is_wifi_enabled().await.unwrap_or(false),
+            None => false,
+        },
+        bluetooth: match bt {
+            Some(bt) => bt.is_powered().await.unwrap_or(false),
+            None => false,
+        },
+    };
+    // Already on; recording the radios now would forget which ones to restore
+    if is_enabled(radios.wifi, radios.bluetooth) {
+        return Ok(());
+    }
+
+    if let (true, Some(nm)) = (radios.wifi, nm) {
+        nm.set_wifi_enabled(false).await?;
+    }
+    if let (true, Some(bt)) = (radios.bluetooth, bt) {
+        bt.set_powered(false).await?;
+    }
+    store_saved(radios);
+    Ok(())
+}
+
+/// Turns back on the radios that were on before `enable`. Without a record of them,
+/// both are turned on. The record is kept until both are back, so a failed attempt can
+/// be retried.
+pub async fn disable(nm: Option<&dyn WifiBackend>, bt: Option<&BluetoothManager>) -> zbus::Result<()> {
+    let radios = load_saved().unwrap_or(SavedRadios { wifi: true, bluetooth: true });
+
+    if let (true, Some(nm)) = (radios.wifi, nm) {
+        nm.set_wifi_enabled(true).await?;
+    }
+    if let (true, Some(bt)) = (radios.bluetooth, bt) {
+        bt.set_powered(true).await?;
+    }
+    if let Some(path) = state_path() {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
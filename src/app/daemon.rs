@@ -5,14 +5,76 @@ use tokio::net::UnixListener;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 const SOCKET_NAME: &str = "orbit.sock";
+/// Longest command the daemon accepts; anything past it is dropped.
+const MAX_COMMAND_BYTES: usize = 4096;
+/// How long a client gets to finish sending its command line.
+const COMMAND_READ_SECS: u64 = 2;
+
+/// Stack pages a `toggle` can open to.
+pub const TABS: [&str; 3] = ["wifi", "saved", "bluetooth"];
+
+/// Per-edge margin overrides carried by a `toggle`, in pixels.
+#[derive(Debug, Clone, Default)]
+pub struct Margins {
+    pub top: Option<i32>,
+    pub right: Option<i32>,
+    pub bottom: Option<i32>,
+    pub left: Option<i32>,
+}
+
+impl Margins {
+    /// Reads a `margin=N` (all edges) or `margin-<edge>=N` token; false if it is neither.
+    fn parse_token(&mut self, token: &str) -> bool {
+        let (key, value) = match token.split_once('=') {
+            Some((key, value)) => (key, value),
+            None => return false,
+        };
+        let value = match value.trim().parse::<i32>() {
+            Ok(v) => v,
+            Err(_) => return false,
+        };
+        match key {
+            "margin" => {
+                self.top = Some(value);
+                self.right = Some(value);
+                self.bottom = Some(value);
+                self.left = Some(value);
+            }
+            "margin-top" => self.top = Some(value),
+            "margin-right" => self.right = Some(value),
+            "margin-bottom" => self.bottom = Some(value),
+            "margin-left" => self.left = Some(value),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.top.is_none() && self.right.is_none() && self.bottom.is_none() && self.left.is_none()
+    }
+
+    fn to_tokens(&self) -> String {
+        if let (Some(top), Some(right), Some(bottom), Some(left)) = (self.top, self.right, self.bottom, self.left) {
+            if top == right && top == bottom && top == left {
+                return format!(":margin={}", top);
+            }
+        }
+        [("top", self.top), ("right", self.right), ("bottom", self.bottom), ("left", self.left)]
+            .iter()
+            .filter_map(|(edge, v)| v.map(|v| format!(":margin-{}={}", edge, v)))
+            .collect()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum DaemonCommand {
     Show,
     Hide,
-    Toggle(Option<String>),
+    /// Optional position, monitor connector, starting tab and margin overrides.
+    Toggle(Option<String>, Option<String>, Option<String>, Margins),
     ReloadTheme,
     ReloadConfig,
+    Status,
     Quit,
 }
 
@@ -28,13 +90,21 @@ impl DaemonCommand {
         } else if s.starts_with("reload-config") {
             Some(Self::ReloadConfig)
         } else if s.starts_with("toggle") {
-            let parts: Vec<&str> = s.split(':').collect();
-            let pos = if parts.len() > 1 && !parts[1].is_empty() {
-                Some(parts[1].to_string())
-            } else {
-                None
+            // toggle[:position[:monitor[:tab]]], or toggle:position:tab when no monitor is pinned,
+            // followed by any number of margin=N / margin-<edge>=N overrides
+            let mut margins = Margins::default();
+            let parts: Vec<&str> = s.trim_end().split(':')
+                .filter(|p| !margins.parse_token(p))
+                .collect();
+            let field = |i: usize| parts.get(i).filter(|p| !p.is_empty()).map(|p| p.to_string());
+            let pos = field(1);
+            let (monitor, tab) = match field(2) {
+                Some(p) if parts.len() == 3 && TABS.contains(&p.as_str()) => (None, Some(p)),
+                monitor => (monitor, field(3)),
             };
-            Some(Self::Toggle(pos))
+            Some(Self::Toggle(pos, monitor, tab, margins))
+        } else if s.starts_with("status") {
+            Some(Self::Status)
         } else if s.starts_with("quit") {
             Some(Self::Quit)
         } else {
@@ -48,13 +118,21 @@ impl DaemonCommand {
             Self::Hide => "hide".to_string(),
             Self::ReloadTheme => "reload-theme".to_string(),
             Self::ReloadConfig => "reload-config".to_string(),
-            Self::Toggle(pos) => {
-                if let Some(p) = pos {
-                    format!("toggle:{}", p)
-                } else {
-                    "toggle".to_string()
-                }
+            Self::Toggle(pos, monitor, tab, margins) => {
+                let base = match (pos, monitor, tab) {
+                    (pos, monitor, Some(t)) => format!(
+                        "toggle:{}:{}:{}",
+                        pos.as_deref().unwrap_or(""),
+                        monitor.as_deref().unwrap_or(""),
+                        t
+                    ),
+                    (pos, Some(m), None) => format!("toggle:{}:{}", pos.as_deref().unwrap_or(""), m),
+                    (Some(p), None, None) => format!("toggle:{}", p),
+                    (None, None, None) => "toggle".to_string(),
+                };
+                base + &margins.to_tokens()
             }
+            Self::Status => "status".to_string(),
             Self::Quit => "quit".to_string(),
         }
     }
@@ -76,6 +154,38 @@ fn get_socket_path() -> PathBuf {
     path
 }
 
+/// Removes a socket left behind by a daemon that died without cleaning up. Fails with
+/// `AddrInUse` if a live daemon still answers on it.
+fn remove_if_stale(socket_path: &std::path::Path) -> Result<(), std::io::Error> {
+    if !socket_path.exists() {
+        return Ok(());
+    }
+    if StdUnixStream::connect(socket_path).is_ok() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AddrInUse,
+            "Daemon is already running",
+        ));
+    }
+    log::info!("Removing stale socket: {:?}", socket_path);
+    std::fs::remove_file(socket_path)
+}
+
+/// Resolves on SIGTERM or SIGINT.
+async fn shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    let (mut term, mut int) = match (signal(SignalKind::terminate()), signal(SignalKind::interrupt())) {
+        (Ok(term), Ok(int)) => (term, int),
+        _ => {
+            log::warn!("Could not install signal handlers; the socket is only removed on a clean exit");
+            return std::future::pending().await;
+        }
+    };
+    tokio::select! {
+        _ = term.recv() => log::info!("Received SIGTERM, shutting down"),
+        _ = int.recv() => log::info!("Received SIGINT, shutting down"),
+    }
+}
+
 pub struct DaemonServer {
     listener: Option<UnixListener>,
     path: PathBuf,
@@ -86,17 +196,17 @@ impl DaemonServer {
         let socket_path = get_socket_path();
         log::info!("Starting daemon on socket: {:?}", socket_path);
         
-        if socket_path.exists() {
-            if let Ok(_) = StdUnixStream::connect(&socket_path) {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::AddrInUse,
-                    "Daemon is already running",
-                ));
-            }
-            let _ = std::fs::remove_file(&socket_path);
-        }
+        remove_if_stale(&socket_path)?;
         
-        let listener = UnixListener::bind(&socket_path)?;
+        let listener = match UnixListener::bind(&socket_path) {
+            Ok(listener) => listener,
+            // Another process may have left a socket behind between the check and the bind
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                remove_if_stale(&socket_path)?;
+                UnixListener::bind(&socket_path)?
+            }
+            Err(e) => return Err(e),
+        };
         
         Ok(Self {
             listener: Some(listener),
@@ -104,10 +214,14 @@ impl DaemonServer {
         })
     }
     
-    pub fn run<F>(mut self, callback: F) 
+    /// Serves commands on the socket. `status` builds the JSON reply for
+    /// `DaemonCommand::Status`; every other command is forwarded to `callback`.
+    pub fn run<F, S>(mut self, callback: F, status: S) 
     where
         F: Fn(DaemonCommand) + Send + 'static,
+        S: Fn() -> String + Send + Sync + 'static,
     {
+        let status = std::sync::Arc::new(status);
         if let Some(listener) = self.listener.take() {
             // Use a dedicated thread with its own tokio runtime to ensure the listener 
             // is never blocked by the GTK main loop and stays alive.
@@ -117,19 +231,46 @@ impl DaemonServer {
                     .build()
                     .unwrap();
                 
-                let _server_guard = self;
+                let server_guard = self;
                 
                 rt.block_on(async {
+                    let shutdown = shutdown_signal();
+                    tokio::pin!(shutdown);
                     loop {
-                        match listener.accept().await {
+                        let accepted = tokio::select! {
+                            _ = &mut shutdown => break,
+                            accepted = listener.accept() => accepted,
+                        };
+                        match accepted {
                             Ok((mut stream, _)) => {
-                                let mut buf = [0u8; 64];
-                                match stream.read(&mut buf).await {
-                                    Ok(n) if n > 0 => {
-                                        if let Some(cmd) = DaemonCommand::from_bytes(&buf[..n]) {
-                                            callback(cmd);
+                                let read = tokio::time::timeout(
+                                    std::time::Duration::from_secs(COMMAND_READ_SECS),
+                                    read_command(&mut stream),
+                                ).await;
+                                match read {
+                                    Ok(Ok(buf)) if !buf.is_empty() => {
+                                        if let Some(cmd) = DaemonCommand::from_bytes(&buf) {
+                                            let response = match cmd {
+                                                DaemonCommand::Status => {
+                                                    // The status provider blocks on D-Bus calls
+                                                    let status = status.clone();
+                                                    tokio::task::spawn_blocking(move || status())
+                                                        .await
+                                                        .unwrap_or_else(|_| "{}".to_string())
+                                                }
+                                                DaemonCommand::Quit => {
+                                                    // The app exits without unwinding, so Drop never runs
+                                                    let _ = std::fs::remove_file(&server_guard.path);
+                                                    callback(DaemonCommand::Quit);
+                                                    "ok".to_string()
+                                                }
+                                                cmd => {
+                                                    callback(cmd);
+                                                    "ok".to_string()
+                                                }
+                                            };
                                             // Ensure the write completes before closing
-                                            let _ = stream.write_all(b"ok").await;
+                                            let _ = stream.write_all(response.as_bytes()).await;
                                             let _ = stream.flush().await;
                                         } else {
                                             let _ = stream.write_all(b"unknown").await;
@@ -144,11 +285,35 @@ impl DaemonServer {
                         }
                     }
                 });
+                // Removes the socket before the whole process goes down
+                drop(server_guard);
+                std::process::exit(0);
             });
         }
     }
 }
 
+/// Reads one newline-terminated command (or everything up to EOF), without the newline.
+async fn read_command(stream: &mut tokio::net::UnixStream) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 256];
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.iter().position(|&b| b == b'\n') {
+            buf.truncate(end);
+            break;
+        }
+        if buf.len() > MAX_COMMAND_BYTES {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "command too long"));
+        }
+    }
+    Ok(buf)
+}
+
 impl Drop for DaemonServer {
     fn drop(&mut self) {
         if self.path.exists() {
@@ -158,6 +323,22 @@ impl Drop for DaemonServer {
     }
 }
 
+/// Escapes a string for embedding inside a JSON string literal.
+pub fn json_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 pub struct DaemonClient;
 
 impl DaemonClient {
@@ -169,12 +350,14 @@ impl DaemonClient {
         stream.set_read_timeout(Some(std::time::Duration::from_secs(2)))?;
         stream.set_write_timeout(Some(std::time::Duration::from_secs(2)))?;
         
-        stream.write_all(cmd.to_string().as_bytes())?;
+        // The newline ends the command; the daemon reads until it
+        stream.write_all(format!("{}\n", cmd.to_string()).as_bytes())?;
         stream.flush()?;
         
-        let mut buf = [0u8; 32];
-        let n = stream.read(&mut buf)?;
-        Ok(String::from_utf8_lossy(&buf[..n]).to_string())
+        // The server closes the stream after replying, so read until EOF
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf)?;
+        Ok(String::from_utf8_lossy(&buf).to_string())
     }
     
     pub fn is_daemon_running() -> bool {
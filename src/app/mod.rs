@@ -2,35 +2,78 @@ use gtk4::{Application, glib};
 use gtk4::prelude::*;
 use gtk4::gio::ApplicationFlags;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
+pub mod airplane;
 pub mod daemon;
+pub mod profiles;
+pub mod runtime;
+pub mod watcher;
 
 use crate::config::Config;
-use crate::theme::Theme;
-use crate::dbus::{NetworkManager, BluetoothManager};
-use crate::dbus::network_manager::{AccessPoint, SecurityType, SavedNetwork, NetworkDetails};
-use crate::dbus::bluez::BluetoothDevice;
-use crate::ui::{OrbitWindow, DeviceAction};
-use daemon::{DaemonServer, DaemonCommand};
+use crate::theme::{ColorScheme, Theme};
+use crate::dbus::{BluetoothManager, Feature, WifiBackend};
+use crate::dbus::network_manager::{self, AccessPoint, SecurityType, SavedNetwork, NetworkDetails, Ipv4Method, MacRandomMode, ConnectError, NetworkChange, CONNECTIVITY_PORTAL};
+use crate::dbus::bluez::{BluetoothAdapter, BluetoothChange, BluetoothDevice, CardProfiles};
+use crate::dbus::obex::ObexClient;
+use crate::dbus::portal::{self, GlobalShortcutsPortal, SettingsPortal};
+use crate::dbus::tray;
+use crate::ui::{OrbitWindow, DeviceAction, sanitize_error_message};
+use daemon::{DaemonServer, DaemonCommand, Margins};
+use runtime::Runtime;
 
 enum AppEvent {
     WifiScanResult(Vec<AccessPoint>),
+    WifiScanFinished,
     SavedNetworksResult(Vec<SavedNetwork>),
     NetworkDetailsResult(NetworkDetails),
+    ShareNetwork(String, Option<String>),
+    DeviceStats(u64, u64),
     BtScanResult(Vec<BluetoothDevice>),
+    BtScanFinished,
+    BtAdapters(Vec<BluetoothAdapter>, Option<String>),
+    BtDiscoverable(bool),
     WifiPowerState(bool),
+    /// What the connected WiFi backend implements.
+    WifiFeatures(Vec<Feature>),
+    NetworkManagerUnavailable,
+    /// BlueZ did not answer the first attempt; the init thread is still retrying.
+    BluetoothInitializing,
+    BluetoothUnavailable,
     BtPowerState(bool),
     ConnectStarted(String),
     ConnectSuccess,
+    ConnectCancelled,
     ConnectHidden(String, String),
+    WrongPassword(String),
+    /// SSID whose saved password was replaced.
+    PasswordChanged(String),
+    PasswordChangeFailed(String),
     DisconnectStarted(String),
+    /// SSID of the active WiFi connection, re-read whenever it changes.
+    ActiveSsid(Option<String>),
+    /// NetworkManager's global state and connectivity.
+    NetworkState(u32, u32),
     BtActionStarted(String, DeviceAction),
     BtActionComplete,
+    /// Sound server profiles of the audio device at this path.
+    BtAudioProfiles(String, CardProfiles),
+    /// File name, bytes sent, total bytes (0 if unknown).
+    BtTransferProgress(String, u64, u64),
+    BtTransferFinished,
+    WpsFinished,
+    /// Connection path, SSID and saved IPv4 settings of a profile to edit.
+    EditIpv4(String, String, Ipv4Method),
     Error(String),
     Notify(String),
-    CaptivePortal(String),
+    /// SSID and the portal's login page, when NetworkManager's probe revealed it.
+    CaptivePortal(String, Option<String>),
+    ColorSchemeChanged(ColorScheme),
+    NetworkChanged(NetworkChange),
+    BluetoothChanged(BluetoothChange),
     DaemonCommand(DaemonCommand),
     DaemonStarted(DaemonServer),
 }
@@ -54,7 +97,7 @@ impl OrbitApp {
     fn new_with_mode(config: Config, is_daemon: bool) -> Result<Self, glib::Error> {
         let app = Application::new(Some("com.orbit.app"), ApplicationFlags::empty());
         
-        let theme = Theme::load();
+        let theme = Theme::load(ColorScheme::default());
         let theme = Rc::new(RefCell::new(theme));
         
         Ok(Self {
@@ -85,11 +128,21 @@ impl OrbitApp {
             let config = config.clone();
             let win_theme = win_theme.clone();
             
-            let rt = Arc::new(tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime"));
+            let rt = Arc::new(Runtime::new().expect("Failed to create Tokio runtime"));
+            
+            // Resolve the system color scheme before the window first applies its CSS
+            let color_scheme = rt.block_on(async { system_color_scheme().await });
+            if color_scheme != win_theme.borrow().color_scheme {
+                let theme = Theme::load(color_scheme);
+                *win_theme.borrow_mut() = theme;
+            }
             let win = OrbitWindow::new(app, config, win_theme.clone());
             
-            let nm: Arc<Mutex<Option<NetworkManager>>> = Arc::new(Mutex::new(None));
+            let nm: Arc<Mutex<Option<Box<dyn WifiBackend>>>> = Arc::new(Mutex::new(None));
             let bt: Arc<Mutex<Option<BluetoothManager>>> = Arc::new(Mutex::new(None));
+            let connect_cancel = Arc::new(AtomicBool::new(false));
+            // `connect-retries` as of the last config reload, for backends reconnected later
+            let connect_retries = Arc::new(AtomicU32::new(win.connect_retries()));
             
             let (tx, rx) = async_channel::unbounded::<AppEvent>();
             
@@ -101,6 +154,8 @@ impl OrbitApp {
                 let nm_arc = nm.clone();
                 let bt_arc = bt.clone();
                 let tx_init = tx.clone();
+                let connect_retries = connect_retries.clone();
+                let connect_cancel = connect_cancel.clone();
                 
                 std::thread::spawn(move || {
                     if is_daemon {
@@ -116,29 +171,21 @@ impl OrbitApp {
                         }
                     }
 
-                    let mut nm_inst = None;
-                    for i in 0..5 {
-                        if let Ok(inst) = rt_init.block_on(async { NetworkManager::new().await }) {
-                            nm_inst = Some(inst);
-                            break;
-                        }
-                        if i < 4 {
-                            std::thread::sleep(std::time::Duration::from_secs(1));
-                        }
+                    let nm_inst = connect_wifi_backend(&rt_init, connect_retries.load(Ordering::SeqCst), &connect_cancel);
+                    if nm_inst.is_none() {
+                        log::error!("No WiFi backend is available, WiFi features are disabled");
+                        let _ = tx_init.send_blocking(AppEvent::NetworkManagerUnavailable);
                     }
 
-                    let mut bt_inst = None;
-                    for i in 0..5 {
-                        if let Ok(inst) = rt_init.block_on(async { BluetoothManager::new().await }) {
-                            bt_inst = Some(inst);
-                            break;
-                        }
-                        if i < 4 {
-                            std::thread::sleep(std::time::Duration::from_secs(1));
-                        }
+                    let bt_inst = connect_bluetooth(&rt_init, Some(&tx_init));
+                    if bt_inst.is_none() {
+                        log::error!("BlueZ is not available, Bluetooth features are disabled");
+                        let _ = tx_init.send_blocking(AppEvent::BluetoothUnavailable);
                     }
                     
+                    let mut try_hidden = false;
                     if let Some(ref nm) = nm_inst {
+                        let _ = tx_init.send_blocking(AppEvent::WifiFeatures(nm.features()));
                         if let Ok(enabled) = rt_init.block_on(async { nm.is_wifi_enabled().await }) {
                             let _ = tx_init.send_blocking(AppEvent::WifiPowerState(enabled));
                             
@@ -172,16 +219,13 @@ impl OrbitApp {
                                 if connected_ssid.is_none() {
                                     log::info!("Autoconnect timed out, triggering scan");
                                     let _ = rt_init.block_on(async { nm.scan().await });
+                                    try_hidden = true;
                                 }
-                                
-                                if let Some(ssid) = connected_ssid {
-                                    std::thread::sleep(std::time::Duration::from_secs(2));
-                                    if let Ok(connectivity) = rt_init.block_on(async { nm.check_connectivity().await }) {
-                                        if connectivity == 2 {
-                                            let _ = tx_init.send_blocking(AppEvent::CaptivePortal(ssid));
-                                        }
-                                    }
-                                }
+                                let _ = tx_init.send_blocking(AppEvent::ActiveSsid(connected_ssid));
+                            }
+                            
+                            if let Ok((state, connectivity)) = rt_init.block_on(async { nm.network_state().await }) {
+                                let _ = tx_init.send_blocking(AppEvent::NetworkState(state, connectivity));
                             }
                             
                             if let Ok(saved) = rt_init.block_on(async { nm.get_saved_networks().await }) {
@@ -191,6 +235,9 @@ impl OrbitApp {
                     }
                     
                     if let Some(ref bt) = bt_inst {
+                        if let Ok(adapters) = rt_init.block_on(async { bt.list_adapters().await }) {
+                            let _ = tx_init.send_blocking(AppEvent::BtAdapters(adapters, bt.selected_adapter()));
+                        }
                         if let Ok(powered) = rt_init.block_on(async { bt.is_powered().await }) {
                             let _ = tx_init.send_blocking(AppEvent::BtPowerState(powered));
                         }
@@ -199,10 +246,91 @@ impl OrbitApp {
                         }
                     }
                     
-                    let mut nm_guard = nm_arc.lock().unwrap();
-                    *nm_guard = nm_inst;
-                    let mut bt_guard = bt_arc.lock().unwrap();
-                    *bt_guard = bt_inst;
+                    // Watch on clones so the shared instances stay free for everything else
+                    if let Some(ref inst) = nm_inst {
+                        supervise_wifi(inst.clone_box(), nm_arc.clone(), rt_init.clone(), tx_init.clone(), connect_retries.clone(), connect_cancel.clone());
+                    }
+                    if let Some(ref inst) = bt_inst {
+                        supervise_bluetooth(inst.clone(), bt_arc.clone(), rt_init.clone(), tx_init.clone());
+                    }
+
+                    *nm_arc.lock().unwrap() = nm_inst;
+                    *bt_arc.lock().unwrap() = bt_inst;
+
+                    // Queued after init so both tabs have their data while these are tried
+                    if try_hidden {
+                        let nm = nm_arc.clone();
+                        let rt = rt_init.clone();
+                        let tx = tx_init.clone();
+                        rt_init.network(move || {
+                            let inst = match nm.lock().unwrap().as_ref() {
+                                Some(inst) => inst.clone_box(),
+                                None => return,
+                            };
+                            if let Some(ssid) = activate_hidden_networks(inst, &rt) {
+                                let _ = tx.send_blocking(AppEvent::Notify(format!("Connected to {}", ssid)));
+                                let _ = tx.send_blocking(AppEvent::ActiveSsid(Some(ssid)));
+                            }
+                        });
+                    }
+                });
+            }
+            
+            // Follow light/dark switches from the desktop
+            {
+                let rt_scheme = rt.clone();
+                let tx_scheme = tx.clone();
+                std::thread::spawn(move || {
+                    let res = rt_scheme.block_on(async {
+                        let portal = SettingsPortal::new().await?;
+                        portal.watch_color_scheme(|value| {
+                            let _ = tx_scheme.send_blocking(AppEvent::ColorSchemeChanged(ColorScheme::from_portal(value)));
+                        }).await
+                    });
+                    if let Err(e) = res {
+                        log::debug!("Not watching the system color scheme: {}", e);
+                    }
+                });
+            }
+            
+            // Let the daemon own its toggle key on compositors without easy binds
+            if let (true, Some(trigger)) = (is_daemon, win.global_shortcut()) {
+                let rt_keys = rt.clone();
+                let tx_keys = tx.clone();
+                std::thread::spawn(move || {
+                    let res = rt_keys.block_on(async {
+                        let shortcuts = GlobalShortcutsPortal::new().await?;
+                        let session = shortcuts.bind_toggle(&trigger).await?;
+                        log::info!("Bound global shortcut {} through the portal", trigger);
+                        shortcuts.watch_activated(&session, |id| {
+                            if id == portal::TOGGLE_SHORTCUT_ID {
+                                let toggle = DaemonCommand::Toggle(None, None, None, Margins::default());
+                                let _ = tx_keys.send_blocking(AppEvent::DaemonCommand(toggle));
+                            }
+                        }).await
+                    });
+                    if let Err(e) = res {
+                        log::warn!("Global shortcut unavailable: {}", e);
+                    }
+                });
+            }
+            
+            // Without layer-shell there is no bar-friendly popup, so the tray is the way in
+            if is_daemon && (win.tray_icon() || !win.is_layer_shell()) {
+                let rt_tray = rt.clone();
+                let tx_tray = tx.clone();
+                std::thread::spawn(move || {
+                    let res = rt_tray.block_on(async {
+                        let _conn = tray::serve(move || {
+                            let toggle = DaemonCommand::Toggle(None, None, None, Margins::default());
+                            let _ = tx_tray.send_blocking(AppEvent::DaemonCommand(toggle));
+                        }).await?;
+                        log::info!("Tray icon registered");
+                        std::future::pending::<zbus::Result<()>>().await
+                    });
+                    if let Err(e) = res {
+                        log::warn!("Tray icon unavailable: {}", e);
+                    }
                 });
             }
             
@@ -217,9 +345,9 @@ impl OrbitApp {
                 win.show();
             }
             
-            setup_events_receiver(win.clone(), rx.clone(), is_visible.clone(), nm.clone(), bt.clone(), rt.clone(), tx.clone(), win_theme.clone());
-            setup_ui_callbacks(win.clone(), nm.clone(), bt.clone(), rt.clone(), tx.clone(), current_tab.clone());
-            setup_periodic_refresh(win.clone(), nm, bt, rt.clone(), tx.clone(), is_visible.clone(), current_tab.clone());
+            setup_events_receiver(win.clone(), rx.clone(), is_visible.clone(), nm.clone(), bt.clone(), rt.clone(), tx.clone(), win_theme.clone(), connect_retries, connect_cancel.clone());
+            setup_periodic_scan(win.clone(), nm.clone(), rt.clone(), is_visible.clone());
+            setup_ui_callbacks(win.clone(), nm, bt, rt, tx, current_tab, connect_cancel);
         });
         
         self.app.run_with_args(&[] as &[&str])
@@ -230,28 +358,52 @@ fn setup_events_receiver(
     win: OrbitWindow,
     rx: async_channel::Receiver<AppEvent>,
     is_visible: Rc<RefCell<bool>>,
-    nm: Arc<Mutex<Option<NetworkManager>>>,
+    nm: Arc<Mutex<Option<Box<dyn WifiBackend>>>>,
     bt: Arc<Mutex<Option<BluetoothManager>>>,
-    rt: Arc<tokio::runtime::Runtime>,
+    rt: Arc<Runtime>,
     tx: async_channel::Sender<AppEvent>,
     win_theme: Rc<RefCell<Theme>>,
+    connect_retries: Arc<AtomicU32>,
+    connect_cancel: Arc<AtomicBool>,
 ) {
+    let pending_refresh: Rc<RefCell<HashSet<(Refresh, u64)>>> = Rc::new(RefCell::new(HashSet::new()));
+    // Last known active SSID, and whether its next drop was asked for by the user
+    let active_ssid: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+    let drop_expected = Rc::new(RefCell::new(false));
+    // Last known (WiFi, Bluetooth) power, which airplane mode is checked against
+    let radios_on = Rc::new(RefCell::new((false, false)));
     glib::spawn_future_local(async move {
         while let Ok(event) = rx.recv().await {
             match event {
                 AppEvent::WifiScanResult(aps) => {
                     win.network_list().set_networks(aps);
                 }
+                AppEvent::WifiScanFinished => {
+                    win.network_list().finish_scan();
+                }
                 AppEvent::SavedNetworksResult(networks) => {
+                    win.network_list().set_saved_ssids(networks.iter().map(|n| n.ssid.clone()).collect());
                     win.saved_networks_list().set_networks(networks);
                 }
                 AppEvent::NetworkDetailsResult(details) => {
                     win.show_network_details(&details);
                 }
+                AppEvent::ShareNetwork(ssid, psk) => {
+                    win.show_share_qr(&ssid, psk.as_deref());
+                }
+                AppEvent::DeviceStats(rx, tx) => {
+                    win.update_throughput(rx, tx);
+                }
                 AppEvent::BtScanResult(devices) => {
                     win.device_list().set_devices(devices);
                 }
+                AppEvent::BtScanFinished => {
+                    win.device_list().finish_scan();
+                }
                 AppEvent::WifiPowerState(enabled) => {
+                    if !enabled {
+                        *drop_expected.borrow_mut() = true;
+                    }
                     if let Some(tab) = win.stack().visible_child_name() {
                         let tab_str = tab.as_str();
                         if tab_str == "wifi" || tab_str == "saved" {
@@ -259,6 +411,29 @@ fn setup_events_receiver(
                             win.header().set_power_state(enabled);
                         }
                     }
+                    radios_on.borrow_mut().0 = enabled;
+                    let (wifi_on, bt_on) = *radios_on.borrow();
+                    win.header().set_airplane_state(airplane::is_enabled(wifi_on, bt_on));
+                }
+                AppEvent::WifiFeatures(features) => {
+                    win.set_wifi_features(&features);
+                }
+                AppEvent::NetworkManagerUnavailable => {
+                    win.network_list().show_unavailable();
+                    win.saved_networks_list().show_unavailable();
+                    win.header().set_wifi_available(false);
+                }
+                AppEvent::BluetoothInitializing => {
+                    win.device_list().show_initializing();
+                }
+                AppEvent::BluetoothUnavailable => {
+                    win.device_list().show_no_adapter();
+                }
+                AppEvent::BtAdapters(adapters, selected) => {
+                    win.device_list().set_adapters(adapters, selected);
+                }
+                AppEvent::BtDiscoverable(discoverable) => {
+                    win.device_list().set_discoverable(discoverable);
                 }
                 AppEvent::BtPowerState(enabled) => {
                     if let Some(tab) = win.stack().visible_child_name() {
@@ -268,54 +443,144 @@ fn setup_events_receiver(
                             win.header().set_power_state(enabled);
                         }
                     }
+                    radios_on.borrow_mut().1 = enabled;
+                    let (wifi_on, bt_on) = *radios_on.borrow();
+                    win.header().set_airplane_state(airplane::is_enabled(wifi_on, bt_on));
                 }
                 AppEvent::Error(msg) => {
-                    win.network_list().set_connecting_ssid(None);
+                    *drop_expected.borrow_mut() = false;
+                    win.network_list().fail_connect(&sanitize_error_message(&msg));
                     win.network_list().set_disconnecting_ssid(None);
                     win.show_error(&msg);
                 }
                 AppEvent::Notify(msg) => {
-                    std::thread::spawn(move || {
-                        let _ = std::process::Command::new("notify-send")
-                            .arg("Orbit")
-                            .arg(&msg)
-                            .arg("--app-name=Orbit")
-                            .arg("-i")
-                            .arg("network-wireless")
-                            .spawn();
-                    });
+                    send_notification(&win.notify_command(), &msg);
                 }
-                AppEvent::CaptivePortal(ssid) => {
-                    std::thread::spawn(move || {
-                        let _ = std::process::Command::new("notify-send")
-                            .arg("Orbit")
-                            .arg(&format!("Captive portal detected on {} — opening login page...", ssid))
-                            .arg("--app-name=Orbit")
-                            .arg("-i")
-                            .arg("network-wireless")
-                            .spawn();
+                AppEvent::CaptivePortal(ssid, detected_url) => {
+                    let (notify, open_browser) = win.captive_portal_behavior();
+                    let url = win.captive_portal_url()
+                        .or(detected_url)
+                        .unwrap_or_else(|| FALLBACK_PORTAL_URL.to_string());
+                    if !notify {
+                        log::info!("Captive portal on {}, not announcing (captive-portal = off)", ssid);
+                        continue;
+                    }
+                    let message = if open_browser {
+                        format!("Captive portal detected on {} — opening login page...", ssid)
+                    } else {
+                        format!("Captive portal detected on {} — sign in to get online", ssid)
+                    };
+                    send_notification(&win.notify_command(), &message);
+                    if open_browser {
                         let _ = std::process::Command::new("xdg-open")
-                            .arg("http://neverssl.com")
+                            .arg(&url)
                             .spawn();
-                    });
+                    }
+                }
+                AppEvent::NetworkChanged(change) => {
+                    let refreshes: &[(Refresh, u64)] = match change {
+                        NetworkChange::AccessPoints => &[(Refresh::AccessPoints, REFRESH_SOON_MS)],
+                        NetworkChange::SignalStrength => &[(Refresh::AccessPoints, REFRESH_LATER_MS)],
+                        NetworkChange::ActiveConnection => &[
+                            (Refresh::AccessPoints, REFRESH_SOON_MS),
+                            (Refresh::SavedNetworks, REFRESH_SOON_MS),
+                        ],
+                        NetworkChange::SavedConnections => &[(Refresh::SavedNetworks, REFRESH_SOON_MS)],
+                        NetworkChange::Radio => &[(Refresh::WifiPower, REFRESH_SOON_MS)],
+                        NetworkChange::State => &[],
+                    };
+                    if matches!(change, NetworkChange::State | NetworkChange::ActiveConnection | NetworkChange::Radio) {
+                        refresh_network_state(&nm, &rt, &tx);
+                    }
+                    if matches!(change, NetworkChange::ActiveConnection) {
+                        // Checked even while hidden so a dropped link is always reported
+                        let nm_ref = nm.clone();
+                        let rt_ref = rt.clone();
+                        let tx_ref = tx.clone();
+                        rt.network(move || {
+                            if let Some(ref nm_inst) = *nm_ref.lock().unwrap() {
+                                let ssid = rt_ref.block_on(async { nm_inst.get_active_ssid().await });
+                                let _ = tx_ref.send_blocking(AppEvent::ActiveSsid(ssid));
+                            }
+                        });
+                    }
+                    for &(kind, delay) in refreshes {
+                        schedule_refresh(kind, delay, &pending_refresh, &win, &is_visible, &nm, &bt, &rt, &tx);
+                    }
+                }
+                AppEvent::BluetoothChanged(change) => {
+                    let (kind, delay) = match change {
+                        BluetoothChange::Devices => (Refresh::Devices, REFRESH_SOON_MS),
+                        BluetoothChange::SignalStrength => (Refresh::Devices, REFRESH_LATER_MS),
+                        BluetoothChange::Adapter => (Refresh::BtAdapter, REFRESH_SOON_MS),
+                    };
+                    schedule_refresh(kind, delay, &pending_refresh, &win, &is_visible, &nm, &bt, &rt, &tx);
+                }
+                AppEvent::ColorSchemeChanged(color_scheme) => {
+                    if win_theme.borrow().color_scheme != color_scheme {
+                        log::info!("System color scheme changed to {:?}", color_scheme);
+                        let new_theme = Theme::load(color_scheme);
+                        *win_theme.borrow_mut() = new_theme;
+                        win.apply_theme();
+                    }
                 }
                 AppEvent::ConnectStarted(ssid) => {
+                    // A cancel that arrived after the previous connect finished must not abort this one
+                    connect_cancel.store(false, Ordering::SeqCst);
+                    if active_ssid.borrow().as_deref().is_some_and(|active| active != ssid) {
+                        *drop_expected.borrow_mut() = true;
+                    }
                     win.network_list().set_connecting_ssid(Some(ssid));
                 }
                 AppEvent::DisconnectStarted(ssid) => {
+                    *drop_expected.borrow_mut() = true;
                     win.network_list().set_disconnecting_ssid(Some(ssid));
                 }
+                AppEvent::NetworkState(state, connectivity) => {
+                    win.header().set_network_state(network_manager::state_label(state, connectivity));
+                    win.network_list().set_connectivity(connectivity);
+                }
+                AppEvent::ActiveSsid(ssid) => {
+                    let previous = active_ssid.replace(ssid.clone());
+                    if previous != ssid {
+                        let expected = drop_expected.replace(false);
+                        match (previous, ssid) {
+                            (Some(previous), None) if !expected => {
+                                let _ = tx.send_blocking(AppEvent::Notify(format!("Disconnected from {}", previous)));
+                            }
+                            (_, Some(ssid)) if win.captive_portal_behavior().0 => {
+                                check_captive_portal(&nm, &rt, &tx, ssid);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
                 AppEvent::ConnectSuccess => {
                     win.network_list().set_connecting_ssid(None);
                     win.network_list().set_disconnecting_ssid(None);
                     win.hide_password_dialog();
                 }
+                AppEvent::WrongPassword(ssid) => {
+                    win.network_list().set_connecting_ssid(None);
+                    win.show_wrong_password(&ssid);
+                }
+                AppEvent::ConnectCancelled => {
+                    win.network_list().set_connecting_ssid(None);
+                    win.hide_password_dialog();
+                }
+                AppEvent::PasswordChanged(ssid) => {
+                    win.hide_password_dialog();
+                    send_notification(&win.notify_command(), &format!("Updated the password for {}", ssid));
+                }
+                AppEvent::PasswordChangeFailed(message) => {
+                    win.show_password_error(&message);
+                }
                 AppEvent::ConnectHidden(ssid, password) => {
                     let nm_ref = nm.clone();
                     let rt_ref = rt.clone();
                     let tx_ref = tx.clone();
                     
-                    std::thread::spawn(move || {
+                    rt.network(move || {
                         let nm_guard = nm_ref.lock().unwrap();
                         if let Some(ref nm_inst) = *nm_guard {
                             let rt_inner = rt_ref.clone();
@@ -350,6 +615,21 @@ fn setup_events_receiver(
                 AppEvent::BtActionComplete => {
                     win.device_list().set_action_state(None, None);
                 }
+                AppEvent::BtAudioProfiles(path, profiles) => {
+                    win.device_list().set_audio_profiles(&path, profiles);
+                }
+                AppEvent::BtTransferProgress(file_name, transferred, size) => {
+                    win.show_transfer_progress(&file_name, transferred, size);
+                }
+                AppEvent::BtTransferFinished => {
+                    win.hide_transfer_progress();
+                }
+                AppEvent::WpsFinished => {
+                    win.hide_wps_prompt();
+                }
+                AppEvent::EditIpv4(path, ssid, current) => {
+                    show_ipv4_editor(&win, &nm, &rt, &tx, path, ssid, &current);
+                }
                 AppEvent::DaemonCommand(cmd) => {
                     match cmd {
                         DaemonCommand::Show => {
@@ -359,7 +639,7 @@ fn setup_events_receiver(
                             let bt_ref = bt.clone();
                             let rt_ref = rt.clone();
                             let tx_ref = tx.clone();
-                            std::thread::spawn(move || {
+                            rt.network(move || {
                                 let nm_guard = nm_ref.lock().unwrap();
                                 if let Some(ref nm_inst) = *nm_guard {
                                     if let Ok(enabled) = rt_ref.block_on(async { nm_inst.is_wifi_enabled().await }) {
@@ -384,12 +664,28 @@ fn setup_events_receiver(
                             win.hide();
                             *is_visible.borrow_mut() = false;
                         }
-                        DaemonCommand::Toggle(position) => {
-                            if *is_visible.borrow() {
+                        DaemonCommand::Toggle(position, monitor, tab, margins) => {
+                            let switches_tab = tab.as_deref()
+                                .is_some_and(|t| win.stack().visible_child_name().as_deref() != Some(t));
+                            if *is_visible.borrow() && switches_tab {
+                                // Open on another tab: jump there instead of closing
+                                win.open_tab(tab.as_deref().unwrap_or_default());
+                            } else if *is_visible.borrow() {
                                 win.hide();
                                 *is_visible.borrow_mut() = false;
                             } else {
-                                if let Some(pos) = position {
+                                if let Some(t) = tab {
+                                    if !win.open_tab(&t) {
+                                        log::warn!("Unknown tab '{}'", t);
+                                    }
+                                }
+                                if let Some(m) = monitor {
+                                    win.set_monitor_name(&m);
+                                }
+                                if !margins.is_empty() {
+                                    win.set_margins(&margins);
+                                }
+                                if let Some(pos) = position.or_else(|| win.default_position()) {
                                     win.set_position(&pos);
                                 }
                                 win.show();
@@ -398,7 +694,7 @@ fn setup_events_receiver(
                                 let bt_ref = bt.clone();
                                 let rt_ref = rt.clone();
                                 let tx_ref = tx.clone();
-                                std::thread::spawn(move || {
+                                rt.network(move || {
                                     let nm_guard = nm_ref.lock().unwrap();
                                     if let Some(ref nm_inst) = *nm_guard {
                                         if let Ok(enabled) = rt_ref.block_on(async { nm_inst.is_wifi_enabled().await }) {
@@ -421,13 +717,24 @@ fn setup_events_receiver(
                             }
                         }
                         DaemonCommand::ReloadTheme => {
-                            let new_theme = Theme::load();
+                            let color_scheme = win_theme.borrow().color_scheme;
+                            let new_theme = Theme::load(color_scheme);
                             *win_theme.borrow_mut() = new_theme;
                             win.apply_theme();
                         }
                         DaemonCommand::ReloadConfig => {
                             win.reload_config();
+                            let nm_ref = nm.clone();
+                            let retries = win.connect_retries();
+                            connect_retries.store(retries, Ordering::SeqCst);
+                            // May wait on an in-flight connect, so keep it off the UI thread
+                            rt.network(move || {
+                                if let Some(ref mut nm_inst) = *nm_ref.lock().unwrap() {
+                                    nm_inst.set_connect_retries(retries);
+                                }
+                            });
                         }
+                        DaemonCommand::Status => {}
                         DaemonCommand::Quit => {
                             std::process::exit(0);
                         }
@@ -435,8 +742,19 @@ fn setup_events_receiver(
                 }
                 AppEvent::DaemonStarted(server) => {
                     let tx_cmd = tx.clone();
-                    server.run(move |cmd| {
-                        let _ = tx_cmd.send_blocking(AppEvent::DaemonCommand(cmd));
+                    let nm_status = nm.clone();
+                    let bt_status = bt.clone();
+                    let rt_status = rt.clone();
+                    let last_status = Mutex::new(DaemonStatus::default());
+                    server.run(
+                        move |cmd| {
+                            let _ = tx_cmd.send_blocking(AppEvent::DaemonCommand(cmd));
+                        },
+                        move || daemon_status_json(&nm_status, &bt_status, &rt_status, &last_status),
+                    );
+                    let tx_watch = tx.clone();
+                    watcher::watch_config_files(move |cmd| {
+                        let _ = tx_watch.send_blocking(AppEvent::DaemonCommand(cmd));
                     });
                 }
             }
@@ -444,13 +762,352 @@ fn setup_events_receiver(
     });
 }
 
+/// Reads the desktop color scheme from the settings portal, giving up quickly when no
+/// portal is running.
+async fn system_color_scheme() -> ColorScheme {
+    let read = async {
+        let portal = SettingsPortal::new().await?;
+        portal.color_scheme().await
+    };
+    match tokio::time::timeout(std::time::Duration::from_millis(500), read).await {
+        Ok(Ok(value)) => ColorScheme::from_portal(value),
+        Ok(Err(e)) => {
+            log::debug!("Settings portal unavailable: {}", e);
+            ColorScheme::default()
+        }
+        Err(_) => {
+            log::debug!("Settings portal did not answer in time");
+            ColorScheme::default()
+        }
+    }
+}
+
+/// Connects to the WiFi daemon, retrying for a few seconds in case it is still starting.
+fn connect_wifi_backend(rt: &Runtime, connect_retries: u32, connect_cancel: &Arc<AtomicBool>) -> Option<Box<dyn WifiBackend>> {
+    for i in 0..5 {
+        if let Ok(mut inst) = rt.block_on(async { crate::dbus::wifi::connect().await }) {
+            inst.set_connect_retries(connect_retries);
+            inst.set_cancel_flag(connect_cancel.clone());
+            return Some(inst);
+        }
+        if i < 4 {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+    }
+    None
+}
+
+/// Gives BlueZ a few seconds to come up. With `tx`, the device list is told that
+/// Bluetooth is still initializing once the first attempt fails.
+fn connect_bluetooth(rt: &Runtime, tx: Option<&async_channel::Sender<AppEvent>>) -> Option<BluetoothManager> {
+    for i in 0..5 {
+        if let Ok(inst) = rt.block_on(async { BluetoothManager::new().await }) {
+            return Some(inst);
+        }
+        if let (0, Some(tx)) = (i, tx) {
+            let _ = tx.send_blocking(AppEvent::BluetoothInitializing);
+        }
+        if i < 4 {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+    }
+    None
+}
+
+/// Publishes NetworkManager's global state for the header. Cheap enough to run while the
+/// panel is hidden, so the line is current as soon as it opens.
+fn refresh_network_state(
+    nm: &Arc<Mutex<Option<Box<dyn WifiBackend>>>>,
+    rt: &Arc<Runtime>,
+    tx: &async_channel::Sender<AppEvent>,
+) {
+    let nm = nm.clone();
+    let rt_job = rt.clone();
+    let tx = tx.clone();
+    rt.network(move || {
+        if let Some(ref nm_inst) = *nm.lock().unwrap() {
+            if let Ok((state, connectivity)) = rt_job.block_on(async { nm_inst.network_state().await }) {
+                let _ = tx.send_blocking(AppEvent::NetworkState(state, connectivity));
+            }
+        }
+    });
+}
+
+/// How long a new connection gets to reach portal or full connectivity.
+const PORTAL_WAIT_SECS: u64 = 20;
+
+/// Reports a captive portal on `ssid` once the new connection's connectivity has settled.
+/// Runs on its own thread so waiting for DHCP doesn't hold up the network queue.
+fn check_captive_portal(
+    nm: &Arc<Mutex<Option<Box<dyn WifiBackend>>>>,
+    rt: &Arc<Runtime>,
+    tx: &async_channel::Sender<AppEvent>,
+    ssid: String,
+) {
+    let nm = nm.clone();
+    let rt = rt.clone();
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let inst = match nm.lock().unwrap().as_ref() {
+            Some(inst) => inst.clone_box(),
+            None => return,
+        };
+        let connectivity = rt.block_on(async { inst.wait_for_connectivity(PORTAL_WAIT_SECS).await });
+        if matches!(connectivity, Ok(CONNECTIVITY_PORTAL)) {
+            let url = rt.block_on(async { inst.captive_portal_url().await });
+            let _ = tx.send_blocking(AppEvent::CaptivePortal(ssid, url));
+        }
+    });
+}
+
+/// Shows a desktop notification through `notify-command`. The command gets the title and
+/// message as its last two arguments; `notify-send` also gets Orbit's app name and icon.
+/// An empty command turns notifications off.
+fn send_notification(command: &str, message: &str) {
+    let mut parts = command.split_whitespace();
+    let program = match parts.next() {
+        Some(program) => program,
+        None => return,
+    };
+    let mut cmd = std::process::Command::new(program);
+    cmd.args(parts);
+    if std::path::Path::new(program).file_name().is_some_and(|name| name == "notify-send") {
+        cmd.arg("--app-name=Orbit").arg("-i").arg("network-wireless");
+    }
+    if let Err(e) = cmd.arg("Orbit").arg(message).spawn() {
+        log::warn!("Could not run notify command '{}': {}", command, e);
+    }
+}
+
+/// Tries saved hidden networks one by one, highest autoconnect priority first, and returns
+/// the SSID that came up. Hidden SSIDs never show up in a scan, so NetworkManager's own
+/// autoconnect often skips them. Each gets a single attempt, and a timeout ends the search:
+/// it usually means none of them are in range.
+fn activate_hidden_networks(mut nm: Box<dyn WifiBackend>, rt: &Runtime) -> Option<String> {
+    nm.set_connect_retries(0);
+    let mut hidden: Vec<SavedNetwork> = match rt.block_on(async { nm.get_saved_networks().await }) {
+        Ok(saved) => saved.into_iter().filter(|n| n.is_hidden && n.autoconnect && !n.is_active).collect(),
+        Err(_) => return None,
+    };
+    if hidden.is_empty() {
+        return None;
+    }
+    hidden.sort_by(|a, b| b.priority.cmp(&a.priority));
+    let device = rt.block_on(async { nm.get_wireless_devices().await }).ok()?.into_iter().next()?;
+    for network in hidden {
+        log::info!("Trying saved hidden network {}", network.ssid);
+        match rt.block_on(async { nm.connect_to_network(&network.ssid, None, &device, true).await }) {
+            Ok(()) => return Some(network.ssid),
+            Err(ConnectError::Timeout) => {
+                log::info!("Hidden network {} timed out, not trying the rest", network.ssid);
+                break;
+            }
+            Err(e) => log::info!("Hidden network {} did not come up: {}", network.ssid, e),
+        }
+    }
+    None
+}
+
+/// Pause before re-establishing a connection whose signal watch ended.
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Forwards WiFi signals from `watcher`. A watch only ends when the bus connection is gone
+/// (e.g. the system bus restarted), so the backend is then reconnected and the shared
+/// instance swapped out; otherwise every later call would fail on the dead connection.
+fn supervise_wifi(
+    mut watcher: Box<dyn WifiBackend>,
+    nm: Arc<Mutex<Option<Box<dyn WifiBackend>>>>,
+    rt: Arc<Runtime>,
+    tx: async_channel::Sender<AppEvent>,
+    connect_retries: Arc<AtomicU32>,
+    connect_cancel: Arc<AtomicBool>,
+) {
+    std::thread::spawn(move || loop {
+        let tx_watch = tx.clone();
+        let res = rt.block_on(watcher.watch_changes(Box::new(move |change| {
+            let _ = tx_watch.send_blocking(AppEvent::NetworkChanged(change));
+        })));
+        log::warn!("Stopped watching {} signals: {:?}", watcher.name(), res);
+        watcher = loop {
+            std::thread::sleep(RECONNECT_DELAY);
+            if let Some(inst) = connect_wifi_backend(&rt, connect_retries.load(Ordering::SeqCst), &connect_cancel) {
+                break inst;
+            }
+        };
+        log::info!("Reconnected to {}", watcher.name());
+        *nm.lock().unwrap() = Some(watcher.clone_box());
+        let _ = tx.send_blocking(AppEvent::WifiFeatures(watcher.features()));
+        let _ = tx.send_blocking(AppEvent::NetworkChanged(NetworkChange::Radio));
+        let _ = tx.send_blocking(AppEvent::NetworkChanged(NetworkChange::ActiveConnection));
+    });
+}
+
+/// Bluetooth counterpart of `supervise_wifi`.
+fn supervise_bluetooth(
+    mut watcher: BluetoothManager,
+    bt: Arc<Mutex<Option<BluetoothManager>>>,
+    rt: Arc<Runtime>,
+    tx: async_channel::Sender<AppEvent>,
+) {
+    std::thread::spawn(move || loop {
+        let tx_watch = tx.clone();
+        let res = rt.block_on(watcher.watch_changes(move |change| {
+            let _ = tx_watch.send_blocking(AppEvent::BluetoothChanged(change));
+        }));
+        log::warn!("Stopped watching BlueZ signals: {:?}", res);
+        watcher = loop {
+            std::thread::sleep(RECONNECT_DELAY);
+            if let Some(inst) = connect_bluetooth(&rt, None) {
+                break inst;
+            }
+        };
+        log::info!("Reconnected to BlueZ");
+        *bt.lock().unwrap() = Some(watcher.clone());
+        let _ = tx.send_blocking(AppEvent::BluetoothChanged(BluetoothChange::Adapter));
+        let _ = tx.send_blocking(AppEvent::BluetoothChanged(BluetoothChange::Devices));
+    });
+}
+
+/// Ends a Bluetooth scan and publishes the final device list.
+fn stop_bt_discovery(
+    bt: &Arc<Mutex<Option<BluetoothManager>>>,
+    rt: &Arc<Runtime>,
+    tx: &async_channel::Sender<AppEvent>,
+) {
+    let bt = bt.clone();
+    let rt_job = rt.clone();
+    let tx = tx.clone();
+    rt.bluetooth(move || {
+        let bt_guard = bt.lock().unwrap();
+        if let Some(ref bt_inst) = *bt_guard {
+            let _ = rt_job.block_on(async { bt_inst.stop_discovery().await });
+            if let Ok(devices) = rt_job.block_on(async { bt_inst.get_devices().await }) {
+                let _ = tx.send_blocking(AppEvent::BtScanResult(devices));
+            }
+        }
+        let _ = tx.send_blocking(AppEvent::BtScanFinished);
+    });
+}
+
+/// Opens the IPv4 dialog for the profile at `path` and saves what the user enters.
+fn show_ipv4_editor(
+    win: &OrbitWindow,
+    nm: &Arc<Mutex<Option<Box<dyn WifiBackend>>>>,
+    rt: &Arc<Runtime>,
+    tx: &async_channel::Sender<AppEvent>,
+    path: String,
+    ssid: String,
+    current: &Ipv4Method,
+) {
+    let nm = nm.clone();
+    let rt = rt.clone();
+    let tx = tx.clone();
+    win.show_ipv4_dialog(&ssid.clone(), current, move |method: Ipv4Method| {
+        let nm = nm.clone();
+        let rt_job = rt.clone();
+        let tx = tx.clone();
+        let path = path.clone();
+        let ssid = ssid.clone();
+        rt.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                let res = match method {
+                    Ipv4Method::Auto => rt_job.block_on(async { nm_inst.set_ipv4_auto(&path).await }),
+                    Ipv4Method::Manual { address, prefix, gateway, dns } => {
+                        rt_job.block_on(async { nm_inst.set_ipv4_manual(&path, &address, prefix, &gateway, dns).await })
+                    }
+                };
+                match res {
+                    Ok(()) => {
+                        let _ = tx.send_blocking(AppEvent::Notify(format!("IPv4 settings saved for {}. Reconnect to apply.", ssid)));
+                    }
+                    Err(e) => {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Failed to update IPv4 settings: {}", e)));
+                    }
+                }
+            }
+        });
+    });
+}
+
+/// Looks up the sound server profiles of the audio device at `path` for its fold-out.
+fn send_audio_profiles(bt: &BluetoothManager, path: &str, rt: &Runtime, tx: &async_channel::Sender<AppEvent>) {
+    let profiles = match rt.block_on(async { bt.get_audio_profiles(path).await }) {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            log::warn!("Failed to read audio profiles of {}: {}", path, e);
+            CardProfiles::default()
+        }
+    };
+    let _ = tx.send_blocking(AppEvent::BtAudioProfiles(path.to_string(), profiles));
+}
+
+/// Rejected passwords get their own event so the password dialog can say so directly.
+fn connect_failed_event(ssid: &str, error: ConnectError) -> AppEvent {
+    match error {
+        ConnectError::WrongPassword => AppEvent::WrongPassword(ssid.to_string()),
+        ConnectError::Cancelled => AppEvent::ConnectCancelled,
+        e => AppEvent::Error(format!("Connect failed: {}", e)),
+    }
+}
+
+/// Last values reported by `orbit status`, answered again while a backend is busy.
+#[derive(Default)]
+struct DaemonStatus {
+    wifi_enabled: bool,
+    active: Option<(String, u8)>,
+    bt_powered: bool,
+    bt_connected: usize,
+}
+
+/// Builds the JSON reply for the daemon `status` command from the live D-Bus connections.
+/// A backend that is locked by a running job (e.g. a connect that can take 15s) is not
+/// waited for; its part of `last` is reported instead.
+fn daemon_status_json(
+    nm: &Arc<Mutex<Option<Box<dyn WifiBackend>>>>,
+    bt: &Arc<Mutex<Option<BluetoothManager>>>,
+    rt: &Runtime,
+    last: &Mutex<DaemonStatus>,
+) -> String {
+    let mut status = last.lock().unwrap();
+    if let Ok(guard) = nm.try_lock() {
+        if let Some(ref nm_inst) = *guard {
+            status.wifi_enabled = rt.block_on(async { nm_inst.is_wifi_enabled().await }).unwrap_or(false);
+            if let Ok(aps) = rt.block_on(async { nm_inst.get_access_points().await }) {
+                status.active = aps.into_iter()
+                    .find(|ap| ap.is_connected)
+                    .map(|ap| (ap.ssid, ap.signal_strength));
+            }
+        }
+    }
+    
+    if let Ok(guard) = bt.try_lock() {
+        if let Some(ref bt_inst) = *guard {
+            status.bt_powered = rt.block_on(async { bt_inst.is_powered().await }).unwrap_or(false);
+            if let Ok(devices) = rt.block_on(async { bt_inst.get_devices().await }) {
+                status.bt_connected = devices.iter().filter(|d| d.is_connected).count();
+            }
+        }
+    }
+    
+    let (ssid, signal) = match status.active {
+        Some((ref ssid, signal)) => (format!("\"{}\"", daemon::json_escape(ssid)), signal.to_string()),
+        None => ("null".to_string(), "null".to_string()),
+    };
+    format!(
+        r#"{{"wifi_enabled": {}, "ssid": {}, "signal": {}, "bt_powered": {}, "bt_connected": {}}}"#,
+        status.wifi_enabled, ssid, signal, status.bt_powered, status.bt_connected
+    )
+}
+
 fn setup_ui_callbacks(
     win: OrbitWindow,
-    nm: Arc<Mutex<Option<NetworkManager>>>,
+    nm: Arc<Mutex<Option<Box<dyn WifiBackend>>>>,
     bt: Arc<Mutex<Option<BluetoothManager>>>,
-    rt: Arc<tokio::runtime::Runtime>,
+    rt: Arc<Runtime>,
     tx: async_channel::Sender<AppEvent>,
     current_tab: Rc<RefCell<String>>,
+    connect_cancel: Arc<AtomicBool>,
 ) {
     let header = win.header().clone();
     let stack = win.stack().clone();
@@ -469,7 +1126,7 @@ fn setup_ui_callbacks(
         let nm = nm_wifi.clone();
         let rt = rt_wifi.clone();
         let tx = tx_wifi.clone();
-        std::thread::spawn(move || {
+        rt_wifi.network(move || {
             let nm_guard = nm.lock().unwrap();
             if let Some(ref nm_inst) = *nm_guard {
                 if let Ok(enabled) = rt.block_on(async { nm_inst.is_wifi_enabled().await }) {
@@ -492,7 +1149,7 @@ fn setup_ui_callbacks(
         let nm = nm_saved.clone();
         let rt = rt_saved.clone();
         let tx = tx_saved.clone();
-        std::thread::spawn(move || {
+        rt_saved.network(move || {
             let nm_guard = nm.lock().unwrap();
             if let Some(ref nm_inst) = *nm_guard {
                 if let Ok(saved) = rt.block_on(async { nm_inst.get_saved_networks().await }) {
@@ -515,12 +1172,104 @@ fn setup_ui_callbacks(
         let bt = bt_tab.clone();
         let rt = rt_bt_tab.clone();
         let tx = tx_bt_tab.clone();
-        std::thread::spawn(move || {
+        rt_bt_tab.bluetooth(move || {
             let bt_guard = bt.lock().unwrap();
             if let Some(ref bt_inst) = *bt_guard {
+                if let Ok(adapters) = rt.block_on(async { bt_inst.list_adapters().await }) {
+                    let _ = tx.send_blocking(AppEvent::BtAdapters(adapters, bt_inst.selected_adapter()));
+                }
                 if let Ok(enabled) = rt.block_on(async { bt_inst.is_powered().await }) {
                     let _ = tx.send_blocking(AppEvent::BtPowerState(enabled));
                 }
+                if let Ok(discoverable) = rt.block_on(async { bt_inst.is_discoverable().await }) {
+                    let _ = tx.send_blocking(AppEvent::BtDiscoverable(discoverable));
+                }
+            }
+        });
+    });
+    
+    let bt_disc = bt.clone();
+    let rt_disc = rt.clone();
+    let tx_disc = tx.clone();
+    let win_disc = win.clone();
+    win.device_list().set_on_discoverable_toggle(move |discoverable: bool| {
+        if win_disc.is_readonly() {
+            log::warn!("Readonly: Reverting discoverable toggle");
+            win_disc.device_list().set_discoverable(!discoverable);
+            return;
+        }
+        let timeout = win_disc.discoverable_timeout();
+        let bt = bt_disc.clone();
+        let rt = rt_disc.clone();
+        let tx = tx_disc.clone();
+        rt_disc.bluetooth(move || {
+            let bt_guard = bt.lock().unwrap();
+            if let Some(ref bt_inst) = *bt_guard {
+                let res = rt.block_on(async {
+                    if discoverable {
+                        bt_inst.set_discoverable_timeout(timeout).await?;
+                        bt_inst.set_pairable(true).await?;
+                    }
+                    bt_inst.set_discoverable(discoverable).await
+                });
+                if let Err(e) = res {
+                    let _ = tx.send_blocking(AppEvent::Error(format!("Failed to change discoverable mode: {}", e)));
+                }
+                if let Ok(current) = rt.block_on(async { bt_inst.is_discoverable().await }) {
+                    let _ = tx.send_blocking(AppEvent::BtDiscoverable(current));
+                }
+            }
+        });
+    });
+    
+    let bt_alias = bt.clone();
+    let rt_alias = rt.clone();
+    let tx_alias = tx.clone();
+    let win_alias = win.clone();
+    win.device_list().set_on_adapter_renamed(move |path: String, alias: String| {
+        if win_alias.is_readonly() {
+            log::warn!("Readonly: Ignoring adapter rename");
+            return;
+        }
+        let bt = bt_alias.clone();
+        let rt = rt_alias.clone();
+        let tx = tx_alias.clone();
+        rt_alias.bluetooth(move || {
+            let bt_guard = bt.lock().unwrap();
+            if let Some(ref bt_inst) = *bt_guard {
+                if let Err(e) = rt.block_on(async { bt_inst.set_adapter_alias(&path, &alias).await }) {
+                    let _ = tx.send_blocking(AppEvent::Error(format!("Renaming adapter failed: {}", e)));
+                }
+                if let Ok(adapters) = rt.block_on(async { bt_inst.list_adapters().await }) {
+                    let _ = tx.send_blocking(AppEvent::BtAdapters(adapters, bt_inst.selected_adapter()));
+                }
+            }
+        });
+    });
+    
+    let bt_adapter = bt.clone();
+    let rt_adapter = rt.clone();
+    let tx_adapter = tx.clone();
+    win.device_list().set_on_adapter_selected(move |path: String| {
+        let bt = bt_adapter.clone();
+        let rt = rt_adapter.clone();
+        let tx = tx_adapter.clone();
+        rt_adapter.bluetooth(move || {
+            let mut bt_guard = bt.lock().unwrap();
+            if let Some(ref mut bt_inst) = *bt_guard {
+                if let Err(e) = rt.block_on(async { bt_inst.select_adapter(&path).await }) {
+                    let _ = tx.send_blocking(AppEvent::Error(format!("Failed to switch adapter: {}", e)));
+                    return;
+                }
+                if let Ok(powered) = rt.block_on(async { bt_inst.is_powered().await }) {
+                    let _ = tx.send_blocking(AppEvent::BtPowerState(powered));
+                }
+                if let Ok(discoverable) = rt.block_on(async { bt_inst.is_discoverable().await }) {
+                    let _ = tx.send_blocking(AppEvent::BtDiscoverable(discoverable));
+                }
+                if let Ok(devices) = rt.block_on(async { bt_inst.get_devices().await }) {
+                    let _ = tx.send_blocking(AppEvent::BtScanResult(devices));
+                }
             }
         });
     });
@@ -529,11 +1278,16 @@ fn setup_ui_callbacks(
     let nm_scan = nm.clone();
     let rt_scan = rt.clone();
     let tx_scan = tx.clone();
+    let net_list = win.network_list().clone();
     win.network_list().scan_button().connect_clicked(move |_| {
+        // NM rate-limits RequestScan, so overlapping clicks only race each other
+        if !net_list.begin_scan() {
+            return;
+        }
         let nm = nm_scan.clone();
         let rt = rt_scan.clone();
         let tx = tx_scan.clone();
-        std::thread::spawn(move || {
+        rt_scan.network(move || {
             let nm_guard = nm.lock().unwrap();
             if let Some(ref nm_inst) = *nm_guard {
                 let _ = rt.block_on(async { nm_inst.scan().await });
@@ -542,17 +1296,23 @@ fn setup_ui_callbacks(
                     let _ = tx.send_blocking(AppEvent::WifiScanResult(aps));
                 }
             }
+            let _ = tx.send_blocking(AppEvent::WifiScanFinished);
         });
     });
 
     let nm_auto = nm.clone();
     let rt_auto = rt.clone();
     let tx_auto = tx.clone();
+    let win_auto = win.clone();
     win.saved_networks_list().set_on_autoconnect_toggle(move |path: String, enabled: bool| {
+        if win_auto.is_readonly() {
+            log::warn!("Readonly: Ignoring autoconnect toggle for {}", path);
+            return;
+        }
         let nm = nm_auto.clone();
         let rt = rt_auto.clone();
         let tx = tx_auto.clone();
-        std::thread::spawn(move || {
+        rt_auto.network(move || {
             let nm_guard = nm.lock().unwrap();
             if let Some(ref nm_inst) = *nm_guard {
                 match rt.block_on(async { nm_inst.set_autoconnect(&path, enabled).await }) {
@@ -578,40 +1338,281 @@ fn setup_ui_callbacks(
         });
     });
 
-    let nm_forget = nm.clone();
-    let rt_forget = rt.clone();
-    let tx_forget = tx.clone();
-    win.saved_networks_list().set_on_forget(move |path: String| {
-        let nm = nm_forget.clone();
-        let rt = rt_forget.clone();
-        let tx = tx_forget.clone();
-        std::thread::spawn(move || {
+    let nm_metered = nm.clone();
+    let rt_metered = rt.clone();
+    let tx_metered = tx.clone();
+    let win_metered = win.clone();
+    win.saved_networks_list().set_on_metered_toggle(move |path: String, metered: bool| {
+        if win_metered.is_readonly() {
+            log::warn!("Readonly: Ignoring metered toggle for {}", path);
+            return;
+        }
+        let nm = nm_metered.clone();
+        let rt = rt_metered.clone();
+        let tx = tx_metered.clone();
+        rt_metered.network(move || {
             let nm_guard = nm.lock().unwrap();
             if let Some(ref nm_inst) = *nm_guard {
-                match rt.block_on(async { nm_inst.forget_network(&path).await }) {
-                    Ok(()) => {
-                        let _ = tx.send_blocking(AppEvent::Notify("Network forgotten".to_string()));
-                        if let Ok(saved) = rt.block_on(async { nm_inst.get_saved_networks().await }) {
-                            let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
-                        }
-                    }
-                    Err(e) => {
-                        let _ = tx.send_blocking(AppEvent::Error(format!("Forget failed: {}", e)));
+                if let Err(e) = rt.block_on(async { nm_inst.set_metered(&path, metered).await }) {
+                    let _ = tx.send_blocking(AppEvent::Error(format!("Failed to update metered setting: {}", e)));
+                    if let Ok(saved) = rt.block_on(async { nm_inst.get_saved_networks().await }) {
+                        let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
                     }
                 }
             }
         });
     });
-    
-    let nm_conn = nm.clone();
-    let rt_conn = rt.clone();
-    let tx_conn = tx.clone();
-    let win_conn_hidden = win.clone();
-    let tx_conn_hidden = tx.clone();
-    win.network_list().set_on_connect_hidden(move || {
-        let tx = tx_conn_hidden.clone();
-        win_conn_hidden.show_hidden_dialog(move |data| {
-            if let Some((ssid, password)) = data {
+
+    let nm_priority = nm.clone();
+    let rt_priority = rt.clone();
+    let tx_priority = tx.clone();
+    let win_priority = win.clone();
+    win.saved_networks_list().set_on_priority_change(move |changes: Vec<(String, i32)>| {
+        if win_priority.is_readonly() {
+            log::warn!("Readonly: Ignoring priority change for {} networks", changes.len());
+            return;
+        }
+        let nm = nm_priority.clone();
+        let rt = rt_priority.clone();
+        let tx = tx_priority.clone();
+        rt_priority.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                for (path, priority) in changes {
+                    if let Err(e) = rt.block_on(async { nm_inst.set_autoconnect_priority(&path, priority).await }) {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Failed to update priority: {}", e)));
+                        break;
+                    }
+                }
+                if let Ok(saved) = rt.block_on(async { nm_inst.get_saved_networks().await }) {
+                    let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
+                }
+            }
+        });
+    });
+
+    let nm_mac = nm.clone();
+    let rt_mac = rt.clone();
+    let tx_mac = tx.clone();
+    let win_mac = win.clone();
+    win.saved_networks_list().set_on_mac_randomization(move |path: String, mode: MacRandomMode| {
+        if win_mac.is_readonly() {
+            log::warn!("Readonly: Ignoring MAC randomization change for {}", path);
+            return;
+        }
+        let nm = nm_mac.clone();
+        let rt = rt_mac.clone();
+        let tx = tx_mac.clone();
+        rt_mac.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                match rt.block_on(async { nm_inst.set_mac_randomization(&path, mode).await }) {
+                    Ok(()) => {
+                        let _ = tx.send_blocking(AppEvent::Notify(format!("{} will apply on next connect", mode.label())));
+                    }
+                    Err(e) => {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Failed to update MAC randomization: {}", e)));
+                        if let Ok(saved) = rt.block_on(async { nm_inst.get_saved_networks().await }) {
+                            let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
+                        }
+                    }
+                }
+            }
+        });
+    });
+
+    let nm_add = nm.clone();
+    let rt_add = rt.clone();
+    let tx_add = tx.clone();
+    let win_add = win.clone();
+    win.saved_networks_list().set_on_add_network(move || {
+        if win_add.is_readonly() {
+            log::warn!("Readonly: Ignoring add network");
+            return;
+        }
+        let nm = nm_add.clone();
+        let rt = rt_add.clone();
+        let tx = tx_add.clone();
+        win_add.show_add_network_dialog(move |network| {
+            let nm = nm.clone();
+            let rt_job = rt.clone();
+            let tx = tx.clone();
+            rt.network(move || {
+                let nm_guard = nm.lock().unwrap();
+                if let Some(ref nm_inst) = *nm_guard {
+                    match rt_job.block_on(async { nm_inst.import_network(&network).await }) {
+                        Ok(true) => {
+                            let _ = tx.send_blocking(AppEvent::Notify(format!("Saved {}", network.ssid)));
+                            if let Ok(saved) = rt_job.block_on(async { nm_inst.get_saved_networks().await }) {
+                                let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
+                            }
+                        }
+                        Ok(false) => {
+                            let _ = tx.send_blocking(AppEvent::Error(format!("{} is already saved", network.ssid)));
+                        }
+                        Err(e) => {
+                            let _ = tx.send_blocking(AppEvent::Error(format!("Saving {} failed: {}", network.ssid, e)));
+                        }
+                    }
+                }
+            });
+        });
+    });
+
+    let nm_forget = nm.clone();
+    let rt_forget = rt.clone();
+    let tx_forget = tx.clone();
+    let win_forget = win.clone();
+    win.saved_networks_list().set_on_forget(move |path: String| {
+        if win_forget.is_readonly() {
+            log::warn!("Readonly: Ignoring forget for {}", path);
+            return;
+        }
+        let active_ssid = win_forget.saved_networks_list().network(&path)
+            .filter(|n| n.is_active)
+            .map(|n| n.ssid);
+        let nm = nm_forget.clone();
+        let rt = rt_forget.clone();
+        let tx = tx_forget.clone();
+        rt_forget.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                if let Some(ssid) = active_ssid {
+                    let _ = tx.send_blocking(AppEvent::DisconnectStarted(ssid));
+                }
+                match rt.block_on(async { nm_inst.forget_network(&path).await }) {
+                    Ok(()) => {
+                        let _ = tx.send_blocking(AppEvent::Notify("Network forgotten".to_string()));
+                        if let Ok(saved) = rt.block_on(async { nm_inst.get_saved_networks().await }) {
+                            let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Forget failed: {}", e)));
+                    }
+                }
+            }
+        });
+    });
+    
+    let nm_forget_ssid = nm.clone();
+    let rt_forget_ssid = rt.clone();
+    let tx_forget_ssid = tx.clone();
+    let win_forget_ssid = win.clone();
+    win.network_list().set_on_forget(move |ssid: String| {
+        if win_forget_ssid.is_readonly() {
+            log::warn!("Readonly: Ignoring forget for {}", ssid);
+            return;
+        }
+        let paths: Vec<String> = win_forget_ssid.saved_networks_list().networks_for_ssid(&ssid)
+            .into_iter()
+            .map(|n| n.path)
+            .collect();
+        if paths.is_empty() {
+            log::warn!("No saved profile found for {}", ssid);
+            return;
+        }
+        let nm = nm_forget_ssid.clone();
+        let rt = rt_forget_ssid.clone();
+        let tx = tx_forget_ssid.clone();
+        rt_forget_ssid.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                let res = rt.block_on(async {
+                    for path in &paths {
+                        nm_inst.forget_network(path).await?;
+                    }
+                    Ok::<(), zbus::Error>(())
+                });
+                match res {
+                    Ok(()) => {
+                        let _ = tx.send_blocking(AppEvent::Notify(format!("Forgot {}", ssid)));
+                    }
+                    Err(e) => {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Forget failed: {}", e)));
+                    }
+                }
+                if let Ok(saved) = rt.block_on(async { nm_inst.get_saved_networks().await }) {
+                    let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
+                }
+            }
+        });
+    });
+    
+    let nm_ipv4 = nm.clone();
+    let rt_ipv4 = rt.clone();
+    let tx_ipv4 = tx.clone();
+    let win_ipv4 = win.clone();
+    win.saved_networks_list().set_on_edit_ipv4(move |path: String, ssid: String| {
+        if win_ipv4.is_readonly() {
+            log::warn!("Readonly: Ignoring IPv4 edit for {}", path);
+            return;
+        }
+        let nm = nm_ipv4.clone();
+        let rt = rt_ipv4.clone();
+        let tx = tx_ipv4.clone();
+        // Read the profile first so the dialog starts from what is saved
+        rt_ipv4.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                match rt.block_on(async { nm_inst.get_ipv4_settings(&path).await }) {
+                    Ok(current) => {
+                        let _ = tx.send_blocking(AppEvent::EditIpv4(path, ssid, current));
+                    }
+                    Err(e) => {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Failed to read IPv4 settings: {}", e)));
+                    }
+                }
+            }
+        });
+    });
+
+    let nm_psk = nm.clone();
+    let rt_psk = rt.clone();
+    let tx_psk = tx.clone();
+    let win_psk = win.clone();
+    win.saved_networks_list().set_on_change_password(move |path: String, ssid: String| {
+        if win_psk.is_readonly() {
+            log::warn!("Readonly: Ignoring password change for {}", path);
+            return;
+        }
+        let nm = nm_psk.clone();
+        let rt = rt_psk.clone();
+        let tx = tx_psk.clone();
+        win_psk.show_change_password_dialog(&ssid.clone(), move |password: String| {
+            let nm = nm.clone();
+            let rt_job = rt.clone();
+            let tx = tx.clone();
+            let path = path.clone();
+            let ssid = ssid.clone();
+            rt.network(move || {
+                let nm_guard = nm.lock().unwrap();
+                if let Some(ref nm_inst) = *nm_guard {
+                    match rt_job.block_on(async { nm_inst.update_psk(&path, &password).await }) {
+                        Ok(()) => {
+                            let _ = tx.send_blocking(AppEvent::PasswordChanged(ssid));
+                        }
+                        Err(e) => {
+                            let _ = tx.send_blocking(AppEvent::PasswordChangeFailed(format!("Password change failed: {}", e)));
+                        }
+                    }
+                    if let Ok(saved) = rt_job.block_on(async { nm_inst.get_saved_networks().await }) {
+                        let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
+                    }
+                }
+            });
+        });
+    });
+
+    let nm_conn = nm.clone();
+    let rt_conn = rt.clone();
+    let tx_conn = tx.clone();
+    let win_conn_hidden = win.clone();
+    let tx_conn_hidden = tx.clone();
+    win.network_list().set_on_connect_hidden(move || {
+        let tx = tx_conn_hidden.clone();
+        win_conn_hidden.show_hidden_dialog(move |data| {
+            if let Some((ssid, password)) = data {
                 let _ = tx.send_blocking(AppEvent::ConnectHidden(ssid, password));
             }
         });
@@ -632,7 +1633,7 @@ fn setup_ui_callbacks(
             let nm_val = nm.clone();
             let rt_val = rt.clone();
             let tx_val = tx.clone();
-            std::thread::spawn(move || {
+            rt.network(move || {
                 let nm_guard = nm_val.lock().unwrap();
                 if let Some(ref nm_inst) = *nm_guard {
                     let _ = rt_val.block_on(async { nm_inst.disconnect_ap(&ssid_inner, &ap_path_inner).await });
@@ -666,11 +1667,11 @@ fn setup_ui_callbacks(
                 let tx_val = tx.clone();
                 let ssid_val = ssid.clone();
                 let ap_path_val = ap_path.clone();
-                std::thread::spawn(move || {
+                rt.network(move || {
                     log::info!("UI: Connect clicked for network: '{}' (Has saved: {})", ssid_val, has_saved);
                     let nm_guard = nm_val.lock().unwrap();
                     if let Some(ref nm_inst) = *nm_guard {
-                        match rt_val.block_on(async { nm_inst.connect_to_network(&ssid_val, None, &ap_path_val).await }) {
+                        match rt_val.block_on(async { nm_inst.connect_to_network(&ssid_val, None, &ap_path_val, true).await }) {
                             Ok(()) => {
                                 std::thread::sleep(std::time::Duration::from_millis(1000));
                                 let _ = tx_val.send_blocking(AppEvent::ConnectSuccess);
@@ -681,19 +1682,56 @@ fn setup_ui_callbacks(
                             }
                             Err(e) => { 
                                 log::error!("UI: Connect failed for '{}': {}", ssid_val, e);
-                                let _ = tx_val.send_blocking(AppEvent::Error(format!("Connect failed: {}", e))); 
+                                let _ = tx_val.send_blocking(connect_failed_event(&ssid_val, e));
                             }
                         }
                     }
                 });
+            } else if ap.security == SecurityType::Enterprise {
+                let ssid_val = ssid.clone();
+                let nm_val = nm.clone();
+                let rt_val = rt.clone();
+                let tx_val = tx.clone();
+                let ap_path_val = ap_path.clone();
+                win_p.show_enterprise_dialog(&ssid, move |credentials| {
+                    if let Some(creds) = credentials {
+                        let nm_inner = nm_val.clone();
+                        let rt_inner = rt_val.clone();
+                        let tx_inner = tx_val.clone();
+                        let ssid_inner = ssid_val.clone();
+                        let ap_path_inner = ap_path_val.clone();
+
+                        let _ = tx_inner.send_blocking(AppEvent::ConnectStarted(ssid_inner.clone()));
+                        rt_val.network(move || {
+                            log::info!("UI: Connect clicked (802.1X {}) for: '{}'", creds.eap_method, ssid_inner);
+                            let nm_guard = nm_inner.lock().unwrap();
+                            if let Some(ref nm_inst) = *nm_guard {
+                                match rt_inner.block_on(async { nm_inst.connect_enterprise(&ssid_inner, &creds, &ap_path_inner).await }) {
+                                    Ok(()) => {
+                                        std::thread::sleep(std::time::Duration::from_millis(1000));
+                                        let _ = tx_inner.send_blocking(AppEvent::ConnectSuccess);
+                                        let _ = tx_inner.send_blocking(AppEvent::Notify(format!("Connected to {}", ssid_inner)));
+                                        if let Ok(aps) = rt_inner.block_on(async { nm_inst.get_access_points().await }) {
+                                            let _ = tx_inner.send_blocking(AppEvent::WifiScanResult(aps));
+                                        }
+                                    }
+                                    Err(e) => {
+                                        log::error!("UI: Connect failed for '{}': {}", ssid_inner, e);
+                                        let _ = tx_inner.send_blocking(connect_failed_event(&ssid_inner, e));
+                                    }
+                                }
+                            }
+                        });
+                    }
+                });
             } else {
                 let ssid_val = ssid.clone();
                 let nm_val = nm.clone();
                 let rt_val = rt.clone();
                 let tx_val = tx.clone();
                 let ap_path_val = ap_path.clone();
-                win_p.show_password_dialog(&ssid, move |password| {
-                    if let Some(pwd) = password {
+                win_p.show_password_dialog(&ssid, &ap.security, move |password| {
+                    if let Some((pwd, autoconnect)) = password {
                         let nm_inner = nm_val.clone();
                         let rt_inner = rt_val.clone();
                         let tx_inner = tx_val.clone();
@@ -701,11 +1739,11 @@ fn setup_ui_callbacks(
                         let ap_path_inner = ap_path_val.clone();
 
                         let _ = tx_inner.send_blocking(AppEvent::ConnectStarted(ssid_inner.clone()));
-                        std::thread::spawn(move || {
+                        rt_val.network(move || {
                             log::info!("UI: Connect clicked (with password) for: '{}'", ssid_inner);
                             let nm_guard = nm_inner.lock().unwrap();
                             if let Some(ref nm_inst) = *nm_guard {
-                                match rt_inner.block_on(async { nm_inst.connect_to_network(&ssid_inner, Some(&pwd), &ap_path_inner).await }) {
+                                match rt_inner.block_on(async { nm_inst.connect_to_network(&ssid_inner, Some(&pwd), &ap_path_inner, autoconnect).await }) {
                                     Ok(()) => {
                                         std::thread::sleep(std::time::Duration::from_millis(1000));
                                         let _ = tx_inner.send_blocking(AppEvent::ConnectSuccess);
@@ -716,7 +1754,7 @@ fn setup_ui_callbacks(
                                     }
                                     Err(e) => { 
                                         log::error!("UI: Connect failed for '{}': {}", ssid_inner, e);
-                                        let _ = tx_inner.send_blocking(AppEvent::Error(format!("Connect failed: {}", e))); 
+                                        let _ = tx_inner.send_blocking(connect_failed_event(&ssid_inner, e));
                                     }
                                 }
                             }
@@ -727,6 +1765,117 @@ fn setup_ui_callbacks(
         }
     });
     
+    let nm_wps = nm.clone();
+    let rt_wps = rt.clone();
+    let tx_wps = tx.clone();
+    let win_wps = win.clone();
+    let cancel_wps = connect_cancel.clone();
+    win.network_list().set_on_connect_wps(move |ap: AccessPoint| {
+        let nm = nm_wps.clone();
+        let rt = rt_wps.clone();
+        let tx = tx_wps.clone();
+        let ssid = ap.ssid.clone();
+        let device_path = ap.device_path.clone();
+        let _ = tx.send_blocking(AppEvent::ConnectStarted(ssid.clone()));
+        let cancel = cancel_wps.clone();
+        win_wps.show_wps_prompt(&ssid, move || cancel.store(true, Ordering::SeqCst));
+        rt_wps.network(move || {
+            log::info!("UI: WPS clicked for network: '{}'", ssid);
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                match rt.block_on(async { nm_inst.connect_wps(&ssid, &device_path).await }) {
+                    Ok(()) => {
+                        let _ = tx.send_blocking(AppEvent::ConnectSuccess);
+                        let _ = tx.send_blocking(AppEvent::Notify(format!("Connected to {}", ssid)));
+                        if let Ok(aps) = rt.block_on(async { nm_inst.get_access_points().await }) {
+                            let _ = tx.send_blocking(AppEvent::WifiScanResult(aps));
+                        }
+                    }
+                    Err(ConnectError::Cancelled) => {
+                        let _ = tx.send_blocking(AppEvent::ConnectCancelled);
+                    }
+                    Err(ConnectError::Timeout) | Err(ConnectError::WrongPassword) => {
+                        let _ = tx.send_blocking(AppEvent::Error(
+                            "WPS timed out. Press the WPS button on the router, then try again".to_string(),
+                        ));
+                    }
+                    Err(e) => {
+                        log::error!("UI: WPS failed for '{}': {}", ssid, e);
+                        let _ = tx.send_blocking(AppEvent::Error(format!("WPS failed: {}", e)));
+                    }
+                }
+            }
+            let _ = tx.send_blocking(AppEvent::WpsFinished);
+        });
+    });
+    
+    // Only raises the flag: the connect job holds the NetworkManager lock until it notices
+    win.network_list().set_on_cancel_connect(move |ssid: String| {
+        log::info!("UI: Cancel clicked for: '{}'", ssid);
+        connect_cancel.store(true, Ordering::SeqCst);
+    });
+    
+    let nm_bssid = nm.clone();
+    let rt_bssid = rt.clone();
+    let tx_bssid = tx.clone();
+    let win_bssid = win.clone();
+    win.network_list().set_on_connect_bssid(move |ap: AccessPoint| {
+        let has_saved = win_bssid.network_list().is_saved(&ap.ssid);
+
+        if ap.security == SecurityType::Enterprise && !has_saved {
+            let _ = tx_bssid.send_blocking(AppEvent::Error(format!("Connect to {} once before picking an access point", ap.ssid)));
+            return;
+        }
+
+        let nm = nm_bssid.clone();
+        let rt = rt_bssid.clone();
+        let tx = tx_bssid.clone();
+        let ssid = ap.ssid.clone();
+        let bssid = ap.bssid.clone();
+        let ap_path = ap.path.clone();
+        let device_path = ap.device_path.clone();
+        let start_connect = move |password: Option<String>, autoconnect: bool| {
+            let nm_val = nm.clone();
+            let rt_val = rt.clone();
+            let tx_val = tx.clone();
+            let ssid_val = ssid.clone();
+            let bssid_val = bssid.clone();
+            let ap_path_val = ap_path.clone();
+            let device_path_val = device_path.clone();
+            let _ = tx_val.send_blocking(AppEvent::ConnectStarted(ssid_val.clone()));
+            rt.network(move || {
+                log::info!("UI: Connect clicked for network: '{}', pinning to {}", ssid_val, bssid_val);
+                let nm_guard = nm_val.lock().unwrap();
+                if let Some(ref nm_inst) = *nm_guard {
+                    match rt_val.block_on(async { nm_inst.connect_to_bssid(&ssid_val, &bssid_val, &ap_path_val, password.as_deref(), &device_path_val, autoconnect).await }) {
+                        Ok(()) => {
+                            std::thread::sleep(std::time::Duration::from_millis(1000));
+                            let _ = tx_val.send_blocking(AppEvent::ConnectSuccess);
+                            let _ = tx_val.send_blocking(AppEvent::Notify(format!("Connected to {} ({})", ssid_val, bssid_val)));
+                            if let Ok(aps) = rt_val.block_on(async { nm_inst.get_access_points().await }) {
+                                let _ = tx_val.send_blocking(AppEvent::WifiScanResult(aps));
+                            }
+                        }
+                        Err(e) => {
+                            log::error!("UI: Connect failed for '{}' ({}): {}", ssid_val, bssid_val, e);
+                            let _ = tx_val.send_blocking(connect_failed_event(&ssid_val, e));
+                        }
+                    }
+                }
+            });
+        };
+
+        if ap.security == SecurityType::None || has_saved {
+            start_connect(None, true);
+        } else {
+            win_bssid.show_password_dialog(&ap.ssid, &ap.security, move |password| {
+                if let Some((pwd, autoconnect)) = password {
+                    start_connect(Some(pwd), autoconnect);
+                }
+            });
+        }
+    });
+    
     let nm_details = nm.clone();
     let rt_details = rt.clone();
     let tx_details = tx.clone();
@@ -734,7 +1883,7 @@ fn setup_ui_callbacks(
         let nm = nm_details.clone();
         let rt = rt_details.clone();
         let tx = tx_details.clone();
-        std::thread::spawn(move || {
+        rt_details.network(move || {
             let nm_guard = nm.lock().unwrap();
             if let Some(ref nm_inst) = *nm_guard {
                 match rt.block_on(async { nm_inst.get_network_details(&ssid).await }) {
@@ -749,44 +1898,192 @@ fn setup_ui_callbacks(
         });
     });
     
+    let nm_share = nm.clone();
+    let rt_share = rt.clone();
+    let tx_share = tx.clone();
+    win.set_on_share(move |ssid: String| {
+        let nm = nm_share.clone();
+        let rt = rt_share.clone();
+        let tx = tx_share.clone();
+        rt_share.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                match rt.block_on(async { nm_inst.get_saved_psk(&ssid).await }) {
+                    Ok(psk) => {
+                        let _ = tx.send_blocking(AppEvent::ShareNetwork(ssid, psk));
+                    }
+                    Err(e) => {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Failed to share network: {}", e)));
+                    }
+                }
+            }
+        });
+    });
+    
+    let nm_dns = nm.clone();
+    let rt_dns = rt.clone();
+    let tx_dns = tx.clone();
+    let win_dns = win.clone();
+    win.set_on_dns_override(move |ssid: String, device_path: String, servers: Option<Vec<String>>| {
+        if win_dns.is_readonly() {
+            log::warn!("Readonly: Ignoring DNS change for {}", ssid);
+            return;
+        }
+        let nm = nm_dns.clone();
+        let rt = rt_dns.clone();
+        let tx = tx_dns.clone();
+        rt_dns.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                let override_dns = servers.is_some();
+                let res = rt.block_on(async {
+                    let path = nm_inst.get_connection_path(&ssid).await
+                        .ok_or_else(|| zbus::Error::Address(format!("No saved connection for {}", ssid)))?;
+                    match servers {
+                        Some(servers) => nm_inst.set_dns(&path, servers).await?,
+                        None => nm_inst.clear_dns(&path).await?,
+                    }
+                    nm_inst.reapply(&device_path).await
+                });
+                match res {
+                    Ok(()) => {
+                        let msg = if override_dns {
+                            format!("Custom DNS applied to {}", ssid)
+                        } else {
+                            format!("Automatic DNS restored for {}", ssid)
+                        };
+                        let _ = tx.send_blocking(AppEvent::Notify(msg));
+                    }
+                    Err(e) => {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Failed to update DNS: {}", e)));
+                    }
+                }
+            }
+        });
+    });
+    
+    let nm_stats = nm.clone();
+    let rt_stats = rt.clone();
+    let tx_stats = tx.clone();
+    let stats_queued = Arc::new(AtomicBool::new(false));
+    win.set_on_stats_tick(move |device_path: String| {
+        // Skip this tick while the previous one still waits behind a long-running connect
+        if stats_queued.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let nm = nm_stats.clone();
+        let rt = rt_stats.clone();
+        let tx = tx_stats.clone();
+        let stats_queued = stats_queued.clone();
+        rt_stats.network(move || {
+            stats_queued.store(false, Ordering::SeqCst);
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                match rt.block_on(async { nm_inst.get_device_statistics(&device_path).await }) {
+                    Ok((rx, tx_bytes)) => {
+                        let _ = tx.send_blocking(AppEvent::DeviceStats(rx, tx_bytes));
+                    }
+                    Err(e) => {
+                        log::debug!("Failed to read device statistics: {}", e);
+                    }
+                }
+            }
+        });
+    });
+    
+    // Discovery runs in the background: devices show up through the BlueZ watcher as they
+    // are found, and the queue stays free for refreshes and actions meanwhile.
     let bt_scan = bt.clone();
     let rt_bt = rt.clone();
     let tx_bt = tx.clone();
-    let dev_list = win.device_list().clone();
+    let win_scan = win.clone();
+    let scan_timer: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
     win.device_list().scan_button().connect_clicked(move |_| {
-        dev_list.show_scanning();
+        let dev_list = win_scan.device_list();
+        if dev_list.is_scanning() {
+            if let Some(source) = scan_timer.borrow_mut().take() {
+                source.remove();
+            }
+            dev_list.stopping_scan();
+            stop_bt_discovery(&bt_scan, &rt_bt, &tx_bt);
+            return;
+        }
+        dev_list.begin_scan();
         let bt = bt_scan.clone();
         let rt = rt_bt.clone();
         let tx = tx_bt.clone();
-        std::thread::spawn(move || {
+        rt_bt.bluetooth(move || {
             let bt_guard = bt.lock().unwrap();
             if let Some(ref bt_inst) = *bt_guard {
-                let _ = rt.block_on(async { bt_inst.start_discovery().await });
-                std::thread::sleep(std::time::Duration::from_secs(5));
-                let _ = rt.block_on(async { bt_inst.stop_discovery().await });
-                if let Ok(devices) = rt.block_on(async { bt_inst.get_devices().await }) {
-                    let _ = tx.send_blocking(AppEvent::BtScanResult(devices));
+                match rt.block_on(async { bt_inst.start_discovery().await }) {
+                    Ok(()) => {
+                        if let Ok(devices) = rt.block_on(async { bt_inst.get_devices().await }) {
+                            let _ = tx.send_blocking(AppEvent::BtScanResult(devices));
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Scan failed: {}", e)));
+                        let _ = tx.send_blocking(AppEvent::BtScanFinished);
+                    }
                 }
             }
         });
+        let secs = win_scan.bt_scan_secs();
+        if secs > 0 {
+            let bt = bt_scan.clone();
+            let rt = rt_bt.clone();
+            let tx = tx_bt.clone();
+            let timer = scan_timer.clone();
+            let dev_list = dev_list.clone();
+            let source = glib::timeout_add_local_once(std::time::Duration::from_secs(secs as u64), move || {
+                *timer.borrow_mut() = None;
+                dev_list.stopping_scan();
+                stop_bt_discovery(&bt, &rt, &tx);
+            });
+            *scan_timer.borrow_mut() = Some(source);
+        }
     });
     
     let bt_act = bt.clone();
     let rt_act = rt.clone();
     let tx_act = tx.clone();
+    let win_act = win.clone();
     win.device_list().set_on_action(move |path: String, action: DeviceAction| {
+        if win_act.is_readonly() && matches!(action, DeviceAction::Pair | DeviceAction::Forget | DeviceAction::Trust(_) | DeviceAction::Rename(_)) {
+            log::warn!("Readonly: Ignoring Bluetooth device change for {}", path);
+            return;
+        }
         let bt = bt_act.clone();
         let rt = rt_act.clone();
         let tx = tx_act.clone();
-        let _ = tx.send_blocking(AppEvent::BtActionStarted(path.clone(), action.clone()));
-        std::thread::spawn(move || {
+        // Transport controls answer instantly; a spinner would only flicker
+        if !matches!(action, DeviceAction::Media(_)) {
+            let _ = tx.send_blocking(AppEvent::BtActionStarted(path.clone(), action.clone()));
+        }
+        if let DeviceAction::SendFile(file) = action {
+            match win_act.device_list().device(&path) {
+                Some(device) => send_file(device, file, rt, tx),
+                None => {
+                    let _ = tx.send_blocking(AppEvent::BtActionComplete);
+                }
+            }
+            return;
+        }
+        let retry = win_act.bt_connect_retry();
+        rt_act.bluetooth(move || {
             let bt_guard = bt.lock().unwrap();
             if let Some(ref bt_inst) = *bt_guard {
                 let res = match action {
+                    DeviceAction::Connect if retry => rt.block_on(async { bt_inst.connect_device_with_retry(&path).await }),
                     DeviceAction::Connect => rt.block_on(async { bt_inst.connect_device(&path).await }),
                     DeviceAction::Disconnect => rt.block_on(async { bt_inst.disconnect_device(&path).await }),
                     DeviceAction::Pair => rt.block_on(async { bt_inst.pair_device(&path).await }),
                     DeviceAction::Forget => rt.block_on(async { bt_inst.forget_device(&path).await }),
+                    DeviceAction::Trust(trusted) => rt.block_on(async { bt_inst.set_trusted(&path, trusted).await }),
+                    DeviceAction::SetProfile(ref profile) => rt.block_on(async { bt_inst.set_audio_profile(&path, profile).await }),
+                    DeviceAction::Rename(ref alias) => rt.block_on(async { bt_inst.set_alias(&path, alias).await }),
+                    DeviceAction::Media(command) => rt.block_on(async { bt_inst.media_command(&path, command).await }),
+                    DeviceAction::SendFile(_) => return,
                 };
                 match res {
                     Ok(()) => {
@@ -795,6 +2092,9 @@ fn setup_ui_callbacks(
                         if let Ok(devices) = rt.block_on(async { bt_inst.get_devices().await }) {
                             let _ = tx.send_blocking(AppEvent::BtScanResult(devices));
                         }
+                        if matches!(action, DeviceAction::SetProfile(_)) {
+                            send_audio_profiles(bt_inst, &path, &rt, &tx);
+                        }
                     }
                     Err(e) => {
                         let _ = tx.send_blocking(AppEvent::BtActionComplete);
@@ -808,6 +2108,58 @@ fn setup_ui_callbacks(
         });
     });
     
+    let bt_prof = bt.clone();
+    let rt_prof = rt.clone();
+    let tx_prof = tx.clone();
+    win.device_list().set_on_profiles_requested(move |path: String| {
+        let bt = bt_prof.clone();
+        let rt = rt_prof.clone();
+        let tx = tx_prof.clone();
+        rt_prof.bluetooth(move || {
+            let bt_guard = bt.lock().unwrap();
+            if let Some(ref bt_inst) = *bt_guard {
+                send_audio_profiles(bt_inst, &path, &rt, &tx);
+            }
+        });
+    });
+    
+    let bt_all = bt.clone();
+    let rt_all = rt.clone();
+    let tx_all = tx.clone();
+    win.device_list().set_on_disconnect_all(move || {
+        let bt = bt_all.clone();
+        let rt = rt_all.clone();
+        let tx = tx_all.clone();
+        rt_all.bluetooth(move || {
+            let bt_guard = bt.lock().unwrap();
+            if let Some(ref bt_inst) = *bt_guard {
+                let devices = match rt.block_on(async { bt_inst.get_devices().await }) {
+                    Ok(devices) => devices,
+                    Err(e) => {
+                        let _ = tx.send_blocking(AppEvent::Error(format!("Disconnect failed: {}", e)));
+                        return;
+                    }
+                };
+                let mut failed = Vec::new();
+                for device in devices.iter().filter(|d| d.is_connected) {
+                    let _ = tx.send_blocking(AppEvent::BtActionStarted(device.path.clone(), DeviceAction::Disconnect));
+                    if let Err(e) = rt.block_on(async { bt_inst.disconnect_device(&device.path).await }) {
+                        log::warn!("Failed to disconnect {}: {}", device.name, e);
+                        failed.push(device.name.clone());
+                    }
+                }
+                let _ = tx.send_blocking(AppEvent::BtActionComplete);
+                if !failed.is_empty() {
+                    let _ = tx.send_blocking(AppEvent::Error(format!("Could not disconnect {}", failed.join(", "))));
+                }
+                std::thread::sleep(std::time::Duration::from_millis(500));
+                if let Ok(devices) = rt.block_on(async { bt_inst.get_devices().await }) {
+                    let _ = tx.send_blocking(AppEvent::BtScanResult(devices));
+                }
+            }
+        });
+    });
+    
     let nm_pwr = nm.clone();
     let bt_pwr = bt.clone();
     let rt_pwr = rt.clone();
@@ -822,6 +2174,11 @@ fn setup_ui_callbacks(
         }
 
         let enabled = switch.is_active();
+        if win_pwr_switch.is_readonly() {
+            log::warn!("Readonly: Reverting power switch change");
+            header.set_power_state(!enabled);
+            return;
+        }
         let nm = nm_pwr.clone();
         let bt = bt_pwr.clone();
         let rt = rt_pwr.clone();
@@ -830,77 +2187,247 @@ fn setup_ui_callbacks(
         
         log::info!("Toggle: Power switch active notify to {} (Active tab: '{}')", enabled, tab);
 
-        std::thread::spawn(move || {
-            if tab == "wifi" || tab == "saved" {
+        if tab == "wifi" || tab == "saved" {
+            rt_pwr.network(move || {
                 log::info!("Toggle: Executing WiFi power change to {}", enabled);
                 let nm_guard = nm.lock().unwrap();
                 if let Some(ref nm_inst) = *nm_guard {
                     let _ = rt.block_on(async { nm_inst.set_wifi_enabled(enabled).await });
                     let _ = tx.send_blocking(AppEvent::WifiPowerState(enabled));
                 }
-            } else if tab == "bluetooth" {
+            });
+        } else if tab == "bluetooth" {
+            rt_pwr.bluetooth(move || {
                 log::info!("Toggle: Executing Bluetooth power change to {}", enabled);
                 let bt_guard = bt.lock().unwrap();
                 if let Some(ref bt_inst) = *bt_guard {
                     let _ = rt.block_on(async { bt_inst.set_powered(enabled).await });
                     let _ = tx.send_blocking(AppEvent::BtPowerState(enabled));
                 }
+            });
+        }
+    });
+
+    let nm_air = nm.clone();
+    let bt_air = bt.clone();
+    let rt_air = rt.clone();
+    let tx_air = tx.clone();
+    let win_air = win.clone();
+    win.header().airplane_button().connect_toggled(move |button| {
+        let header = win_air.header();
+        if header.is_programmatic_update() {
+            return;
+        }
+        let enabled = button.is_active();
+        if win_air.is_readonly() {
+            log::warn!("Readonly: Reverting airplane mode change");
+            header.set_airplane_state(!enabled);
+            return;
+        }
+        let nm = nm_air.clone();
+        let bt = bt_air.clone();
+        let rt = rt_air.clone();
+        let tx = tx_air.clone();
+        log::info!("Toggle: Airplane mode to {}", enabled);
+        rt_air.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            let bt_guard = bt.lock().unwrap();
+            let result = rt.block_on(async {
+                if enabled {
+                    airplane::enable(nm_guard.as_deref(), bt_guard.as_ref()).await
+                } else {
+                    airplane::disable(nm_guard.as_deref(), bt_guard.as_ref()).await
+                }
+            });
+            if let Err(e) = result {
+                let _ = tx.send_blocking(AppEvent::Error(format!("Airplane mode failed: {}", e)));
+            }
+            if let Some(ref nm_inst) = *nm_guard {
+                if let Ok(wifi_enabled) = rt.block_on(async { nm_inst.is_wifi_enabled().await }) {
+                    let _ = tx.send_blocking(AppEvent::WifiPowerState(wifi_enabled));
+                }
+            }
+            if let Some(ref bt_inst) = *bt_guard {
+                if let Ok(powered) = rt.block_on(async { bt_inst.is_powered().await }) {
+                    let _ = tx.send_blocking(AppEvent::BtPowerState(powered));
+                }
             }
         });
     });
 }
 
-fn setup_periodic_refresh(
-    _win: OrbitWindow,
-    nm: Arc<Mutex<Option<NetworkManager>>>,
-    bt: Arc<Mutex<Option<BluetoothManager>>>,
-    rt: Arc<tokio::runtime::Runtime>,
-    tx: async_channel::Sender<AppEvent>,
+/// Pushes `file` to `device` over OBEX. Transfers can take minutes, so this runs on its
+/// own thread rather than holding up the Bluetooth queue.
+fn send_file(device: BluetoothDevice, file: String, rt: Arc<Runtime>, tx: async_channel::Sender<AppEvent>) {
+    let file_name = std::path::Path::new(&file)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.clone());
+    log::info!("Sending {} to {}", file, device.name);
+    std::thread::spawn(move || {
+        let res = rt.block_on(async {
+            let obex = ObexClient::new().await?;
+            obex.send_file(&device.address, &file, |transferred, size| {
+                let _ = tx.send_blocking(AppEvent::BtTransferProgress(file_name.clone(), transferred, size));
+            }).await
+        });
+        let _ = tx.send_blocking(AppEvent::BtTransferFinished);
+        let _ = tx.send_blocking(AppEvent::BtActionComplete);
+        match res {
+            Ok(()) => {
+                let _ = tx.send_blocking(AppEvent::Notify(format!("Sent {} to {}", file_name, device.name)));
+            }
+            Err(e) => {
+                let _ = tx.send_blocking(AppEvent::Error(format!("File transfer failed: {}", e)));
+            }
+        }
+    });
+}
+
+/// Asks the WiFi backend to rescan on the configured intervals. Results are not fetched
+/// here: the new access points arrive as change signals like any other update.
+fn setup_periodic_scan(
+    win: OrbitWindow,
+    nm: Arc<Mutex<Option<Box<dyn WifiBackend>>>>,
+    rt: Arc<Runtime>,
     is_visible: Rc<RefCell<bool>>,
-    current_tab: Rc<RefCell<String>>,
 ) {
-    let stack = _win.stack().clone();
-    glib::timeout_add_local(std::time::Duration::from_secs(5), move || {
-        if !*is_visible.borrow() {
+    let elapsed = Rc::new(RefCell::new(0u32));
+    glib::timeout_add_local(std::time::Duration::from_secs(1), move || {
+        // Read the intervals every tick so a config reload applies immediately
+        let showing_wifi = *is_visible.borrow() && win.stack().visible_child_name().as_deref() == Some("wifi");
+        let interval = if showing_wifi { win.scan_interval_secs() } else { win.idle_interval_secs() };
+        let due = {
+            let mut elapsed = elapsed.borrow_mut();
+            *elapsed += 1;
+            interval != 0 && *elapsed >= interval
+        };
+        if !due {
             return glib::ControlFlow::Continue;
         }
+        *elapsed.borrow_mut() = 0;
         
         let nm = nm.clone();
-        let bt = bt.clone();
-        let rt = rt.clone();
-        let tx = tx.clone();
-        let tab = current_tab.borrow().clone();
-        let current_visible = stack.visible_child_name().map(|s| s.to_string());
-        
-        if Some(tab.clone()) != current_visible {
-             return glib::ControlFlow::Continue;
+        let rt_scan = rt.clone();
+        rt.network(move || {
+            let nm_guard = nm.lock().unwrap();
+            if let Some(ref nm_inst) = *nm_guard {
+                if let Err(e) = rt_scan.block_on(async { nm_inst.scan().await }) {
+                    log::debug!("Periodic scan failed: {}", e);
+                }
+            }
+        });
+        glib::ControlFlow::Continue
+    });
+}
+
+/// Delay for refreshes after something structural changed, enough to absorb a burst of signals.
+const REFRESH_SOON_MS: u64 = 300;
+/// Signal strength changes arrive constantly, so only follow them this often.
+const REFRESH_LATER_MS: u64 = 5000;
+
+/// Any plain-HTTP page gets intercepted by a portal; only used when NetworkManager has
+/// connectivity checking turned off and no `captive-portal-url` is configured.
+const FALLBACK_PORTAL_URL: &str = "http://neverssl.com";
+
+/// Data that a D-Bus change signal can make stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Refresh {
+    AccessPoints,
+    SavedNetworks,
+    WifiPower,
+    Devices,
+    BtAdapter,
+}
+
+impl Refresh {
+    /// The tab that must be on screen for the refresh to be worth doing.
+    fn tab(&self) -> Option<&'static str> {
+        match self {
+            Refresh::AccessPoints => Some("wifi"),
+            Refresh::Devices => Some("bluetooth"),
+            // Also marks saved networks on the WiFi tab
+            Refresh::SavedNetworks | Refresh::WifiPower | Refresh::BtAdapter => None,
+        }
+    }
+}
+
+/// Runs `kind` after `delay_ms` unless the same refresh is already waiting, so bursts of
+/// signals collapse into one D-Bus round trip. Hidden windows and other tabs are skipped;
+/// showing the window or switching tabs fetches fresh data anyway.
+fn schedule_refresh(
+    kind: Refresh,
+    delay_ms: u64,
+    pending: &Rc<RefCell<HashSet<(Refresh, u64)>>>,
+    win: &OrbitWindow,
+    is_visible: &Rc<RefCell<bool>>,
+    nm: &Arc<Mutex<Option<Box<dyn WifiBackend>>>>,
+    bt: &Arc<Mutex<Option<BluetoothManager>>>,
+    rt: &Arc<Runtime>,
+    tx: &async_channel::Sender<AppEvent>,
+) {
+    if !pending.borrow_mut().insert((kind, delay_ms)) {
+        return;
+    }
+    let pending = pending.clone();
+    let stack = win.stack().clone();
+    let is_visible = is_visible.clone();
+    let nm = nm.clone();
+    let bt = bt.clone();
+    let rt = rt.clone();
+    let tx = tx.clone();
+    glib::timeout_add_local_once(std::time::Duration::from_millis(delay_ms), move || {
+        pending.borrow_mut().remove(&(kind, delay_ms));
+        if !*is_visible.borrow() {
+            return;
+        }
+        if let Some(tab) = kind.tab() {
+            if stack.visible_child_name().as_deref() != Some(tab) {
+                return;
+            }
         }
         
-        std::thread::spawn(move || {
-            if tab == "wifi" {
+        let queue = rt.clone();
+        match kind {
+            Refresh::AccessPoints | Refresh::SavedNetworks | Refresh::WifiPower => queue.network(move || {
                 let nm_guard = nm.lock().unwrap();
                 if let Some(ref nm_inst) = *nm_guard {
-                    if let Ok(aps) = rt.block_on(async { nm_inst.get_access_points().await }) {
-                        let _ = tx.send_blocking(AppEvent::WifiScanResult(aps));
+                    match kind {
+                        Refresh::AccessPoints => {
+                            if let Ok(aps) = rt.block_on(async { nm_inst.get_access_points().await }) {
+                                let _ = tx.send_blocking(AppEvent::WifiScanResult(aps));
+                            }
+                        }
+                        Refresh::SavedNetworks => {
+                            if let Ok(saved) = rt.block_on(async { nm_inst.get_saved_networks().await }) {
+                                let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
+                            }
+                        }
+                        _ => {
+                            if let Ok(enabled) = rt.block_on(async { nm_inst.is_wifi_enabled().await }) {
+                                let _ = tx.send_blocking(AppEvent::WifiPowerState(enabled));
+                            }
+                        }
                     }
                 }
-            } else if tab == "bluetooth" {
+            }),
+            Refresh::Devices | Refresh::BtAdapter => queue.bluetooth(move || {
                 let bt_guard = bt.lock().unwrap();
                 if let Some(ref bt_inst) = *bt_guard {
-                    if let Ok(devices) = rt.block_on(async { bt_inst.get_devices().await }) {
-                        let _ = tx.send_blocking(AppEvent::BtScanResult(devices));
-                    }
-                }
-            } else if tab == "saved" {
-                let nm_guard = nm.lock().unwrap();
-                if let Some(ref nm_inst) = *nm_guard {
-                    if let Ok(saved) = rt.block_on(async { nm_inst.get_saved_networks().await }) {
-                        let _ = tx.send_blocking(AppEvent::SavedNetworksResult(saved));
+                    if kind == Refresh::Devices {
+                        if let Ok(devices) = rt.block_on(async { bt_inst.get_devices().await }) {
+                            let _ = tx.send_blocking(AppEvent::BtScanResult(devices));
+                        }
+                    } else {
+                        if let Ok(powered) = rt.block_on(async { bt_inst.is_powered().await }) {
+                            let _ = tx.send_blocking(AppEvent::BtPowerState(powered));
+                        }
+                        if let Ok(discoverable) = rt.block_on(async { bt_inst.is_discoverable().await }) {
+                            let _ = tx.send_blocking(AppEvent::BtDiscoverable(discoverable));
+                        }
                     }
                 }
-            }
-        });
-        
-        glib::ControlFlow::Continue
+            }),
+        }
     });
 }
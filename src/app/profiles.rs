@@ -0,0 +1,37 @@
+use std::io::Write;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
+
+use crate::dbus::network_manager::ExportedNetwork;
+
+/// Layout of an `orbit export` file: one `[[network]]` table per profile.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct ProfileFile {
+    #[serde(default, rename = "network")]
+    networks: Vec<ExportedNetwork>,
+}
+
+/// Writes `networks` as TOML. With `with_secrets` the file holds plain-text keys, so it is
+/// created (or reset, when it already exists) to be readable by the owner only.
+pub fn write(path: &Path, networks: Vec<ExportedNetwork>, with_secrets: bool) -> Result<(), String> {
+    let content = toml::to_string(&ProfileFile { networks }).map_err(|e| e.to_string())?;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    if with_secrets {
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).map_err(|e| e.to_string())?;
+    if with_secrets {
+        // `mode` only applies to newly created files
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| e.to_string())?;
+    }
+    file.write_all(content.as_bytes()).map_err(|e| e.to_string())
+}
+
+pub fn read(path: &Path) -> Result<Vec<ExportedNetwork>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let file: ProfileFile = toml::from_str(&content).map_err(|e| e.to_string())?;
+    Ok(file.networks)
+}
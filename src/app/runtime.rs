@@ -0,0 +1,57 @@
+use std::future::Future;
+use std::sync::mpsc;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Owns the tokio runtime plus one long-lived thread per D-Bus service that runs blocking
+/// jobs in order. Jobs for a service already serialize on its mutex, so a queue per service
+/// keeps the same concurrency without starting a thread for every callback.
+pub struct Runtime {
+    tokio: tokio::runtime::Runtime,
+    network: mpsc::Sender<Job>,
+    bluetooth: mpsc::Sender<Job>,
+}
+
+impl Runtime {
+    pub fn new() -> std::io::Result<Self> {
+        Ok(Self {
+            tokio: tokio::runtime::Runtime::new()?,
+            network: spawn_queue("orbit-network")?,
+            bluetooth: spawn_queue("orbit-bluetooth")?,
+        })
+    }
+
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.tokio.block_on(future)
+    }
+
+    /// Queues a job that talks to NetworkManager.
+    pub fn network<F: FnOnce() + Send + 'static>(&self, job: F) {
+        if self.network.send(Box::new(job)).is_err() {
+            log::error!("Network worker has stopped, dropping job");
+        }
+    }
+
+    /// Queues a job that talks to BlueZ.
+    pub fn bluetooth<F: FnOnce() + Send + 'static>(&self, job: F) {
+        if self.bluetooth.send(Box::new(job)).is_err() {
+            log::error!("Bluetooth worker has stopped, dropping job");
+        }
+    }
+}
+
+fn spawn_queue(name: &str) -> std::io::Result<mpsc::Sender<Job>> {
+    let (tx, rx) = mpsc::channel::<Job>();
+    let thread_name = name.to_string();
+    std::thread::Builder::new()
+        .name(thread_name.clone())
+        .spawn(move || {
+            for job in rx {
+                // Keep the queue alive if a single job panics
+                if std::panic::catch_unwind(std::panic::AssertUnwindSafe(job)).is_err() {
+                    log::error!("Job on {} panicked", thread_name);
+                }
+            }
+        })?;
+    Ok(tx)
+}
@@ -0,0 +1,76 @@
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::theme::Theme;
+use super::daemon::DaemonCommand;
+
+/// Editors emit several events per save; wait this long for them to settle.
+const SETTLE_TIME: Duration = Duration::from_millis(250);
+
+/// Watches config.toml, theme.toml and style.css, calling `callback` with `ReloadConfig`
+/// or `ReloadTheme` after they change.
+pub fn watch_config_files<F>(callback: F)
+where
+    F: Fn(DaemonCommand) + Send + 'static,
+{
+    std::thread::spawn(move || {
+        if let Err(e) = run(callback) {
+            log::warn!("Config file watcher stopped: {}", e);
+        }
+    });
+}
+
+fn run<F: Fn(DaemonCommand)>(callback: F) -> notify::Result<()> {
+    let config_path = Config::config_path();
+    let theme_paths: Vec<PathBuf> = [Theme::theme_path(), Theme::style_css_path()]
+        .into_iter()
+        .flatten()
+        .collect();
+    let dir = match config_path.as_ref().and_then(|p| p.parent()) {
+        Some(dir) => dir.to_path_buf(),
+        None => return Ok(()),
+    };
+    // Watch the directory rather than the files: editors often save by replacing the file,
+    // and the files may not exist yet
+    std::fs::create_dir_all(&dir)?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    log::info!("Watching {:?} for config and theme changes", dir);
+
+    while let Ok(first) = rx.recv() {
+        let mut reload_config = false;
+        let mut reload_theme = false;
+        let mut pending = Some(first);
+        while let Some(res) = pending {
+            match res {
+                Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) => {
+                    for path in &event.paths {
+                        if Some(path) == config_path.as_ref() {
+                            reload_config = true;
+                        } else if theme_paths.contains(path) {
+                            reload_theme = true;
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Config file watcher error: {}", e),
+            }
+            pending = rx.recv_timeout(SETTLE_TIME).ok();
+        }
+
+        if reload_config {
+            log::info!("config.toml changed, reloading");
+            callback(DaemonCommand::ReloadConfig);
+        }
+        if reload_theme {
+            log::info!("Theme files changed, reloading");
+            callback(DaemonCommand::ReloadTheme);
+        }
+    }
+    Ok(())
+}
@@ -7,6 +7,10 @@ pub struct Config {
     #[serde(default = "default_position")]
     pub position: String,
     
+    /// Where `orbit toggle` without a position opens. Unset keeps the last position used.
+    #[serde(default)]
+    pub default_position: Option<String>,
+    
     #[serde(default = "default_margin")]
     pub margin_top: i32,
     
@@ -18,19 +22,164 @@ pub struct Config {
     
     #[serde(default = "default_margin")]
     pub margin_left: i32,
+    
+    /// Layer-shell exclusive zone: 0 keeps clear of bars that reserve space, -1 ignores them.
+    #[serde(default)]
+    pub exclusive_zone: i32,
+    
+    #[serde(default = "default_width")]
+    pub width: i32,
+    
+    #[serde(default = "default_height")]
+    pub height: i32,
+    
+    #[serde(default)]
+    pub readonly: bool,
+    
+    #[serde(default = "default_signal_unit")]
+    pub signal_unit: String,
+    
+    /// Color the connected network's signal bars with the accent; off keeps them neutral.
+    #[serde(default = "default_accent_signal_bars")]
+    pub accent_signal_bars: bool,
+    
+    #[serde(default = "default_discoverable_timeout")]
+    pub discoverable_timeout: u32,
+    
+    /// Seconds a Bluetooth scan runs before discovery stops; 0 runs until stopped.
+    #[serde(default = "default_bt_scan_secs")]
+    pub bt_scan_secs: u32,
+    
+    /// Try a failed Bluetooth connect once more, for devices that miss the first page
+    /// while waking up.
+    #[serde(default = "default_bt_connect_retry")]
+    pub bt_connect_retry: bool,
+    
+    /// Extra activation attempts after a WiFi connection times out or fails transiently.
+    #[serde(default)]
+    pub connect_retries: u32,
+    
+    /// Output connector name (e.g. "DP-1") to pin the window to.
+    #[serde(default)]
+    pub monitor: Option<String>,
+    
+    /// Seconds between WiFi scans while the panel shows the WiFi tab; 0 disables.
+    #[serde(default = "default_scan_interval")]
+    pub scan_interval_secs: u32,
+    
+    /// Seconds between WiFi scans while the panel is hidden; 0 disables.
+    #[serde(default)]
+    pub idle_interval_secs: u32,
+    
+    /// Open password fields with the text visible; follows the peek icon.
+    #[serde(default)]
+    pub show_password: bool,
+    
+    /// List sections folded by clicking their header, e.g. "wifi-available".
+    #[serde(default)]
+    pub collapsed_sections: Vec<String>,
+    
+    /// What to do when a network has a captive portal: "auto", "notify" or "off".
+    #[serde(default = "default_captive_portal")]
+    pub captive_portal: String,
+    
+    /// Page to open for a captive portal instead of the one NetworkManager's probe finds.
+    #[serde(default)]
+    pub captive_portal_url: Option<String>,
+    
+    /// Program (plus leading arguments) that shows desktop notifications; empty disables them.
+    #[serde(default = "default_notify_command")]
+    pub notify_command: String,
+    
+    /// Key the daemon asks the global shortcuts portal to bind to toggle, e.g. "LOGO+W".
+    #[serde(default)]
+    pub global_shortcut: Option<String>,
+    
+    /// Show a tray icon that toggles the panel. The daemon shows one regardless when the
+    /// compositor has no layer-shell.
+    #[serde(default)]
+    pub tray_icon: bool,
 }
 
+/// Sets a top-level key, keeping the comments around an existing entry. A missing key is
+/// appended to the file unless `is_default`, so untouched settings stay commented out.
+fn set_value(doc: &mut toml_edit::DocumentMut, key: &str, value: impl Into<toml_edit::Value>, is_default: bool) {
+    let mut value = value.into();
+    match doc.get_mut(key).and_then(|item| item.as_value_mut()) {
+        Some(existing) => {
+            if existing.to_string().trim() == value.to_string().trim() {
+                return;
+            }
+            // Keep the `key = value  # comment` spacing and trailing comment
+            *value.decor_mut() = existing.decor().clone();
+            *existing = value;
+        }
+        None if is_default => {}
+        None => {
+            doc.insert(key, toml_edit::Item::Value(value));
+        }
+    }
+}
+
+/// Commented-out defaults that `orbit config-init` writes.
+const CONFIG_TEMPLATE: &str = include_str!("../config/config.example.toml");
+
 fn default_position() -> String { "center".to_string() }
 fn default_margin() -> i32 { 10 }
+fn default_width() -> i32 { 420 }
+fn default_height() -> i32 { 500 }
+fn default_signal_unit() -> String { "percent".to_string() }
+fn default_accent_signal_bars() -> bool { true }
+fn default_discoverable_timeout() -> u32 { 180 }
+fn default_bt_scan_secs() -> u32 { 10 }
+fn default_bt_connect_retry() -> bool { true }
+fn default_scan_interval() -> u32 { 30 }
+fn default_captive_portal() -> String { "auto".to_string() }
+fn default_notify_command() -> String { "notify-send".to_string() }
+
+/// NetworkManager refuses scan requests that come in faster than this.
+const MIN_SCAN_INTERVAL_SECS: u32 = 10;
+
+/// Window sizes outside these ranges leave the lists unusable or the panel off-screen.
+const WIDTH_RANGE: std::ops::RangeInclusive<i32> = 320..=4096;
+const HEIGHT_RANGE: std::ops::RangeInclusive<i32> = 300..=4096;
+
+/// Named window positions, accepted by `position`, `default-position` and `orbit toggle`.
+pub const POSITIONS: [&str; 9] = [
+    "top-left", "top-center", "top-right",
+    "center-left", "center", "center-right",
+    "bottom-left", "bottom-center", "bottom-right",
+];
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             position: default_position(),
+            default_position: None,
             margin_top: default_margin(),
             margin_right: default_margin(),
             margin_bottom: default_margin(),
             margin_left: default_margin(),
+            exclusive_zone: 0,
+            width: default_width(),
+            height: default_height(),
+            readonly: false,
+            signal_unit: default_signal_unit(),
+            accent_signal_bars: default_accent_signal_bars(),
+            discoverable_timeout: default_discoverable_timeout(),
+            bt_scan_secs: default_bt_scan_secs(),
+            bt_connect_retry: default_bt_connect_retry(),
+            connect_retries: 0,
+            scan_interval_secs: default_scan_interval(),
+            idle_interval_secs: 0,
+            monitor: None,
+            show_password: false,
+            collapsed_sections: Vec::new(),
+            captive_portal: default_captive_portal(),
+            captive_portal_url: None,
+            notify_command: default_notify_command(),
+            global_shortcut: None,
+            tray_icon: false,
         }
     }
 }
@@ -39,14 +188,18 @@ impl Config {
     pub fn load() -> Self {
         let config_path = match Self::config_path() {
             Some(p) => p,
-            None => return Self::default(),
+            None => {
+                eprintln!("Neither XDG_CONFIG_HOME nor HOME is set, using the default config");
+                return Self::default();
+            }
         };
         
         if config_path.exists() {
             match std::fs::read_to_string(&config_path) {
                 Ok(content) => {
-                    match toml::from_str(&content) {
-                        Ok(config) => {
+                    match toml::from_str::<Self>(&content) {
+                        Ok(mut config) => {
+                            config.validate();
                             return config;
                         }
                         Err(e) => {
@@ -63,26 +216,146 @@ impl Config {
         Self::default()
     }
     
+    /// Writes position, margins, monitor, the password peek state and folded sections back
+    /// to config.toml, leaving other keys and all comments in the file as they were.
+    pub fn save(&self) -> Result<(), String> {
+        let config_path = Self::config_path()
+            .ok_or("Cannot determine config path: neither XDG_CONFIG_HOME nor HOME is set")?;
+        
+        // Edited in place so comments, ordering and formatting in the file survive
+        let mut doc = match std::fs::read_to_string(&config_path) {
+            Ok(content) => content.parse::<toml_edit::DocumentMut>()
+                .map_err(|e| format!("Failed to parse config: {}", e))?,
+            Err(_) => toml_edit::DocumentMut::new(),
+        };
+        
+        let defaults = Self::default();
+        set_value(&mut doc, "position", self.position.as_str(), self.position == defaults.position);
+        set_value(&mut doc, "margin-top", self.margin_top as i64, self.margin_top == defaults.margin_top);
+        set_value(&mut doc, "margin-right", self.margin_right as i64, self.margin_right == defaults.margin_right);
+        set_value(&mut doc, "margin-bottom", self.margin_bottom as i64, self.margin_bottom == defaults.margin_bottom);
+        set_value(&mut doc, "margin-left", self.margin_left as i64, self.margin_left == defaults.margin_left);
+        if let Some(ref monitor) = self.monitor {
+            set_value(&mut doc, "monitor", monitor.as_str(), false);
+        }
+        set_value(&mut doc, "show-password", self.show_password, self.show_password == defaults.show_password);
+        set_value(
+            &mut doc,
+            "collapsed-sections",
+            self.collapsed_sections.iter().map(String::as_str).collect::<toml_edit::Array>(),
+            self.collapsed_sections.is_empty(),
+        );
+        
+        if let Some(parent) = config_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        std::fs::write(&config_path, doc.to_string())
+            .map_err(|e| format!("Failed to write config: {}", e))
+    }
+    
+    /// `$XDG_CONFIG_HOME/orbit`, or `~/.config/orbit` when it is unset. Relative
+    /// `XDG_CONFIG_HOME` values are ignored, as the XDG spec requires.
+    pub fn config_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(base.join("orbit"))
+    }
+    
     pub fn config_path() -> Option<PathBuf> {
-        let home = std::env::var("HOME").ok()?;
-        Some(PathBuf::from(home)
-            .join(".config")
-            .join("orbit")
-            .join("config.toml"))
+        Some(Self::config_dir()?.join("config.toml"))
+    }
+    
+    /// Writes a commented default config.toml unless one exists. Returns its path and
+    /// whether it was written.
+    pub fn write_template() -> Result<(PathBuf, bool), String> {
+        let path = Self::config_path()
+            .ok_or("Cannot determine config path: neither XDG_CONFIG_HOME nor HOME is set")?;
+        let written = write_if_missing(&path, CONFIG_TEMPLATE)?;
+        Ok((path, written))
+    }
+    
+    /// Raises non-zero scan intervals below `MIN_SCAN_INTERVAL_SECS` to the minimum and
+    /// clamps the window size to `WIDTH_RANGE` and `HEIGHT_RANGE`.
+    fn validate(&mut self) {
+        for (key, value) in [
+            ("scan-interval-secs", &mut self.scan_interval_secs),
+            ("idle-interval-secs", &mut self.idle_interval_secs),
+        ] {
+            if *value != 0 && *value < MIN_SCAN_INTERVAL_SECS {
+                log::warn!("{} = {} is too frequent, using {}", key, value, MIN_SCAN_INTERVAL_SECS);
+                *value = MIN_SCAN_INTERVAL_SECS;
+            }
+        }
+        for (key, value, range) in [
+            ("width", &mut self.width, WIDTH_RANGE),
+            ("height", &mut self.height, HEIGHT_RANGE),
+        ] {
+            if !range.contains(value) {
+                let clamped = (*value).clamp(*range.start(), *range.end());
+                log::warn!("{} = {} is out of range, using {}", key, value, clamped);
+                *value = clamped;
+            }
+        }
+    }
+    
+    pub fn use_dbm(&self) -> bool {
+        self.signal_unit.eq_ignore_ascii_case("dbm")
+    }
+    
+    /// Whether a detected captive portal should be announced at all.
+    pub fn captive_portal_notifies(&self) -> bool {
+        !self.captive_portal.eq_ignore_ascii_case("off")
     }
     
+    /// Whether the login page should be opened in a browser; only in "auto" mode.
+    pub fn captive_portal_opens_browser(&self) -> bool {
+        !self.captive_portal.eq_ignore_ascii_case("off")
+            && !self.captive_portal.eq_ignore_ascii_case("notify")
+    }
+    
+    /// (column, row) of the configured position; an unknown name falls back to the center.
     pub fn position_tuple(&self) -> (i32, i32) {
-        match self.position.as_str() {
-            "top-left" => (0, 0),
-            "top-center" => (1, 0),
-            "top-right" => (2, 0),
-            "center-left" => (0, 1),
-            "center" => (1, 1),
-            "center-right" => (2, 1),
-            "bottom-left" => (0, 2),
-            "bottom-center" => (1, 2),
-            "bottom-right" => (2, 2),
-            _ => (1, 1),
+        Self::parse_position(&self.position).unwrap_or_else(|| {
+            log::warn!("Unknown position '{}', using center (expected one of: {})", self.position, POSITIONS.join(", "));
+            (1, 1)
+        })
+    }
+    
+    /// (column, row) for one of `POSITIONS`, or `None` for anything else. "centre",
+    /// "middle" and "center-center" are accepted for the center.
+    pub fn parse_position(name: &str) -> Option<(i32, i32)> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "top-left" => Some((0, 0)),
+            "top-center" => Some((1, 0)),
+            "top-right" => Some((2, 0)),
+            "center-left" => Some((0, 1)),
+            "center" | "centre" | "middle" | "center-center" => Some((1, 1)),
+            "center-right" => Some((2, 1)),
+            "bottom-left" => Some((0, 2)),
+            "bottom-center" => Some((1, 2)),
+            "bottom-right" => Some((2, 2)),
+            _ => None,
         }
     }
 }
+
+/// Creates `path` with `content`, leaving an existing file untouched. Returns whether
+/// the file was written.
+pub fn write_if_missing(path: &std::path::Path, content: &str) -> Result<bool, String> {
+    use std::io::Write;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let mut file = match std::fs::OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => return Ok(false),
+        Err(e) => return Err(format!("Failed to create {}: {}", path.display(), e)),
+    };
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(true)
+}
@@ -1,37 +1,176 @@
+use futures_util::StreamExt;
 use zbus::Connection;
 use zbus::zvariant::ObjectPath;
 
+/// Pause before the second `Connect` on a device that did not answer the first.
+const CONNECT_RETRY_DELAY_MS: u64 = 1500;
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BluetoothDevice {
     pub path: String,
     pub name: String,
+    /// Bluetooth address, e.g. `AA:BB:CC:DD:EE:FF`.
+    #[serde(default)]
+    pub address: String,
     pub device_type: Option<DeviceType>,
     pub is_connected: bool,
     pub is_paired: bool,
+    #[serde(default)]
+    pub is_trusted: bool,
     pub battery_percentage: Option<u8>,
+    /// Received signal strength in dBm, only reported while discovering.
+    #[serde(default)]
+    pub rssi: Option<i16>,
+    /// AVRCP player of a connected audio device, when it exposes one.
+    #[serde(default)]
+    pub media_player: Option<MediaPlayer>,
+}
+
+/// Now-playing state of an AVRCP player (`org.bluez.MediaPlayer1`).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MediaPlayer {
+    pub path: String,
+    /// "playing", "paused", "stopped", "forward-seek", "reverse-seek" or "error".
+    pub status: String,
+    pub title: String,
+    pub artist: String,
+}
+
+/// Transport controls sent to a device's media player.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaCommand {
+    Play,
+    Pause,
+    Next,
+    Previous,
+}
+
+impl MediaCommand {
+    fn method(&self) -> &'static str {
+        match self {
+            MediaCommand::Play => "Play",
+            MediaCommand::Pause => "Pause",
+            MediaCommand::Next => "Next",
+            MediaCommand::Previous => "Previous",
+        }
+    }
+}
+
+/// A sound server card profile such as A2DP playback or HSP/HFP headset.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AudioProfile {
+    pub name: String,
+    pub description: String,
+}
+
+/// Profiles of an audio device's sound server card.
+#[derive(Debug, Clone, Default)]
+pub struct CardProfiles {
+    pub profiles: Vec<AudioProfile>,
+    /// Name of the active profile, which need not be in `profiles` (e.g. "off").
+    pub active: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum DeviceType {
+    /// Speakers, car kits and other audio sinks.
     Audio,
+    Headphones,
     Keyboard,
     Mouse,
     Phone,
+    Computer,
+    Gamepad,
+    Printer,
+    Watch,
 }
 
+impl DeviceType {
+    /// Reads BlueZ's freedesktop icon hint, e.g. "input-keyboard".
+    fn from_icon(icon: &str) -> Option<Self> {
+        match icon {
+            "audio-card" | "audio-speakers" => Some(Self::Audio),
+            "audio-headset" | "audio-headphones" => Some(Self::Headphones),
+            "input-keyboard" => Some(Self::Keyboard),
+            "input-mouse" | "input-tablet" => Some(Self::Mouse),
+            "input-gaming" => Some(Self::Gamepad),
+            "phone" => Some(Self::Phone),
+            "computer" => Some(Self::Computer),
+            "printer" => Some(Self::Printer),
+            _ => None,
+        }
+    }
+
+    /// Reads the major/minor fields of a BR/EDR Class of Device.
+    fn from_class(class: u32) -> Option<Self> {
+        let major = (class >> 8) & 0x1f;
+        let minor = (class >> 2) & 0x3f;
+        match major {
+            0x01 => Some(Self::Computer),
+            0x02 => Some(Self::Phone),
+            0x04 => match minor {
+                0x01 | 0x02 | 0x06 => Some(Self::Headphones),
+                0x05 | 0x07 | 0x08 | 0x0a => Some(Self::Audio),
+                _ => None,
+            },
+            // Peripheral: bits 4-5 say keyboard/pointer, bits 0-3 the device subtype
+            0x05 => match (minor >> 4, minor & 0x0f) {
+                (_, 0x01) | (_, 0x02) => Some(Self::Gamepad),
+                (_, 0x05) | (0x02, _) => Some(Self::Mouse),
+                (0x01, _) | (0x03, _) => Some(Self::Keyboard),
+                _ => None,
+            },
+            // Imaging: the minor field is a bitmask, bit 5 being printer
+            0x06 if minor & 0x20 != 0 => Some(Self::Printer),
+            0x07 if minor == 0x01 => Some(Self::Watch),
+            _ => None,
+        }
+    }
+
+    /// Reads the GAP Appearance that LE-only devices advertise instead of a class.
+    fn from_appearance(appearance: u16) -> Option<Self> {
+        match (appearance >> 6, appearance & 0x3f) {
+            (0x001, _) => Some(Self::Phone),
+            (0x002, _) => Some(Self::Computer),
+            (0x003, _) => Some(Self::Watch),
+            (0x00a, _) | (0x021, _) => Some(Self::Audio),
+            (0x025, _) => Some(Self::Headphones),
+            (0x00f, 0x01) => Some(Self::Keyboard),
+            (0x00f, 0x02) | (0x00f, 0x05) => Some(Self::Mouse),
+            (0x00f, 0x03) | (0x00f, 0x04) => Some(Self::Gamepad),
+            _ => None,
+        }
+    }
+
+    /// Whether the device plays audio and may have sound server profiles.
+    pub fn is_audio(self) -> bool {
+        matches!(self, Self::Audio | Self::Headphones)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BluetoothAdapter {
+    pub path: String,
+    pub name: String,
+    pub address: String,
+}
+
+#[derive(Clone)]
 pub struct BluetoothManager {
     conn: Connection,
+    adapters: Vec<String>,
     adapter_path: Option<String>,
 }
 
 impl BluetoothManager {
     pub async fn new() -> zbus::Result<Self> {
         let conn = Connection::system().await?;
-        let adapter_path = Self::find_adapter(&conn).await?;
-        Ok(Self { conn, adapter_path })
+        let adapters = Self::find_adapters(&conn).await?;
+        let adapter_path = adapters.first().cloned();
+        Ok(Self { conn, adapters, adapter_path })
     }
 
-    async fn find_adapter(conn: &Connection) -> zbus::Result<Option<String>> {
+    async fn find_adapters(conn: &Connection) -> zbus::Result<Vec<String>> {
         let reply: std::collections::HashMap<zbus::zvariant::OwnedObjectPath, std::collections::HashMap<String, std::collections::HashMap<String, zbus::zvariant::OwnedValue>>> = conn
             .call_method(
                 Some("org.bluez"),
@@ -44,12 +183,63 @@ impl BluetoothManager {
             .body()
             .deserialize()?;
 
+        let mut adapters: Vec<String> = reply.into_iter()
+            .filter(|(_, interfaces)| interfaces.contains_key("org.bluez.Adapter1"))
+            .map(|(path, _)| path.to_string())
+            .collect();
+        // GetManagedObjects is unordered; sort so hci0 stays the default
+        adapters.sort();
+        Ok(adapters)
+    }
+
+    pub async fn list_adapters(&self) -> zbus::Result<Vec<BluetoothAdapter>> {
+        let reply: std::collections::HashMap<zbus::zvariant::OwnedObjectPath, std::collections::HashMap<String, std::collections::HashMap<String, zbus::zvariant::OwnedValue>>> = self.conn
+            .call_method(
+                Some("org.bluez"),
+                "/",
+                Some("org.freedesktop.DBus.ObjectManager"),
+                "GetManagedObjects",
+                &(),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+
+        let mut adapters = Vec::new();
         for (path, interfaces) in reply {
-            if interfaces.contains_key("org.bluez.Adapter1") {
-                return Ok(Some(path.to_string()));
+            if let Some(props) = interfaces.get("org.bluez.Adapter1") {
+                let name = props.get("Alias")
+                    .or_else(|| props.get("Name"))
+                    .and_then(|v| <&str>::try_from(v).ok())
+                    .unwrap_or("Bluetooth Adapter")
+                    .to_string();
+                let address = props.get("Address")
+                    .and_then(|v| <&str>::try_from(v).ok())
+                    .unwrap_or_default()
+                    .to_string();
+                adapters.push(BluetoothAdapter {
+                    path: path.to_string(),
+                    name,
+                    address,
+                });
             }
         }
-        Ok(None)
+        adapters.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(adapters)
+    }
+
+    pub fn selected_adapter(&self) -> Option<String> {
+        self.adapter_path.clone()
+    }
+
+    pub async fn select_adapter(&mut self, path: &str) -> zbus::Result<()> {
+        // Adapters can be hot-plugged, so re-check before switching
+        self.adapters = Self::find_adapters(&self.conn).await?;
+        if !self.adapters.iter().any(|a| a == path) {
+            return Err(zbus::Error::Address(format!("Bluetooth adapter not found: {}", path)));
+        }
+        self.adapter_path = Some(path.to_string());
+        Ok(())
     }
 
     pub async fn is_powered(&self) -> zbus::Result<bool> {
@@ -90,6 +280,56 @@ impl BluetoothManager {
         Ok(())
     }
 
+    pub async fn is_discoverable(&self) -> zbus::Result<bool> {
+        let adapter_str = self.adapter_path.as_ref()
+            .ok_or_else(|| zbus::Error::Address("No Bluetooth adapter found".to_string()))?;
+        let adapter = ObjectPath::try_from(adapter_str.as_str()).map_err(|e| zbus::Error::Variant(e))?;
+        
+        let reply = self.conn
+            .call_method(
+                Some("org.bluez"),
+                &adapter,
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.bluez.Adapter1", "Discoverable"),
+            )
+            .await?
+            .body()
+            .deserialize::<zbus::zvariant::OwnedValue>()?;
+
+        bool::try_from(reply).map_err(zbus::Error::from)
+    }
+
+    pub async fn set_discoverable(&self, discoverable: bool) -> zbus::Result<()> {
+        self.set_adapter_property("Discoverable", zbus::zvariant::Value::Bool(discoverable)).await
+    }
+
+    pub async fn set_pairable(&self, pairable: bool) -> zbus::Result<()> {
+        self.set_adapter_property("Pairable", zbus::zvariant::Value::Bool(pairable)).await
+    }
+
+    /// Seconds until BlueZ turns discoverable mode off again; 0 keeps it on indefinitely.
+    pub async fn set_discoverable_timeout(&self, seconds: u32) -> zbus::Result<()> {
+        self.set_adapter_property("DiscoverableTimeout", zbus::zvariant::Value::U32(seconds)).await
+    }
+
+    async fn set_adapter_property(&self, property: &str, value: zbus::zvariant::Value<'_>) -> zbus::Result<()> {
+        let adapter_str = self.adapter_path.as_ref()
+            .ok_or_else(|| zbus::Error::Address("No Bluetooth adapter found".to_string()))?;
+        let adapter = ObjectPath::try_from(adapter_str.as_str()).map_err(|e| zbus::Error::Variant(e))?;
+        
+        self.conn
+            .call_method(
+                Some("org.bluez"),
+                &adapter,
+                Some("org.freedesktop.DBus.Properties"),
+                "Set",
+                &("org.bluez.Adapter1", property, value),
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn start_discovery(&self) -> zbus::Result<()> {
         let adapter_str = self.adapter_path.as_ref()
             .ok_or_else(|| zbus::Error::Address("No Bluetooth adapter found".to_string()))?;
@@ -137,15 +377,31 @@ impl BluetoothManager {
             .body()
             .deserialize()?;
 
+        let players = media_players(&reply);
+
         let mut devices = Vec::new();
         for (path, interfaces) in reply {
             if let Some(props) = interfaces.get("org.bluez.Device1") {
-                let name = props.get("Name")
-                    .or_else(|| props.get("Alias"))
+                if let Some(ref adapter) = self.adapter_path {
+                    let device_adapter = props.get("Adapter")
+                        .and_then(|v| zbus::zvariant::ObjectPath::try_from(&**v).ok());
+                    if device_adapter.map(|a| a.as_str() != adapter.as_str()).unwrap_or(false) {
+                        continue;
+                    }
+                }
+
+                // Alias mirrors Name unless the user renamed the device
+                let name = props.get("Alias")
+                    .or_else(|| props.get("Name"))
                     .and_then(|v| <&str>::try_from(v).ok())
                     .unwrap_or("Unknown Device")
                     .to_string();
 
+                let address = props.get("Address")
+                    .and_then(|v| <&str>::try_from(v).ok())
+                    .unwrap_or_default()
+                    .to_string();
+
                 let is_connected = props.get("Connected")
                     .and_then(|v| bool::try_from(v).ok())
                     .unwrap_or(false);
@@ -154,32 +410,44 @@ impl BluetoothManager {
                     .and_then(|v| bool::try_from(v).ok())
                     .unwrap_or(false);
 
+                let is_trusted = props.get("Trusted")
+                    .and_then(|v| bool::try_from(v).ok())
+                    .unwrap_or(false);
+
+                // Current BlueZ only reports battery on the separate Battery1 interface
                 let battery_percentage = props.get("BatteryPercentage")
+                    .or_else(|| interfaces.get("org.bluez.Battery1").and_then(|battery| battery.get("Percentage")))
                     .and_then(|v| u8::try_from(v).ok());
 
-                let icon = props.get("Icon")
-                    .and_then(|v| <&str>::try_from(v).ok());
+                let rssi = props.get("RSSI")
+                    .and_then(|v| i16::try_from(v).ok());
 
-                let device_type = match icon {
-                    Some("audio-card") | Some("audio-speakers") | Some("audio-headset") | Some("audio-headphones") => Some(DeviceType::Audio),
-                    Some("input-keyboard") => Some(DeviceType::Keyboard),
-                    Some("input-mouse") | Some("input-tablet") => Some(DeviceType::Mouse),
-                    Some("phone") => Some(DeviceType::Phone),
-                    _ => None,
-                };
+                // BlueZ derives Icon from the class or appearance, but not for every value,
+                // so fall back to reading those ourselves
+                let device_type = props.get("Icon")
+                    .and_then(|v| <&str>::try_from(v).ok())
+                    .and_then(DeviceType::from_icon)
+                    .or_else(|| props.get("Class").and_then(|v| u32::try_from(v).ok()).and_then(DeviceType::from_class))
+                    .or_else(|| props.get("Appearance").and_then(|v| u16::try_from(v).ok()).and_then(DeviceType::from_appearance));
 
                 devices.push(BluetoothDevice {
                     path: path.to_string(),
                     name,
+                    address,
                     device_type,
                     is_connected,
                     is_paired,
+                    is_trusted,
                     battery_percentage,
+                    rssi,
+                    media_player: players.iter()
+                        .find(|p| p.path.starts_with(&format!("{}/", path.as_str())))
+                        .cloned(),
                 });
             }
         }
 
-        devices.sort_by(|a, b| b.is_connected.cmp(&a.is_connected).then_with(|| b.is_paired.cmp(&a.is_paired)).then_with(|| a.name.cmp(&b.name)));
+        devices.sort_by(|a, b| b.is_connected.cmp(&a.is_connected).then_with(|| b.is_paired.cmp(&a.is_paired)).then_with(|| b.rssi.cmp(&a.rssi)).then_with(|| a.name.cmp(&b.name)));
         Ok(devices)
     }
 
@@ -197,6 +465,26 @@ impl BluetoothManager {
         Ok(())
     }
 
+    /// Like `connect_device`, but tries once more after a short pause when the first attempt
+    /// fails outright, which is how devices waking from sleep usually answer the first page.
+    pub async fn connect_device_with_retry(&self, path: &str) -> zbus::Result<()> {
+        match self.connect_device(path).await {
+            Err(zbus::Error::MethodError(name, detail, _))
+                if matches!(name.as_str(), "org.bluez.Error.Failed" | "org.freedesktop.DBus.Error.NoReply") =>
+            {
+                log::warn!(
+                    "Connecting {} failed ({}), retrying in {}ms",
+                    path,
+                    detail.as_deref().unwrap_or(name.as_str()),
+                    CONNECT_RETRY_DELAY_MS
+                );
+                tokio::time::sleep(tokio::time::Duration::from_millis(CONNECT_RETRY_DELAY_MS)).await;
+                self.connect_device(path).await
+            }
+            res => res,
+        }
+    }
+
     pub async fn disconnect_device(&self, path: &str) -> zbus::Result<()> {
         let p = ObjectPath::try_from(path).map_err(|e| zbus::Error::Variant(e))?;
         self.conn
@@ -225,6 +513,109 @@ impl BluetoothManager {
         Ok(())
     }
 
+    pub async fn set_trusted(&self, path: &str, trusted: bool) -> zbus::Result<()> {
+        let p = ObjectPath::try_from(path).map_err(|e| zbus::Error::Variant(e))?;
+        let value = zbus::zvariant::Value::Bool(trusted);
+        self.conn
+            .call_method(
+                Some("org.bluez"),
+                &p,
+                Some("org.freedesktop.DBus.Properties"),
+                "Set",
+                &("org.bluez.Device1", "Trusted", value),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_alias(&self, path: &str, alias: &str) -> zbus::Result<()> {
+        let p = ObjectPath::try_from(path).map_err(|e| zbus::Error::Variant(e))?;
+        // An empty alias makes BlueZ fall back to the remote device name
+        let value = zbus::zvariant::Value::from(alias);
+        self.conn
+            .call_method(
+                Some("org.bluez"),
+                &p,
+                Some("org.freedesktop.DBus.Properties"),
+                "Set",
+                &("org.bluez.Device1", "Alias", value),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Renames the local adapter at `path`, which is the name other devices see while it is
+    /// discoverable.
+    pub async fn set_adapter_alias(&self, path: &str, alias: &str) -> zbus::Result<()> {
+        let p = ObjectPath::try_from(path).map_err(|e| zbus::Error::Variant(e))?;
+        // An empty alias makes BlueZ fall back to the system name
+        let value = zbus::zvariant::Value::from(alias);
+        self.conn
+            .call_method(
+                Some("org.bluez"),
+                &p,
+                Some("org.freedesktop.DBus.Properties"),
+                "Set",
+                &("org.bluez.Adapter1", "Alias", value),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Sends `command` to the AVRCP player of the device at `path`.
+    pub async fn media_command(&self, path: &str, command: MediaCommand) -> zbus::Result<()> {
+        let reply: std::collections::HashMap<zbus::zvariant::OwnedObjectPath, std::collections::HashMap<String, std::collections::HashMap<String, zbus::zvariant::OwnedValue>>> = self.conn
+            .call_method(
+                Some("org.bluez"),
+                "/",
+                Some("org.freedesktop.DBus.ObjectManager"),
+                "GetManagedObjects",
+                &(),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+        let player = media_players(&reply).into_iter()
+            .find(|p| p.path.starts_with(&format!("{}/", path)))
+            .ok_or_else(|| zbus::Error::Address("The device has no media player".to_string()))?;
+
+        let p = ObjectPath::try_from(player.path.as_str()).map_err(zbus::Error::Variant)?;
+        self.conn
+            .call_method(
+                Some("org.bluez"),
+                &p,
+                Some("org.bluez.MediaPlayer1"),
+                command.method(),
+                &(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Reads the sound server card profiles of a connected audio device. This runs
+    /// `pactl list cards`, so it is only done when the user asks to see them.
+    pub async fn get_audio_profiles(&self, path: &str) -> zbus::Result<CardProfiles> {
+        get_card_profiles(path).await
+    }
+
+    /// Switches the sound server card for an audio device to `profile`.
+    /// BlueZ doesn't own A2DP/HFP selection, so this goes through `pactl`
+    /// (PulseAudio or pipewire-pulse).
+    pub async fn set_audio_profile(&self, path: &str, profile: &str) -> zbus::Result<()> {
+        let card = card_name(path)
+            .ok_or_else(|| zbus::Error::Address("Not a Bluetooth device path".to_string()))?;
+        let output = tokio::process::Command::new("pactl")
+            .args(["set-card-profile", &card, profile])
+            .output()
+            .await
+            .map_err(|e| zbus::Error::Address(format!("Failed to run pactl: {}", e)))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(zbus::Error::Address(format!("Failed to switch profile: {}", stderr.trim())));
+        }
+        Ok(())
+    }
+
     pub async fn forget_device(&self, path: &str) -> zbus::Result<()> {
         let adapter_str = self.adapter_path.as_ref()
             .ok_or_else(|| zbus::Error::Address("No Bluetooth adapter found".to_string()))?;
@@ -244,4 +635,163 @@ impl BluetoothManager {
             .await?;
         Ok(())
     }
+
+    /// Calls `on_change` for each BlueZ signal that affects the device list or adapter state.
+    /// Runs until the bus connection closes.
+    pub async fn watch_changes<F: Fn(BluetoothChange)>(&self, on_change: F) -> zbus::Result<()> {
+        let rule = zbus::MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .sender("org.bluez")?
+            .build();
+        let mut signals = zbus::MessageStream::for_match_rule(rule, &self.conn, None).await?;
+
+        while let Some(msg) = signals.next().await {
+            let msg = match msg {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let header = msg.header();
+            let member = header.member().map(|m| m.as_str()).unwrap_or_default();
+
+            let change = match member {
+                "InterfacesAdded" | "InterfacesRemoved" => Some(BluetoothChange::Devices),
+                "PropertiesChanged" => {
+                    match msg.body().deserialize::<(String, std::collections::HashMap<String, zbus::zvariant::OwnedValue>, Vec<String>)>() {
+                        Ok((iface, changed, _)) => BluetoothChange::from_properties(&iface, &changed),
+                        Err(_) => None,
+                    }
+                }
+                _ => None,
+            };
+            if let Some(change) = change {
+                on_change(change);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a BlueZ signal invalidated, as reported by `watch_changes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BluetoothChange {
+    /// Devices appeared, went away or changed connection/pairing state.
+    Devices,
+    /// Only RSSI changed. These arrive constantly while discovering.
+    SignalStrength,
+    /// Adapter power or discoverability changed.
+    Adapter,
+}
+
+impl BluetoothChange {
+    fn from_properties(interface: &str, changed: &std::collections::HashMap<String, zbus::zvariant::OwnedValue>) -> Option<Self> {
+        match interface {
+            "org.bluez.Adapter1" if changed.contains_key("Powered") || changed.contains_key("Discoverable") => {
+                Some(BluetoothChange::Adapter)
+            }
+            "org.bluez.Device1" if changed.keys().all(|k| k == "RSSI") => Some(BluetoothChange::SignalStrength),
+            "org.bluez.Device1" | "org.bluez.Battery1" | "org.bluez.MediaTransport1" => Some(BluetoothChange::Devices),
+            // Position ticks along during playback; only track and play state matter here
+            "org.bluez.MediaPlayer1" if changed.contains_key("Track") || changed.contains_key("Status") => {
+                Some(BluetoothChange::Devices)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// AVRCP players in a `GetManagedObjects` reply. They live under their device,
+/// e.g. ".../dev_AA_BB_CC_DD_EE_FF/player0".
+fn media_players(objects: &std::collections::HashMap<zbus::zvariant::OwnedObjectPath, std::collections::HashMap<String, std::collections::HashMap<String, zbus::zvariant::OwnedValue>>>) -> Vec<MediaPlayer> {
+    objects.iter()
+        .filter_map(|(path, interfaces)| {
+            let props = interfaces.get("org.bluez.MediaPlayer1")?;
+            let track = props.get("Track")
+                .and_then(|v| v.try_clone().ok())
+                .and_then(|v| std::collections::HashMap::<String, zbus::zvariant::OwnedValue>::try_from(v).ok())
+                .unwrap_or_default();
+            let track_field = |key: &str| track.get(key)
+                .and_then(|v| <&str>::try_from(v).ok())
+                .unwrap_or_default()
+                .to_string();
+            Some(MediaPlayer {
+                path: path.to_string(),
+                status: props.get("Status")
+                    .and_then(|v| <&str>::try_from(v).ok())
+                    .unwrap_or_default()
+                    .to_string(),
+                title: track_field("Title"),
+                artist: track_field("Artist"),
+            })
+        })
+        .collect()
+}
+
+/// Maps a BlueZ device path (".../dev_AA_BB_CC_DD_EE_FF") to its sound server card name.
+fn card_name(path: &str) -> Option<String> {
+    let address = path.rsplit('/').next()?.strip_prefix("dev_")?;
+    Some(format!("bluez_card.{}", address))
+}
+
+/// Reads the available and active profiles of a device's card from `pactl list cards`.
+async fn get_card_profiles(path: &str) -> zbus::Result<CardProfiles> {
+    let card = card_name(path)
+        .ok_or_else(|| zbus::Error::Address("Not a Bluetooth device path".to_string()))?;
+    let output = tokio::process::Command::new("pactl")
+        .env("LC_ALL", "C")
+        .args(["list", "cards"])
+        .output()
+        .await
+        .map_err(|e| zbus::Error::Address(format!("Failed to run pactl: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(zbus::Error::Address(format!("Failed to list profiles: {}", stderr.trim())));
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+
+    let mut in_card = false;
+    let mut in_profiles = false;
+    let mut profiles = Vec::new();
+    let mut active = None;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if line.starts_with("Card #") {
+            if in_card {
+                break;
+            }
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix("Name: ") {
+            if line.starts_with('\t') && !line.starts_with("\t\t") {
+                in_card = name == card;
+            }
+            continue;
+        }
+        if !in_card {
+            continue;
+        }
+        if trimmed == "Profiles:" {
+            in_profiles = true;
+        } else if let Some(name) = trimmed.strip_prefix("Active Profile: ") {
+            active = Some(name.to_string());
+            in_profiles = false;
+        } else if in_profiles && line.starts_with("\t\t") {
+            // "a2dp-sink: High Fidelity Playback (A2DP Sink) (sinks: 1, ..., available: yes)"
+            if let Some((name, rest)) = trimmed.split_once(": ") {
+                if rest.contains("available: no") || name == "off" {
+                    continue;
+                }
+                let description = rest.rsplit_once(" (sinks:").map(|(d, _)| d).unwrap_or(rest);
+                profiles.push(AudioProfile {
+                    name: name.to_string(),
+                    description: description.to_string(),
+                });
+            }
+        } else if in_profiles {
+            in_profiles = false;
+        }
+    }
+    if !in_card && profiles.is_empty() {
+        return Err(zbus::Error::Address(format!("The sound server has no card for {}", card)));
+    }
+    Ok(CardProfiles { profiles, active })
 }
@@ -0,0 +1,455 @@
+use zbus::Connection;
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::network_manager::{self, AccessPoint, ConnectError, MacRandomMode, NetworkChange, SavedNetwork, SecurityType};
+
+const IWD: &str = "net.connman.iwd";
+const AGENT_PATH: &str = "/org/orbit/IwdAgent";
+
+type Properties = HashMap<String, zbus::zvariant::OwnedValue>;
+type ManagedObjects = HashMap<zbus::zvariant::OwnedObjectPath, HashMap<String, Properties>>;
+
+/// Answers iwd's passphrase requests with the password of the connect in progress.
+struct PassphraseAgent {
+    passphrase: Arc<Mutex<Option<String>>>,
+}
+
+#[zbus::interface(name = "net.connman.iwd.Agent")]
+impl PassphraseAgent {
+    fn release(&self) {}
+
+    fn request_passphrase(&self, _network: zbus::zvariant::OwnedObjectPath) -> zbus::fdo::Result<String> {
+        self.passphrase.lock().unwrap().clone()
+            .ok_or_else(|| zbus::fdo::Error::Failed("No passphrase available".to_string()))
+    }
+
+    fn cancel(&self, reason: String) {
+        log::info!("iwd cancelled the passphrase request: {}", reason);
+    }
+}
+
+/// WiFi through iwd's own D-Bus API, for systems that run iwd without NetworkManager.
+#[derive(Clone)]
+pub struct Iwd {
+    conn: Connection,
+    passphrase: Arc<Mutex<Option<String>>>,
+    cancel_connect: Arc<AtomicBool>,
+}
+
+impl Iwd {
+    pub async fn new() -> zbus::Result<Self> {
+        let conn = Connection::system().await?;
+        let running: bool = conn
+            .call_method(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                Some("org.freedesktop.DBus"),
+                "NameHasOwner",
+                &(IWD),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+        if !running {
+            return Err(zbus::Error::Address("iwd is not running".to_string()));
+        }
+
+        let passphrase = Arc::new(Mutex::new(None));
+        conn.object_server()
+            .at(AGENT_PATH, PassphraseAgent { passphrase: passphrase.clone() })
+            .await?;
+        let agent_path = zbus::zvariant::ObjectPath::try_from(AGENT_PATH).unwrap();
+        if let Err(e) = conn
+            .call_method(
+                Some(IWD),
+                "/net/connman/iwd",
+                Some("net.connman.iwd.AgentManager"),
+                "RegisterAgent",
+                &(agent_path),
+            )
+            .await
+        {
+            log::warn!("Failed to register iwd agent, new secured networks will not connect: {}", e);
+        }
+
+        Ok(Self { conn, passphrase, cancel_connect: Arc::new(AtomicBool::new(false)) })
+    }
+
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancel_connect = flag;
+    }
+
+    async fn objects(&self) -> zbus::Result<ManagedObjects> {
+        self.conn
+            .call_method(
+                Some(IWD),
+                "/",
+                Some("org.freedesktop.DBus.ObjectManager"),
+                "GetManagedObjects",
+                &(),
+            )
+            .await?
+            .body()
+            .deserialize()
+    }
+
+    async fn set_property(&self, path: &str, interface: &str, property: &str, value: zbus::zvariant::Value<'_>) -> zbus::Result<()> {
+        let path_obj: zbus::zvariant::ObjectPath = path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        self.conn
+            .call_method(
+                Some(IWD),
+                &path_obj,
+                Some("org.freedesktop.DBus.Properties"),
+                "Set",
+                &(interface, property, value),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn call(&self, path: &str, interface: &str, method: &str) -> zbus::Result<()> {
+        let path_obj: zbus::zvariant::ObjectPath = path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        self.conn
+            .call_method(Some(IWD), &path_obj, Some(interface), method, &())
+            .await?;
+        Ok(())
+    }
+
+    /// Object paths of devices in station (client) mode.
+    pub async fn get_wireless_devices(&self) -> zbus::Result<Vec<String>> {
+        let objects = self.objects().await?;
+        let mut stations: Vec<String> = objects.iter()
+            .filter(|(_, interfaces)| interfaces.contains_key("net.connman.iwd.Station"))
+            .map(|(path, _)| path.to_string())
+            .collect();
+        stations.sort();
+        Ok(stations)
+    }
+
+    pub async fn is_wifi_enabled(&self) -> zbus::Result<bool> {
+        let objects = self.objects().await?;
+        Ok(objects.values()
+            .filter_map(|interfaces| interfaces.get("net.connman.iwd.Device"))
+            .any(|device| prop_bool(device, "Powered").unwrap_or(false)))
+    }
+
+    pub async fn set_wifi_enabled(&self, enabled: bool) -> zbus::Result<()> {
+        let objects = self.objects().await?;
+        for (path, interfaces) in &objects {
+            if interfaces.contains_key("net.connman.iwd.Device") {
+                self.set_property(path.as_str(), "net.connman.iwd.Device", "Powered", zbus::zvariant::Value::Bool(enabled)).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn scan(&self) -> zbus::Result<()> {
+        for station in self.get_wireless_devices().await? {
+            self.call(&station, "net.connman.iwd.Station", "Scan").await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_active_ssid(&self) -> Option<String> {
+        let objects = self.objects().await.ok()?;
+        objects.values()
+            .filter_map(|interfaces| interfaces.get("net.connman.iwd.Network"))
+            .find(|network| prop_bool(network, "Connected").unwrap_or(false))
+            .and_then(|network| prop_str(network, "Name"))
+    }
+
+    pub async fn get_access_points(&self) -> zbus::Result<Vec<AccessPoint>> {
+        let objects = self.objects().await?;
+        let mut access_points = Vec::new();
+
+        for station in self.get_wireless_devices().await? {
+            let station_path: zbus::zvariant::ObjectPath = station.as_str().try_into()
+                .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+            // Strongest first, signal in 100 * dBm
+            let ordered: Vec<(zbus::zvariant::OwnedObjectPath, i16)> = self.conn
+                .call_method(
+                    Some(IWD),
+                    &station_path,
+                    Some("net.connman.iwd.Station"),
+                    "GetOrderedNetworks",
+                    &(),
+                )
+                .await?
+                .body()
+                .deserialize()?;
+            let interface = objects.get(&zbus::zvariant::OwnedObjectPath::from(station_path.clone()))
+                .and_then(|interfaces| interfaces.get("net.connman.iwd.Device"))
+                .and_then(|device| prop_str(device, "Name"))
+                .unwrap_or_default();
+
+            for (network_path, signal) in ordered {
+                let network = match objects.get(&network_path).and_then(|interfaces| interfaces.get("net.connman.iwd.Network")) {
+                    Some(n) => n,
+                    None => continue,
+                };
+                let ssid = match prop_str(network, "Name") {
+                    Some(s) if !s.is_empty() => s,
+                    _ => continue,
+                };
+                let security = match prop_str(network, "Type").as_deref() {
+                    Some("open") => SecurityType::None,
+                    Some("wep") => SecurityType::WEP,
+                    Some("8021x") => SecurityType::Enterprise,
+                    _ => SecurityType::WPA2,
+                };
+                access_points.push(AccessPoint {
+                    ssid,
+                    signal_strength: network_manager::dbm_to_strength(signal as i32 / 100),
+                    security,
+                    is_connected: prop_bool(network, "Connected").unwrap_or(false),
+                    device_path: station.clone(),
+                    path: network_path.to_string(),
+                    bssid: String::new(),
+                    frequency: 0,
+                    interface: interface.clone(),
+                    siblings: Vec::new(),
+                });
+            }
+        }
+
+        access_points.sort_by_key(|ap| std::cmp::Reverse(ap.signal_strength));
+
+        // Several stations can see the same network; keep the strongest as the row
+        let mut unique_aps: Vec<AccessPoint> = Vec::new();
+        for ap in access_points {
+            match unique_aps.iter_mut().find(|x| x.ssid == ap.ssid) {
+                Some(existing) => {
+                    if ap.is_connected {
+                        existing.is_connected = true;
+                    }
+                    existing.siblings.push(ap);
+                }
+                None => unique_aps.push(ap),
+            }
+        }
+        Ok(unique_aps)
+    }
+
+    async fn find_network(&self, ssid: &str, device_path: &str) -> zbus::Result<Option<String>> {
+        let objects = self.objects().await?;
+        Ok(objects.iter()
+            .filter_map(|(path, interfaces)| interfaces.get("net.connman.iwd.Network").map(|n| (path, n)))
+            .find(|(_, network)| {
+                prop_str(network, "Name").as_deref() == Some(ssid)
+                    && prop_path(network, "Device").as_deref() == Some(device_path)
+            })
+            .map(|(path, _)| path.to_string()))
+    }
+
+    /// Connects through `Network.Connect`, which returns once iwd has connected or given up.
+    /// The password is handed to iwd through the agent when it asks for one.
+    pub async fn connect_to_network(&self, ssid: &str, password: Option<&str>, device_path: &str, autoconnect: bool) -> Result<(), ConnectError> {
+        let network = self.find_network(ssid, device_path).await?
+            .ok_or(ConnectError::Failed(53))?;
+        let network_path: zbus::zvariant::ObjectPath = network.as_str().try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+
+        *self.passphrase.lock().unwrap() = password.map(|p| p.to_string());
+        let connect = self.conn.call_method(Some(IWD), &network_path, Some("net.connman.iwd.Network"), "Connect", &());
+        let result = tokio::select! {
+            result = tokio::time::timeout(tokio::time::Duration::from_secs(30), connect) => Some(result),
+            _ = self.wait_for_cancel() => None,
+        };
+        *self.passphrase.lock().unwrap() = None;
+
+        match result {
+            None => {
+                log::info!("Cancelling connection to {}", ssid);
+                if let Err(e) = self.call(device_path, "net.connman.iwd.Station", "Disconnect").await {
+                    log::warn!("Failed to abort connection to {}: {}", ssid, e);
+                }
+                Err(ConnectError::Cancelled)
+            }
+            Some(Err(_)) => Err(ConnectError::Timeout),
+            Some(Ok(Ok(_))) => {
+                // iwd always saves a new network with AutoConnect on
+                if !autoconnect {
+                    if let Err(e) = self.disable_autoconnect(&network).await {
+                        log::warn!("Failed to turn off autoconnect for {}: {}", ssid, e);
+                    }
+                }
+                Ok(())
+            }
+            Some(Ok(Err(zbus::Error::MethodError(name, _, _)))) if name.as_str() == "net.connman.iwd.Aborted" => {
+                Err(ConnectError::Cancelled)
+            }
+            // iwd reports a failed handshake as a plain failure
+            Some(Ok(Err(zbus::Error::MethodError(name, _, _)))) if name.as_str() == "net.connman.iwd.Failed" && password.is_some() => {
+                Err(ConnectError::WrongPassword)
+            }
+            Some(Ok(Err(e))) => Err(e.into()),
+        }
+    }
+
+    /// Turns off `AutoConnect` on the known network behind `network_path`.
+    async fn disable_autoconnect(&self, network_path: &str) -> zbus::Result<()> {
+        let objects = self.objects().await?;
+        let known = objects.iter()
+            .find(|(path, _)| path.as_str() == network_path)
+            .and_then(|(_, interfaces)| interfaces.get("net.connman.iwd.Network"))
+            .and_then(|network| prop_path(network, "KnownNetwork"));
+        match known {
+            Some(known) => self.set_autoconnect(&known, false).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn wait_for_cancel(&self) {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            if self.cancel_connect.swap(false, Ordering::SeqCst) {
+                return;
+            }
+        }
+    }
+
+    pub async fn disconnect_ap(&self, ssid: &str, _ap_path: &str) -> zbus::Result<()> {
+        let objects = self.objects().await?;
+        for interfaces in objects.values() {
+            let network = match interfaces.get("net.connman.iwd.Network") {
+                Some(n) => n,
+                None => continue,
+            };
+            if prop_bool(network, "Connected").unwrap_or(false) && prop_str(network, "Name").as_deref() == Some(ssid) {
+                if let Some(device) = prop_path(network, "Device") {
+                    self.call(&device, "net.connman.iwd.Station", "Disconnect").await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn has_saved_connection(&self, ssid: &str) -> bool {
+        match self.objects().await {
+            Ok(objects) => objects.values()
+                .filter_map(|interfaces| interfaces.get("net.connman.iwd.KnownNetwork"))
+                .any(|known| prop_str(known, "Name").as_deref() == Some(ssid)),
+            Err(_) => false,
+        }
+    }
+
+    pub async fn get_saved_networks(&self) -> zbus::Result<Vec<SavedNetwork>> {
+        let objects = self.objects().await?;
+        let active_known: Vec<String> = objects.values()
+            .filter_map(|interfaces| interfaces.get("net.connman.iwd.Network"))
+            .filter(|network| prop_bool(network, "Connected").unwrap_or(false))
+            .filter_map(|network| prop_path(network, "KnownNetwork"))
+            .collect();
+
+        let mut saved: Vec<SavedNetwork> = objects.iter()
+            .filter_map(|(path, interfaces)| interfaces.get("net.connman.iwd.KnownNetwork").map(|k| (path, k)))
+            .map(|(path, known)| SavedNetwork {
+                ssid: prop_str(known, "Name").unwrap_or_default(),
+                path: path.to_string(),
+                autoconnect: prop_bool(known, "AutoConnect").unwrap_or(true),
+                is_active: active_known.iter().any(|p| p == path.as_str()),
+                mac_randomization: MacRandomMode::default(),
+                metered: false,
+                priority: 0,
+                is_hidden: prop_bool(known, "Hidden").unwrap_or(false),
+            })
+            .collect();
+        saved.sort_by(|a, b| b.is_active.cmp(&a.is_active).then_with(|| a.ssid.cmp(&b.ssid)));
+        Ok(saved)
+    }
+
+    pub async fn forget_network(&self, path: &str) -> zbus::Result<()> {
+        self.call(path, "net.connman.iwd.KnownNetwork", "Forget").await
+    }
+
+    pub async fn set_autoconnect(&self, path: &str, autoconnect: bool) -> zbus::Result<()> {
+        self.set_property(path, "net.connman.iwd.KnownNetwork", "AutoConnect", zbus::zvariant::Value::Bool(autoconnect)).await
+    }
+
+    /// Calls `on_change` for each iwd signal that affects what Orbit shows.
+    /// Runs until the bus connection closes.
+    pub async fn watch_changes<F: Fn(NetworkChange)>(&self, on_change: F) -> zbus::Result<()> {
+        let rule = zbus::MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .sender(IWD)?
+            .build();
+        let mut signals = zbus::MessageStream::for_match_rule(rule, &self.conn, None).await?;
+
+        while let Some(msg) = signals.next().await {
+            let msg = match msg {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let header = msg.header();
+            let interface = header.interface().map(|i| i.as_str()).unwrap_or_default();
+            let member = header.member().map(|m| m.as_str()).unwrap_or_default();
+
+            let change = match (interface, member) {
+                ("org.freedesktop.DBus.ObjectManager", "InterfacesAdded") => {
+                    match msg.body().deserialize::<(zbus::zvariant::OwnedObjectPath, HashMap<String, Properties>)>() {
+                        Ok((_, added)) if added.contains_key("net.connman.iwd.KnownNetwork") => Some(NetworkChange::SavedConnections),
+                        Ok(_) => Some(NetworkChange::AccessPoints),
+                        Err(_) => None,
+                    }
+                }
+                ("org.freedesktop.DBus.ObjectManager", "InterfacesRemoved") => {
+                    match msg.body().deserialize::<(zbus::zvariant::OwnedObjectPath, Vec<String>)>() {
+                        Ok((_, removed)) if removed.iter().any(|i| i == "net.connman.iwd.KnownNetwork") => Some(NetworkChange::SavedConnections),
+                        Ok(_) => Some(NetworkChange::AccessPoints),
+                        Err(_) => None,
+                    }
+                }
+                ("org.freedesktop.DBus.Properties", "PropertiesChanged") => {
+                    match msg.body().deserialize::<(String, Properties, Vec<String>)>() {
+                        Ok((iface, changed, _)) => change_from_properties(&iface, &changed),
+                        Err(_) => None,
+                    }
+                }
+                _ => None,
+            };
+            if let Some(change) = change {
+                on_change(change);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn change_from_properties(interface: &str, changed: &Properties) -> Option<NetworkChange> {
+    match interface {
+        "net.connman.iwd.Device" if changed.contains_key("Powered") => Some(NetworkChange::Radio),
+        "net.connman.iwd.Station" if changed.contains_key("State") || changed.contains_key("ConnectedNetwork") => {
+            Some(NetworkChange::ActiveConnection)
+        }
+        // A finished scan has new results
+        "net.connman.iwd.Station" if changed.contains_key("Scanning") => Some(NetworkChange::AccessPoints),
+        "net.connman.iwd.KnownNetwork" => Some(NetworkChange::SavedConnections),
+        _ => None,
+    }
+}
+
+fn prop_str(props: &Properties, key: &str) -> Option<String> {
+    match props.get(key).map(|v| &**v) {
+        Some(zbus::zvariant::Value::Str(s)) => Some(s.to_string()),
+        _ => None,
+    }
+}
+
+fn prop_bool(props: &Properties, key: &str) -> Option<bool> {
+    match props.get(key).map(|v| &**v) {
+        Some(zbus::zvariant::Value::Bool(b)) => Some(*b),
+        _ => None,
+    }
+}
+
+fn prop_path(props: &Properties, key: &str) -> Option<String> {
+    match props.get(key).map(|v| &**v) {
+        Some(zbus::zvariant::Value::ObjectPath(p)) => Some(p.to_string()),
+        _ => None,
+    }
+}
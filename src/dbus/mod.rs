@@ -1,5 +1,11 @@
 pub mod network_manager;
+pub mod iwd;
+pub mod wifi;
 pub mod bluez;
+pub mod obex;
+pub mod portal;
+pub mod tray;
 
 pub use network_manager::{NetworkManager, SecurityType};
+pub use wifi::{Feature, WifiBackend};
 pub use bluez::BluetoothManager;
@@ -1,5 +1,8 @@
 use zbus::Connection;
+use futures_util::StreamExt;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AccessPoint {
@@ -9,6 +12,14 @@ pub struct AccessPoint {
     pub is_connected: bool,
     pub device_path: String,
     pub path: String,
+    pub bssid: String,
+    pub frequency: u32,
+    /// Interface name of the device that sees this access point, e.g. `wlan0`.
+    #[serde(default)]
+    pub interface: String,
+    /// Weaker access points broadcasting the same SSID, strongest first.
+    #[serde(default)]
+    pub siblings: Vec<AccessPoint>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -17,17 +28,51 @@ pub struct SavedNetwork {
     pub path: String,
     pub autoconnect: bool,
     pub is_active: bool,
+    #[serde(default)]
+    pub mac_randomization: MacRandomMode,
+    #[serde(default)]
+    pub metered: bool,
+    /// `connection.autoconnect-priority`; higher wins when several saved networks are in range.
+    #[serde(default)]
+    pub priority: i32,
+    /// `802-11-wireless.hidden`: the SSID is probed for because it is not broadcast.
+    #[serde(default)]
+    pub is_hidden: bool,
+}
+
+/// A saved WiFi profile in the portable form written by `orbit export`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ExportedNetwork {
+    pub ssid: String,
+    pub security: SecurityType,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default = "default_autoconnect")]
+    pub autoconnect: bool,
+    /// PSK, or the WEP key for WEP networks. Only present in exports made with secrets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub psk: Option<String>,
+}
+
+fn default_autoconnect() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct NetworkDetails {
     pub ssid: String,
     pub ip4_address: String,
+    /// First global IPv6 address, falling back to the link-local one; empty without IPv6.
+    #[serde(default)]
+    pub ip6_address: String,
     pub gateway: String,
     pub dns_servers: Vec<String>,
     pub mac_address: String,
     pub connection_speed: String,
+    pub frequency: u32,
     pub is_connected: bool,
+    #[serde(default)]
+    pub device_path: String,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -37,17 +82,190 @@ pub enum SecurityType {
     WPA,
     WPA2,
     WPA3,
+    Enterprise,
+}
+
+/// MAC address used when a saved connection activates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum MacRandomMode {
+    /// No `assigned-mac-address` on the profile, so NetworkManager's global default applies.
+    #[default]
+    Default,
+    Permanent,
+    Random,
+    Stable,
+}
+
+impl MacRandomMode {
+    pub const ALL: [MacRandomMode; 4] = [MacRandomMode::Default, MacRandomMode::Permanent, MacRandomMode::Random, MacRandomMode::Stable];
+
+    /// Value of `assigned-mac-address`, or `None` to leave it unset.
+    pub fn as_nm_str(&self) -> Option<&'static str> {
+        match self {
+            MacRandomMode::Default => None,
+            MacRandomMode::Permanent => Some("permanent"),
+            MacRandomMode::Random => Some("random"),
+            MacRandomMode::Stable => Some("stable"),
+        }
+    }
+
+    pub fn from_nm_str(value: &str) -> Option<Self> {
+        match value {
+            "permanent" => Some(MacRandomMode::Permanent),
+            "random" => Some(MacRandomMode::Random),
+            "stable" => Some(MacRandomMode::Stable),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MacRandomMode::Default => "Default MAC",
+            MacRandomMode::Permanent => "Permanent MAC",
+            MacRandomMode::Random => "Random MAC",
+            MacRandomMode::Stable => "Stable MAC",
+        }
+    }
+}
+
+/// IPv4 addressing for a saved connection.
+#[derive(Debug, Clone)]
+pub enum Ipv4Method {
+    Auto,
+    Manual {
+        address: String,
+        prefix: u32,
+        gateway: String,
+        dns: Vec<String>,
+    },
+}
+
+/// Credentials for WPA-Enterprise (802.1X) networks.
+#[derive(Debug, Clone, Default)]
+pub struct EnterpriseCredentials {
+    pub identity: String,
+    pub password: String,
+    /// EAP method as NetworkManager names it, e.g. "peap" or "ttls".
+    pub eap_method: String,
+    /// Inner authentication, e.g. "mschapv2".
+    pub phase2_auth: String,
+    pub anonymous_identity: Option<String>,
+}
+
+/// NM_DEVICE_STATE_FAILED
+const DEVICE_STATE_FAILED: u32 = 120;
+/// NM_DEVICE_STATE_REASON_NO_SECRETS
+const DEVICE_REASON_NO_SECRETS: u32 = 7;
+/// NMDeviceStateReason values worth another activation: IP configuration and DHCP
+/// failures (5, 6, 17-19) and supplicant disconnects/timeouts (8, 11).
+const TRANSIENT_DEVICE_REASONS: [u32; 7] = [5, 6, 8, 11, 17, 18, 19];
+/// NM_SETTING_WIRELESS_SECURITY_WPS_METHOD_PBC
+const WPS_METHOD_PBC: u32 = 4;
+/// NM_CONNECTIVITY_PORTAL
+pub const CONNECTIVITY_PORTAL: u32 = 2;
+/// NM_CONNECTIVITY_LIMITED
+const CONNECTIVITY_LIMITED: u32 = 3;
+/// NM_CONNECTIVITY_FULL
+const CONNECTIVITY_FULL: u32 = 4;
+/// NM_STATE_* values of the global `State` property.
+const NM_STATE_ASLEEP: u32 = 10;
+const NM_STATE_DISCONNECTED: u32 = 20;
+const NM_STATE_DISCONNECTING: u32 = 30;
+const NM_STATE_CONNECTING: u32 = 40;
+const NM_STATE_CONNECTED_LOCAL: u32 = 50;
+const NM_STATE_CONNECTED_SITE: u32 = 60;
+const NM_STATE_CONNECTED_GLOBAL: u32 = 70;
+
+/// How long a normal activation may take before it counts as timed out.
+const ACTIVATION_TIMEOUT_SECS: u64 = 15;
+/// How long a router's WPS button stays armed after it is pressed.
+pub const WPS_TIMEOUT_SECS: u64 = 120;
+
+/// Why a WiFi activation did not complete.
+#[derive(Debug)]
+pub enum ConnectError {
+    /// NetworkManager rejected the secrets: the password is wrong or missing.
+    WrongPassword,
+    /// The device never reported the network as active.
+    Timeout,
+    /// The device failed with the given `NMDeviceStateReason`.
+    Failed(u32),
+    /// The cancel flag was raised; the activation was torn down.
+    Cancelled,
+    DBus(zbus::Error),
+}
+
+impl std::fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectError::WrongPassword => write!(f, "Wrong password. Please try again."),
+            ConnectError::Timeout => write!(f, "Connection timeout"),
+            ConnectError::Failed(5) => write!(f, "No IP address was assigned"),
+            ConnectError::Failed(53) => write!(f, "No network found with that name"),
+            ConnectError::Failed(reason) => write!(f, "Activation failed (reason {})", reason),
+            ConnectError::Cancelled => write!(f, "Connection cancelled"),
+            ConnectError::DBus(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConnectError {}
+
+impl ConnectError {
+    /// Whether another activation might succeed: timeouts and transient device failures.
+    /// Rejected secrets and cancellations are final.
+    fn is_transient(&self) -> bool {
+        match self {
+            ConnectError::Timeout => true,
+            ConnectError::Failed(reason) => TRANSIENT_DEVICE_REASONS.contains(reason),
+            _ => false,
+        }
+    }
+}
+
+impl From<zbus::Error> for ConnectError {
+    fn from(e: zbus::Error) -> Self {
+        ConnectError::DBus(e)
+    }
 }
 
 #[derive(Clone)]
 pub struct NetworkManager {
     conn: Connection,
+    connect_retries: u32,
+    cancel_connect: Arc<AtomicBool>,
 }
 
 impl NetworkManager {
     pub async fn new() -> zbus::Result<Self> {
         let conn = Connection::system().await?;
-        Ok(Self { conn })
+        // The system bus is up even when NetworkManager is not, so ask for its name explicitly
+        let running: bool = conn
+            .call_method(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                Some("org.freedesktop.DBus"),
+                "NameHasOwner",
+                &("org.freedesktop.NetworkManager"),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+        if !running {
+            return Err(zbus::Error::Address("NetworkManager is not running".to_string()));
+        }
+        Ok(Self { conn, connect_retries: 0, cancel_connect: Arc::new(AtomicBool::new(false)) })
+    }
+
+    /// Number of times `connect_to_network` re-activates after a timeout or transient failure.
+    pub fn set_connect_retries(&mut self, retries: u32) {
+        self.connect_retries = retries;
+    }
+
+    /// Shares the flag that aborts a pending activation. It can be raised without holding
+    /// this instance, which stays busy for the whole connect; it is cleared once observed.
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancel_connect = flag;
     }
     
     pub async fn is_wifi_enabled(&self) -> zbus::Result<bool> {
@@ -95,6 +313,84 @@ impl NetworkManager {
         Ok(reply)
     }
     
+    /// The `Connectivity` property: NetworkManager's last probe result, without probing again.
+    pub async fn connectivity(&self) -> zbus::Result<u32> {
+        let reply = self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                "/org/freedesktop/NetworkManager",
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.freedesktop.NetworkManager", "Connectivity"),
+            )
+            .await?
+            .body()
+            .deserialize::<zbus::zvariant::OwnedValue>()?;
+        
+        u32::try_from(reply).map_err(zbus::Error::from)
+    }
+    
+    /// The global `State` property and the `Connectivity` property, for the header status line.
+    pub async fn network_state(&self) -> zbus::Result<(u32, u32)> {
+        let reply = self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                "/org/freedesktop/NetworkManager",
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.freedesktop.NetworkManager", "State"),
+            )
+            .await?
+            .body()
+            .deserialize::<zbus::zvariant::OwnedValue>()?;
+        
+        let state = u32::try_from(reply).map_err(zbus::Error::from)?;
+        Ok((state, self.connectivity().await?))
+    }
+    
+    /// Waits up to `timeout_secs` for a new connection to settle on a portal or full
+    /// connectivity. Right after activation DHCP may still be running and the state reads
+    /// "none" or "limited"; NetworkManager probes again by itself once addresses are up.
+    /// On timeout a fresh probe decides.
+    pub async fn wait_for_connectivity(&self, timeout_secs: u64) -> zbus::Result<u32> {
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(timeout_secs);
+        loop {
+            let state = self.connectivity().await?;
+            if state == CONNECTIVITY_PORTAL || state == CONNECTIVITY_FULL {
+                return Ok(state);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return self.check_connectivity().await;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        }
+    }
+    
+    /// The URI NetworkManager probes for connectivity, or `None` when checking is disabled.
+    pub async fn connectivity_check_uri(&self) -> zbus::Result<Option<String>> {
+        let reply = self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                "/org/freedesktop/NetworkManager",
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.freedesktop.NetworkManager", "ConnectivityCheckUri"),
+            )
+            .await?
+            .body()
+            .deserialize::<zbus::zvariant::OwnedValue>()?;
+        
+        let uri = String::try_from(reply).map_err(zbus::Error::from)?;
+        Ok(Some(uri).filter(|u| !u.is_empty()))
+    }
+    
+    /// The login page of a captive portal: where the portal redirects NetworkManager's
+    /// connectivity probe, or the probe URI itself, which the portal intercepts.
+    pub async fn captive_portal_url(&self) -> Option<String> {
+        let uri = self.connectivity_check_uri().await.ok()??;
+        Some(portal_redirect(&uri).await.unwrap_or(uri))
+    }
+    
     pub async fn scan(&self) -> zbus::Result<()> {
         let devices = self.get_wireless_devices().await?;
         
@@ -118,27 +414,87 @@ impl NetworkManager {
     pub async fn get_wifi_device_state(&self) -> zbus::Result<u32> {
         let devices = self.get_wireless_devices().await?;
         if let Some(device_path) = devices.get(0) {
-            let path: zbus::zvariant::ObjectPath = device_path.as_str().try_into()
+            return self.get_device_state(device_path).await;
+        }
+        Ok(0)
+    }
+
+    /// Returns cumulative (rx, tx) byte counters for a device, enabling NM's
+    /// statistics refresh on first use since it is off by default.
+    pub async fn get_device_statistics(&self, device_path: &str) -> zbus::Result<(u64, u64)> {
+        let refresh_rate = self.get_statistics_property(device_path, "RefreshRateMs").await
+            .ok()
+            .and_then(|ov| u32::try_from(zbus::zvariant::Value::from(ov)).ok())
+            .unwrap_or(0);
+        if refresh_rate == 0 || refresh_rate > 1000 {
+            let path: zbus::zvariant::ObjectPath = device_path.try_into()
                 .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
-            let reply = self.conn
+            self.conn
                 .call_method(
                     Some("org.freedesktop.NetworkManager"),
                     &path,
                     Some("org.freedesktop.DBus.Properties"),
-                    "Get",
-                    &("org.freedesktop.NetworkManager.Device", "State"),
+                    "Set",
+                    &("org.freedesktop.NetworkManager.Device.Statistics", "RefreshRateMs", zbus::zvariant::Value::U32(1000)),
                 )
-                .await?
-                .body()
-                .deserialize::<zbus::zvariant::OwnedValue>()?;
-            
-            let state: u32 = match u32::try_from(zbus::zvariant::Value::from(reply)) {
-                Ok(t) => t,
-                Err(_) => 0,
-            };
-            return Ok(state);
+                .await?;
         }
-        Ok(0)
+        
+        let rx = u64::try_from(zbus::zvariant::Value::from(self.get_statistics_property(device_path, "RxBytes").await?)).unwrap_or(0);
+        let tx = u64::try_from(zbus::zvariant::Value::from(self.get_statistics_property(device_path, "TxBytes").await?)).unwrap_or(0);
+        Ok((rx, tx))
+    }
+
+    async fn get_statistics_property(&self, device_path: &str, property: &str) -> zbus::Result<zbus::zvariant::OwnedValue> {
+        let path: zbus::zvariant::ObjectPath = device_path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        let reply = self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                &path,
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.freedesktop.NetworkManager.Device.Statistics", property),
+            )
+            .await?
+            .body()
+            .deserialize::<zbus::zvariant::OwnedValue>()?;
+        
+        Ok(reply)
+    }
+
+    pub async fn get_device_state(&self, device_path: &str) -> zbus::Result<u32> {
+        let path: zbus::zvariant::ObjectPath = device_path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        let reply = self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                &path,
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.freedesktop.NetworkManager.Device", "State"),
+            )
+            .await?
+            .body()
+            .deserialize::<zbus::zvariant::OwnedValue>()?;
+        
+        let state: u32 = match u32::try_from(zbus::zvariant::Value::from(reply)) {
+            Ok(t) => t,
+            Err(_) => 0,
+        };
+        Ok(state)
+    }
+
+    /// Fails fast when the device is unmanaged, unavailable (e.g. rfkill-blocked)
+    /// or failed, instead of letting an activation hang until the timeout.
+    async fn ensure_device_ready(&self, device_path: &str) -> zbus::Result<()> {
+        let state = self.get_device_state(device_path).await.unwrap_or(0);
+        // NM_DEVICE_STATE_DISCONNECTED (30) through ACTIVATED (100) can accept a new activation
+        if !(30..=100).contains(&state) {
+            log::warn!("WiFi device {} is not ready (state {}), skipping connect", device_path, state);
+            return Err(zbus::Error::Address("WiFi device unavailable".to_string()));
+        }
+        Ok(())
     }
 
     pub async fn get_wireless_devices(&self) -> zbus::Result<Vec<String>> {
@@ -190,6 +546,21 @@ impl NetworkManager {
         for device_path in devices {
             let path: zbus::zvariant::ObjectPath = device_path.as_str().try_into()
                 .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+            let interface: String = match self.conn
+                .call_method(
+                    Some("org.freedesktop.NetworkManager"),
+                    &path,
+                    Some("org.freedesktop.DBus.Properties"),
+                    "Get",
+                    &("org.freedesktop.NetworkManager.Device", "Interface"),
+                )
+                .await
+            {
+                Ok(reply) => reply.body().deserialize::<zbus::zvariant::OwnedValue>().ok()
+                    .and_then(|ov| String::try_from(zbus::zvariant::Value::from(ov)).ok())
+                    .unwrap_or_default(),
+                Err(_) => String::new(),
+            };
             let ap_paths: Vec<zbus::zvariant::OwnedObjectPath> = self.conn
                 .call_method(
                     Some("org.freedesktop.NetworkManager"),
@@ -228,10 +599,12 @@ impl NetworkManager {
                     continue;
                 }
                 
+                // A percentage, but nothing stops a driver from reporting more
                 let strength: u8 = self.get_ap_property(ap_path.as_str(), "Strength").await
                     .ok()
                     .and_then(|ov| u8::try_from(zbus::zvariant::Value::from(ov)).ok())
-                    .unwrap_or(0);
+                    .unwrap_or(0)
+                    .min(100);
                 let flags: u32 = self.get_ap_property(ap_path.as_str(), "Flags").await
                     .ok()
                     .and_then(|ov| u32::try_from(zbus::zvariant::Value::from(ov)).ok())
@@ -245,7 +618,10 @@ impl NetworkManager {
                     .and_then(|ov| u32::try_from(zbus::zvariant::Value::from(ov)).ok())
                     .unwrap_or(0);
                 
-                let security = if rsn_flags & 0x100 != 0 {
+                // NM_802_11_AP_SEC_KEY_MGMT_802_1X
+                let security = if (rsn_flags | wpa_flags) & 0x200 != 0 {
+                    SecurityType::Enterprise
+                } else if rsn_flags & 0x100 != 0 {
                     SecurityType::WPA3
                 } else if rsn_flags != 0 {
                     SecurityType::WPA2
@@ -257,6 +633,15 @@ impl NetworkManager {
                     SecurityType::None
                 };
                 
+                let frequency: u32 = self.get_ap_property(ap_path.as_str(), "Frequency").await
+                    .ok()
+                    .and_then(|ov| u32::try_from(zbus::zvariant::Value::from(ov)).ok())
+                    .unwrap_or(0);
+                let bssid: String = self.get_ap_property(ap_path.as_str(), "HwAddress").await
+                    .ok()
+                    .and_then(|ov| String::try_from(zbus::zvariant::Value::from(ov)).ok())
+                    .unwrap_or_default();
+                
                 let is_connected = active_ssid.as_ref() == Some(&ssid);
                 
                 access_points.push(AccessPoint {
@@ -266,6 +651,10 @@ impl NetworkManager {
                     is_connected,
                     device_path: device_path.clone(),
                     path: ap_path.to_string(),
+                    bssid,
+                    frequency,
+                    interface: interface.clone(),
+                    siblings: Vec::new(),
                 });
             }
         }
@@ -279,10 +668,13 @@ impl NetworkManager {
             if !seen_ssids.contains(&ap.ssid) {
                 seen_ssids.insert(ap.ssid.clone());
                 unique_aps.push(ap);
-            } else if ap.is_connected {
+            } else {
                 let existing = unique_aps.iter_mut().find(|x| x.ssid == ap.ssid);
                 if let Some(existing) = existing {
-                    existing.is_connected = true;
+                    if ap.is_connected {
+                        existing.is_connected = true;
+                    }
+                    existing.siblings.push(ap);
                 }
             }
         }
@@ -400,11 +792,60 @@ impl NetworkManager {
         None
     }
 
-    pub async fn connect_to_network(&self, ssid: &str, password: Option<&str>, device_path: &str) -> zbus::Result<()> {
+    /// Returns the stored PSK for a saved network, or `None` for open networks. Fails for
+    /// secured networks whose password NetworkManager does not hold.
+    pub async fn get_saved_psk(&self, ssid: &str) -> zbus::Result<Option<String>> {
+        let conn_path = self.find_connection_by_ssid(ssid).await
+            .ok_or_else(|| zbus::Error::Address(format!("No saved connection for {}", ssid)))?;
+        let path_obj: zbus::zvariant::ObjectPath = conn_path.as_str().try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+
+        let settings = self.get_connection_settings_from_path(&path_obj).await?;
+        let key_mgmt = settings.get("802-11-wireless-security")
+            .and_then(|wsec| wsec.get("key-mgmt"))
+            .and_then(|v| <&str>::try_from(&**v).ok())
+            .map(|s| s.to_string());
+        match key_mgmt.as_deref() {
+            None => return Ok(None),
+            Some("wpa-eap") => return Err(zbus::Error::Address("Enterprise networks cannot be shared".to_string())),
+            Some("none") => return Err(zbus::Error::Address("WEP networks cannot be shared".to_string())),
+            _ => {}
+        }
+
+        let secrets: HashMap<String, HashMap<String, zbus::zvariant::OwnedValue>> = self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                &path_obj,
+                Some("org.freedesktop.NetworkManager.Settings.Connection"),
+                "GetSecrets",
+                &("802-11-wireless-security"),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+
+        // Secured, so a missing psk means it is kept by a user's secret agent, not NetworkManager
+        let psk = secrets.get("802-11-wireless-security")
+            .and_then(|wsec| wsec.get("psk"))
+            .and_then(|v| <&str>::try_from(&**v).ok())
+            .map(|s| s.to_string());
+        match psk {
+            Some(psk) => Ok(Some(psk)),
+            None => Err(zbus::Error::Address("The password is not stored system-wide, so it cannot be shared".to_string())),
+        }
+    }
+
+    pub async fn connect_to_network(&self, ssid: &str, password: Option<&str>, device_path: &str, autoconnect: bool) -> Result<(), ConnectError> {
+        self.ensure_device_ready(device_path).await?;
+
         let dev_path: zbus::zvariant::ObjectPath = device_path.try_into()
             .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        let mut states = self.device_state_changes(device_path).await?;
+
+        let added_profile = if let Some(existing_path_str) = self.find_connection_by_ssid(ssid).await {
+            // Connecting by network rather than by AP undoes an earlier `connect_to_bssid`
+            self.set_pinned_bssid(&existing_path_str, None).await?;
 
-        if let Some(existing_path_str) = self.find_connection_by_ssid(ssid).await {
             let existing_path = zbus::zvariant::ObjectPath::try_from(existing_path_str.as_str()).unwrap();
             let specific_object = zbus::zvariant::ObjectPath::try_from("/").unwrap();
             
@@ -415,12 +856,13 @@ impl NetworkManager {
                 "ActivateConnection",
                 &(&existing_path, &dev_path, &specific_object),
             ).await?;
+            None
         } else {
             let mut connection: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
             connection.insert("type", "802-11-wireless".into());
             connection.insert("id", ssid.into());
             connection.insert("uuid", zbus::zvariant::Value::Str(uuid::Uuid::new_v4().to_string().into()));
-            connection.insert("autoconnect", true.into());
+            connection.insert("autoconnect", autoconnect.into());
             
             let mut wireless: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
             wireless.insert("ssid", ssid.as_bytes().into());
@@ -446,93 +888,425 @@ impl NetworkManager {
             ipv6.insert("method", "ignore".into());
             config.insert("ipv6", ipv6);
             
-            let specific_object = zbus::zvariant::ObjectPath::try_from("/").unwrap();
-            self.conn
-                .call_method(
-                    Some("org.freedesktop.NetworkManager"),
-                    "/org/freedesktop/NetworkManager",
-                    Some("org.freedesktop.NetworkManager"),
-                    "AddAndActivateConnection",
-                    &(&config, &dev_path, &specific_object),
-                )
-                .await?;
-        }
+            Some(self.add_and_activate(&config, &dev_path).await?)
+        };
         
-        let mut retries = 0;
-        while retries < 30 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-            if let Some(current) = self.get_active_ssid().await {
-                if current == ssid {
-                    return Ok(());
+        let mut attempt = 0;
+        loop {
+            match self.wait_for_activation(ssid, device_path, added_profile.as_deref(), &mut states).await {
+                Err(e) if e.is_transient() && attempt < self.connect_retries => {
+                    attempt += 1;
+                    let delay = 1000 * (1u64 << (attempt - 1).min(4));
+                    log::warn!("Connecting to {} failed ({}), retrying in {}ms ({}/{})", ssid, e, delay, attempt, self.connect_retries);
+                    tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+                    // The first attempt saved a profile, so re-activate it instead of adding another
+                    let existing = self.find_connection_by_ssid(ssid).await.ok_or(ConnectError::Timeout)?;
+                    let existing_path = zbus::zvariant::ObjectPath::try_from(existing.as_str())
+                        .map_err(|e| zbus::Error::Variant(e))?;
+                    let specific_object = zbus::zvariant::ObjectPath::try_from("/").unwrap();
+                    self.conn.call_method(
+                        Some("org.freedesktop.NetworkManager"),
+                        "/org/freedesktop/NetworkManager",
+                        Some("org.freedesktop.NetworkManager"),
+                        "ActivateConnection",
+                        &(&existing_path, &dev_path, &specific_object),
+                    ).await?;
                 }
+                result => return result,
             }
-            retries += 1;
         }
-        Err(zbus::Error::Address("Connection timeout".to_string()))
     }
 
-    pub async fn connect_hidden(&self, ssid: &str, password: Option<&str>, device_path: &str) -> zbus::Result<()> {
-        let mut connection: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
-        connection.insert("type", "802-11-wireless".into());
-        connection.insert("id", ssid.into());
-        connection.insert("uuid", zbus::zvariant::Value::Str(uuid::Uuid::new_v4().to_string().into()));
-        connection.insert("autoconnect", true.into());
-        
-        let mut wireless: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
-        wireless.insert("ssid", ssid.as_bytes().into());
-        wireless.insert("mode", "infrastructure".into());
-        wireless.insert("hidden", true.into());
-        
-        let mut config: HashMap<&str, HashMap<&str, zbus::zvariant::Value>> = HashMap::new();
-        config.insert("connection", connection);
-        config.insert("802-11-wireless", wireless);
-        
-        if let Some(pwd) = password {
-            let mut wsec: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
-            wsec.insert("key-mgmt", "wpa-psk".into());
-            wsec.insert("auth-alg", "open".into());
-            wsec.insert("psk", pwd.into());
-            config.insert("802-11-wireless-security", wsec);
-        }
-        
-        let mut ipv4: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
-        ipv4.insert("method", "auto".into());
-        config.insert("ipv4", ipv4);
-        
-        let mut ipv6: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
-        ipv6.insert("method", "ignore".into());
-        config.insert("ipv6", ipv6);
-        
-        let dev_path: zbus::zvariant::ObjectPath = device_path.try_into()
-            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
-        
+    /// Subscribes to the device's `StateChanged(new, old, reason)` signal. Subscribe before
+    /// activating so a quick failure is not missed.
+    async fn device_state_changes(&self, device_path: &str) -> zbus::Result<zbus::proxy::SignalStream<'static>> {
+        let proxy = zbus::Proxy::new(
+            &self.conn,
+            "org.freedesktop.NetworkManager",
+            device_path.to_string(),
+            "org.freedesktop.NetworkManager.Device",
+        ).await?;
+        proxy.receive_signal("StateChanged").await
+    }
+
+    /// Adds a new profile and activates it, returning the path of the saved profile.
+    async fn add_and_activate(
+        &self,
+        config: &HashMap<&str, HashMap<&str, zbus::zvariant::Value<'_>>>,
+        dev_path: &zbus::zvariant::ObjectPath<'_>,
+    ) -> zbus::Result<String> {
         let specific_object = zbus::zvariant::ObjectPath::try_from("/").unwrap();
+        self.add_and_activate_on(config, dev_path, &specific_object).await
+    }
 
-        self.conn
+    /// `add_and_activate` on a chosen access point (`specific_object`), or "/" for any.
+    async fn add_and_activate_on(
+        &self,
+        config: &HashMap<&str, HashMap<&str, zbus::zvariant::Value<'_>>>,
+        dev_path: &zbus::zvariant::ObjectPath<'_>,
+        specific_object: &zbus::zvariant::ObjectPath<'_>,
+    ) -> zbus::Result<String> {
+        let (profile, _active): (zbus::zvariant::OwnedObjectPath, zbus::zvariant::OwnedObjectPath) = self.conn
             .call_method(
                 Some("org.freedesktop.NetworkManager"),
                 "/org/freedesktop/NetworkManager",
                 Some("org.freedesktop.NetworkManager"),
                 "AddAndActivateConnection",
-                &(&config, &dev_path, &specific_object),
+                &(config, dev_path, specific_object),
             )
-            .await?;
-        Ok(())
+            .await?
+            .body()
+            .deserialize()?;
+        Ok(profile.to_string())
     }
-    
-    pub async fn disconnect_ap(&self, ssid: &str, _ap_path: &str) -> zbus::Result<()> {
-        let active_paths = self.get_active_connection_paths().await;
-        for path_str in active_paths {
-            let path = match zbus::zvariant::ObjectPath::try_from(path_str.as_str()) {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
 
-            let id_reply = self.conn
-                .call_method(
-                    Some("org.freedesktop.NetworkManager"),
-                    &path,
-                    Some("org.freedesktop.DBus.Properties"),
+    async fn wait_for_activation(
+        &self,
+        ssid: &str,
+        device_path: &str,
+        added_profile: Option<&str>,
+        states: &mut zbus::proxy::SignalStream<'static>,
+    ) -> Result<(), ConnectError> {
+        self.wait_for_activation_within(ssid, device_path, added_profile, states, ACTIVATION_TIMEOUT_SECS).await
+    }
+
+    /// Waits up to `timeout_secs` for `ssid` to become active. A device failure ends the
+    /// wait early with its state reason, so a rejected password is reported as such.
+    /// Raising the cancel flag deactivates the device and deletes `added_profile`, the
+    /// profile this attempt created, so an aborted connect leaves nothing behind.
+    async fn wait_for_activation_within(
+        &self,
+        ssid: &str,
+        device_path: &str,
+        added_profile: Option<&str>,
+        states: &mut zbus::proxy::SignalStream<'static>,
+        timeout_secs: u64,
+    ) -> Result<(), ConnectError> {
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(timeout_secs);
+        loop {
+            let tick = tokio::time::Instant::now() + tokio::time::Duration::from_millis(500);
+            match tokio::time::timeout_at(tick.min(deadline), states.next()).await {
+                Ok(Some(msg)) => {
+                    if let Ok((new_state, _old_state, reason)) = msg.body().deserialize::<(u32, u32, u32)>() {
+                        if new_state == DEVICE_STATE_FAILED {
+                            log::warn!("Activation of {} failed with reason {}", ssid, reason);
+                            return Err(if reason == DEVICE_REASON_NO_SECRETS {
+                                ConnectError::WrongPassword
+                            } else {
+                                ConnectError::Failed(reason)
+                            });
+                        }
+                    }
+                }
+                Ok(None) => tokio::time::sleep_until(tick.min(deadline)).await,
+                Err(_) => {}
+            }
+            if self.cancel_connect.swap(false, Ordering::SeqCst) {
+                log::info!("Cancelling activation of {}", ssid);
+                self.abort_activation(device_path, added_profile).await;
+                return Err(ConnectError::Cancelled);
+            }
+            if let Some(current) = self.get_active_ssid().await {
+                if current == ssid {
+                    return Ok(());
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(ConnectError::Timeout);
+            }
+        }
+    }
+
+    async fn abort_activation(&self, device_path: &str, added_profile: Option<&str>) {
+        let active = self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                device_path,
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &("org.freedesktop.NetworkManager.Device", "ActiveConnection"),
+            )
+            .await
+            .ok()
+            .and_then(|reply| reply.body().deserialize::<zbus::zvariant::OwnedValue>().ok())
+            .and_then(|v| zbus::zvariant::OwnedObjectPath::try_from(v).ok());
+        if let Some(active_path) = active.filter(|p| p.as_str() != "/") {
+            if let Err(e) = self.conn
+                .call_method(
+                    Some("org.freedesktop.NetworkManager"),
+                    "/org/freedesktop/NetworkManager",
+                    Some("org.freedesktop.NetworkManager"),
+                    "DeactivateConnection",
+                    &(&active_path),
+                )
+                .await
+            {
+                log::warn!("Failed to deactivate {}: {}", active_path.as_str(), e);
+            }
+        }
+        if let Some(profile) = added_profile {
+            if let Err(e) = self.forget_network(profile).await {
+                log::warn!("Failed to delete profile {}: {}", profile, e);
+            }
+        }
+    }
+
+    /// Connects to `ssid` through the access point at `ap_path` and pins the profile to
+    /// `bssid`, so NetworkManager won't roam away from it. A plain `connect_to_network`
+    /// clears the pin again.
+    pub async fn connect_to_bssid(&self, ssid: &str, bssid: &str, ap_path: &str, password: Option<&str>, device_path: &str, autoconnect: bool) -> Result<(), ConnectError> {
+        self.ensure_device_ready(device_path).await?;
+
+        let Some(bssid_bytes) = parse_mac(bssid) else {
+            return Err(zbus::Error::Address(format!("Invalid BSSID: {}", bssid)).into());
+        };
+        let dev_path: zbus::zvariant::ObjectPath = device_path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        let mut states = self.device_state_changes(device_path).await?;
+        let specific_object = zbus::zvariant::ObjectPath::try_from(ap_path)
+            .map_err(|e| zbus::Error::Variant(e))?;
+
+        let added_profile = if let Some(existing_path_str) = self.find_connection_by_ssid(ssid).await {
+            self.set_pinned_bssid(&existing_path_str, Some(bssid_bytes)).await?;
+
+            let existing_path = zbus::zvariant::ObjectPath::try_from(existing_path_str.as_str()).unwrap();
+            self.conn.call_method(
+                Some("org.freedesktop.NetworkManager"),
+                "/org/freedesktop/NetworkManager",
+                Some("org.freedesktop.NetworkManager"),
+                "ActivateConnection",
+                &(&existing_path, &dev_path, &specific_object),
+            ).await?;
+            None
+        } else {
+            let mut connection: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+            connection.insert("type", "802-11-wireless".into());
+            connection.insert("id", ssid.into());
+            connection.insert("uuid", zbus::zvariant::Value::Str(uuid::Uuid::new_v4().to_string().into()));
+            connection.insert("autoconnect", autoconnect.into());
+            
+            let mut wireless: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+            wireless.insert("ssid", ssid.as_bytes().into());
+            wireless.insert("mode", "infrastructure".into());
+            wireless.insert("bssid", bssid_bytes.into());
+            
+            let mut config: HashMap<&str, HashMap<&str, zbus::zvariant::Value>> = HashMap::new();
+            config.insert("connection", connection);
+            config.insert("802-11-wireless", wireless);
+            
+            if let Some(pwd) = password {
+                let mut wsec: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+                wsec.insert("key-mgmt", "wpa-psk".into());
+                wsec.insert("auth-alg", "open".into());
+                wsec.insert("psk", pwd.into());
+                config.insert("802-11-wireless-security", wsec);
+            }
+            
+            let mut ipv4: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+            ipv4.insert("method", "auto".into());
+            config.insert("ipv4", ipv4);
+            
+            let mut ipv6: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+            ipv6.insert("method", "ignore".into());
+            config.insert("ipv6", ipv6);
+            
+            Some(self.add_and_activate_on(&config, &dev_path, &specific_object).await?)
+        };
+
+        self.wait_for_activation(ssid, device_path, added_profile.as_deref(), &mut states).await
+    }
+
+    /// Sets or clears `802-11-wireless.bssid` on a saved profile, skipping the update when
+    /// it already matches.
+    async fn set_pinned_bssid(&self, path: &str, bssid: Option<Vec<u8>>) -> zbus::Result<()> {
+        let path_obj = zbus::zvariant::ObjectPath::try_from(path)
+            .map_err(|e| zbus::Error::Variant(e))?;
+        let settings = self.get_connection_settings_from_path(&path_obj).await?;
+        let current = settings.get("802-11-wireless")
+            .and_then(|w| w.get("bssid"))
+            .and_then(|v| match &**v {
+                zbus::zvariant::Value::Array(a) => Some(a.iter().filter_map(|iv| u8::try_from(iv).ok()).collect::<Vec<u8>>()),
+                _ => None,
+            });
+        if current == bssid {
+            return Ok(());
+        }
+        self.update_connection_settings(path, |settings| {
+            if let Some(wireless) = settings.get_mut("802-11-wireless") {
+                match bssid {
+                    Some(bytes) => {
+                        wireless.insert("bssid".to_string(), zbus::zvariant::Value::from(bytes));
+                    }
+                    None => {
+                        wireless.remove("bssid");
+                    }
+                }
+            }
+        }).await
+    }
+
+    pub async fn connect_enterprise(&self, ssid: &str, credentials: &EnterpriseCredentials, device_path: &str) -> Result<(), ConnectError> {
+        self.ensure_device_ready(device_path).await?;
+
+        let mut connection: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        connection.insert("type", "802-11-wireless".into());
+        connection.insert("id", ssid.into());
+        connection.insert("uuid", zbus::zvariant::Value::Str(uuid::Uuid::new_v4().to_string().into()));
+        connection.insert("autoconnect", true.into());
+        
+        let mut wireless: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        wireless.insert("ssid", ssid.as_bytes().into());
+        wireless.insert("mode", "infrastructure".into());
+        
+        let mut wsec: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        wsec.insert("key-mgmt", "wpa-eap".into());
+        
+        let mut dot1x: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        dot1x.insert("eap", vec![credentials.eap_method.as_str()].into());
+        dot1x.insert("identity", credentials.identity.as_str().into());
+        dot1x.insert("password", credentials.password.as_str().into());
+        dot1x.insert("phase2-auth", credentials.phase2_auth.as_str().into());
+        if let Some(ref anonymous) = credentials.anonymous_identity {
+            dot1x.insert("anonymous-identity", anonymous.as_str().into());
+        }
+        
+        let mut config: HashMap<&str, HashMap<&str, zbus::zvariant::Value>> = HashMap::new();
+        config.insert("connection", connection);
+        config.insert("802-11-wireless", wireless);
+        config.insert("802-11-wireless-security", wsec);
+        config.insert("802-1x", dot1x);
+        
+        let mut ipv4: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        ipv4.insert("method", "auto".into());
+        config.insert("ipv4", ipv4);
+        
+        let mut ipv6: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        ipv6.insert("method", "ignore".into());
+        config.insert("ipv6", ipv6);
+        
+        let dev_path: zbus::zvariant::ObjectPath = device_path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        let mut states = self.device_state_changes(device_path).await?;
+
+        let added_profile = self.add_and_activate(&config, &dev_path).await?;
+        
+        self.wait_for_activation(ssid, device_path, Some(&added_profile), &mut states).await
+    }
+
+    /// Joins `ssid` with WPS push-button: once the router's WPS button is pressed it hands
+    /// NetworkManager the key, which is saved in a new profile. A failed or timed out
+    /// attempt deletes that profile again.
+    pub async fn connect_wps(&self, ssid: &str, device_path: &str) -> Result<(), ConnectError> {
+        self.ensure_device_ready(device_path).await?;
+
+        let mut connection: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        connection.insert("type", "802-11-wireless".into());
+        connection.insert("id", ssid.into());
+        connection.insert("uuid", zbus::zvariant::Value::Str(uuid::Uuid::new_v4().to_string().into()));
+        connection.insert("autoconnect", true.into());
+        
+        let mut wireless: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        wireless.insert("ssid", ssid.as_bytes().into());
+        wireless.insert("mode", "infrastructure".into());
+        
+        let mut wsec: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        wsec.insert("key-mgmt", "wpa-psk".into());
+        wsec.insert("wps-method", WPS_METHOD_PBC.into());
+        
+        let mut config: HashMap<&str, HashMap<&str, zbus::zvariant::Value>> = HashMap::new();
+        config.insert("connection", connection);
+        config.insert("802-11-wireless", wireless);
+        config.insert("802-11-wireless-security", wsec);
+        
+        let mut ipv4: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        ipv4.insert("method", "auto".into());
+        config.insert("ipv4", ipv4);
+        
+        let mut ipv6: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        ipv6.insert("method", "ignore".into());
+        config.insert("ipv6", ipv6);
+        
+        let dev_path: zbus::zvariant::ObjectPath = device_path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        let mut states = self.device_state_changes(device_path).await?;
+
+        let added_profile = self.add_and_activate(&config, &dev_path).await?;
+        
+        let result = self.wait_for_activation_within(ssid, device_path, Some(&added_profile), &mut states, WPS_TIMEOUT_SECS).await;
+        match result {
+            // A cancelled wait already cleaned up
+            Ok(()) | Err(ConnectError::Cancelled) => result,
+            Err(e) => {
+                self.abort_activation(device_path, Some(&added_profile)).await;
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn connect_hidden(&self, ssid: &str, password: Option<&str>, device_path: &str) -> zbus::Result<()> {
+        self.ensure_device_ready(device_path).await?;
+
+        let mut connection: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        connection.insert("type", "802-11-wireless".into());
+        connection.insert("id", ssid.into());
+        connection.insert("uuid", zbus::zvariant::Value::Str(uuid::Uuid::new_v4().to_string().into()));
+        connection.insert("autoconnect", true.into());
+        
+        let mut wireless: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        wireless.insert("ssid", ssid.as_bytes().into());
+        wireless.insert("mode", "infrastructure".into());
+        wireless.insert("hidden", true.into());
+        
+        let mut config: HashMap<&str, HashMap<&str, zbus::zvariant::Value>> = HashMap::new();
+        config.insert("connection", connection);
+        config.insert("802-11-wireless", wireless);
+        
+        if let Some(pwd) = password {
+            let mut wsec: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+            wsec.insert("key-mgmt", "wpa-psk".into());
+            wsec.insert("auth-alg", "open".into());
+            wsec.insert("psk", pwd.into());
+            config.insert("802-11-wireless-security", wsec);
+        }
+        
+        let mut ipv4: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        ipv4.insert("method", "auto".into());
+        config.insert("ipv4", ipv4);
+        
+        let mut ipv6: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        ipv6.insert("method", "ignore".into());
+        config.insert("ipv6", ipv6);
+        
+        let dev_path: zbus::zvariant::ObjectPath = device_path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        
+        let specific_object = zbus::zvariant::ObjectPath::try_from("/").unwrap();
+
+        self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                "/org/freedesktop/NetworkManager",
+                Some("org.freedesktop.NetworkManager"),
+                "AddAndActivateConnection",
+                &(&config, &dev_path, &specific_object),
+            )
+            .await?;
+        Ok(())
+    }
+    
+    pub async fn disconnect_ap(&self, ssid: &str, _ap_path: &str) -> zbus::Result<()> {
+        let active_paths = self.get_active_connection_paths().await;
+        for path_str in active_paths {
+            let path = match zbus::zvariant::ObjectPath::try_from(path_str.as_str()) {
+                Ok(p) => p,
+                Err(_) => continue,
+            };
+
+            let id_reply = self.conn
+                .call_method(
+                    Some("org.freedesktop.NetworkManager"),
+                    &path,
+                    Some("org.freedesktop.DBus.Properties"),
                     "Get",
                     &("org.freedesktop.NetworkManager.Connection.Active", "Id"),
                 )
@@ -578,6 +1352,41 @@ impl NetworkManager {
         Ok(())
     }
 
+    /// Replaces the stored password of a saved WPA/WPA3 or WEP network, keeping its
+    /// autoconnect priority and every other setting. An active network is reactivated so
+    /// the new key is used straight away.
+    pub async fn update_psk(&self, path: &str, new_password: &str) -> zbus::Result<()> {
+        let path_obj: zbus::zvariant::ObjectPath = path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        let settings = self.get_connection_settings_from_path(&path_obj).await?;
+        let key_mgmt = settings.get("802-11-wireless-security")
+            .and_then(|wsec| wsec.get("key-mgmt"))
+            .and_then(|v| <&str>::try_from(&**v).ok());
+        let key = match key_mgmt {
+            Some("none") => "wep-key0",
+            Some("wpa-psk") | Some("sae") => "psk",
+            _ => return Err(zbus::Error::Address("This network has no password to change".to_string())),
+        };
+
+        let password = new_password.to_string();
+        self.update_connection_settings(path, move |settings| {
+            let wsec = settings.entry("802-11-wireless-security".to_string()).or_default();
+            wsec.insert(key.to_string(), zbus::zvariant::Value::from(password));
+        }).await?;
+
+        if self.get_active_connection_paths().await.iter().any(|p| p == path) {
+            let no_object = zbus::zvariant::ObjectPath::try_from("/").unwrap();
+            self.conn.call_method(
+                Some("org.freedesktop.NetworkManager"),
+                "/org/freedesktop/NetworkManager",
+                Some("org.freedesktop.NetworkManager"),
+                "ActivateConnection",
+                &(&path_obj, &no_object, &no_object),
+            ).await?;
+        }
+        Ok(())
+    }
+
     pub async fn get_saved_networks(&self) -> zbus::Result<Vec<SavedNetwork>> {
         let connections_reply = self.conn
             .call_method(
@@ -612,20 +1421,214 @@ impl NetworkManager {
                         
                         let is_active = active_connections.contains(&conn_path.to_string());
                         
+                        // "assigned-mac-address" is the string form of cloned-mac-address on D-Bus
+                        let mac_randomization = settings.get("802-11-wireless")
+                            .and_then(|w| w.get("assigned-mac-address"))
+                            .and_then(|v| <&str>::try_from(&**v).ok())
+                            .and_then(MacRandomMode::from_nm_str)
+                            .unwrap_or_default();
+                        
+                        // NMMetered: 1 = yes, 3 = guess-yes
+                        let metered = connection_map.get("metered")
+                            .and_then(|v| i32::try_from(&**v).ok())
+                            .map(|m| m == 1 || m == 3)
+                            .unwrap_or(false);
+                        
+                        let priority = connection_map.get("autoconnect-priority")
+                            .and_then(|v| i32::try_from(&**v).ok())
+                            .unwrap_or(0);
+                        
+                        let is_hidden = settings.get("802-11-wireless")
+                            .and_then(|w| w.get("hidden"))
+                            .and_then(|v| bool::try_from(&**v).ok())
+                            .unwrap_or(false);
+                        
                         saved_networks.push(SavedNetwork {
                             ssid: id,
                             path: conn_path.to_string(),
                             autoconnect,
                             is_active,
+                            mac_randomization,
+                            metered,
+                            priority,
+                            is_hidden,
                         });
                     }
                 }
             }
         }
-        saved_networks.sort_by(|a, b| b.is_active.cmp(&a.is_active).then_with(|| a.ssid.cmp(&b.ssid)));
+        saved_networks.sort_by(|a, b| {
+            b.is_active.cmp(&a.is_active)
+                .then_with(|| b.priority.cmp(&a.priority))
+                .then_with(|| a.ssid.cmp(&b.ssid))
+        });
         Ok(saved_networks)
     }
 
+    /// Saved WiFi profiles in a form `import_network` can recreate. Enterprise profiles are
+    /// left out since their EAP settings and certificates don't carry over. Keys are only
+    /// read with `with_secrets`; a key NetworkManager won't hand out is left empty.
+    pub async fn export_networks(&self, with_secrets: bool) -> zbus::Result<Vec<ExportedNetwork>> {
+        let connections: Vec<zbus::zvariant::OwnedObjectPath> = self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                "/org/freedesktop/NetworkManager/Settings",
+                Some("org.freedesktop.NetworkManager.Settings"),
+                "ListConnections",
+                &(),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+
+        let mut exported = Vec::new();
+        for conn_path in connections {
+            let settings = match self.get_connection_settings_raw(&conn_path).await {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            let connection_map = match settings.get("connection") {
+                Some(c) => c,
+                None => continue,
+            };
+            let conn_type = connection_map.get("type")
+                .and_then(|v| <&str>::try_from(&**v).ok())
+                .unwrap_or_default();
+            if conn_type != "802-11-wireless" {
+                continue;
+            }
+            let id = connection_map.get("id")
+                .and_then(|v| <&str>::try_from(&**v).ok())
+                .unwrap_or_default()
+                .to_string();
+
+            let wireless = settings.get("802-11-wireless");
+            let ssid = wireless
+                .and_then(|w| w.get("ssid"))
+                .and_then(|v| match &**v {
+                    zbus::zvariant::Value::Array(a) => {
+                        let bytes: Vec<u8> = a.iter().filter_map(|iv| u8::try_from(iv).ok()).collect();
+                        Some(String::from_utf8_lossy(&bytes).to_string())
+                    }
+                    _ => None,
+                })
+                .filter(|s| !s.is_empty())
+                .unwrap_or(id);
+
+            let key_mgmt = settings.get("802-11-wireless-security")
+                .and_then(|wsec| wsec.get("key-mgmt"))
+                .and_then(|v| <&str>::try_from(&**v).ok());
+            let security = match key_mgmt {
+                None => SecurityType::None,
+                Some("none") => SecurityType::WEP,
+                Some("sae") => SecurityType::WPA3,
+                Some("wpa-eap") | Some("wpa-eap-suite-b-192") => {
+                    log::info!("Not exporting enterprise network {}", ssid);
+                    continue;
+                }
+                Some(_) => SecurityType::WPA2,
+            };
+
+            let psk = match (with_secrets, secret_key(&security)) {
+                (true, Some(key)) => match self.get_secret(&conn_path, key).await {
+                    Ok(secret) => secret,
+                    Err(e) => {
+                        log::warn!("Failed to read the key of {}: {}", ssid, e);
+                        None
+                    }
+                },
+                _ => None,
+            };
+
+            exported.push(ExportedNetwork {
+                hidden: wireless
+                    .and_then(|w| w.get("hidden"))
+                    .and_then(|v| bool::try_from(&**v).ok())
+                    .unwrap_or(false),
+                autoconnect: connection_map.get("autoconnect")
+                    .and_then(|v| bool::try_from(&**v).ok())
+                    .unwrap_or(true),
+                ssid,
+                security,
+                psk,
+            });
+        }
+        exported.sort_by(|a, b| a.ssid.cmp(&b.ssid));
+        Ok(exported)
+    }
+
+    async fn get_secret(&self, path: &zbus::zvariant::OwnedObjectPath, key: &str) -> zbus::Result<Option<String>> {
+        let secrets: HashMap<String, HashMap<String, zbus::zvariant::OwnedValue>> = self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                path,
+                Some("org.freedesktop.NetworkManager.Settings.Connection"),
+                "GetSecrets",
+                &("802-11-wireless-security"),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+        Ok(secrets.get("802-11-wireless-security")
+            .and_then(|wsec| wsec.get(key))
+            .and_then(|v| <&str>::try_from(&**v).ok())
+            .map(|s| s.to_string()))
+    }
+
+    /// Saves `network` as a new profile without activating it. Returns `false` and changes
+    /// nothing when a profile for the SSID already exists.
+    pub async fn import_network(&self, network: &ExportedNetwork) -> zbus::Result<bool> {
+        if self.find_connection_by_ssid(&network.ssid).await.is_some() {
+            return Ok(false);
+        }
+
+        let mut connection: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        connection.insert("type", "802-11-wireless".into());
+        connection.insert("id", network.ssid.as_str().into());
+        connection.insert("uuid", zbus::zvariant::Value::Str(uuid::Uuid::new_v4().to_string().into()));
+        connection.insert("autoconnect", network.autoconnect.into());
+
+        let mut wireless: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        wireless.insert("ssid", network.ssid.as_bytes().into());
+        wireless.insert("mode", "infrastructure".into());
+        if network.hidden {
+            wireless.insert("hidden", true.into());
+        }
+
+        let mut config: HashMap<&str, HashMap<&str, zbus::zvariant::Value>> = HashMap::new();
+        config.insert("connection", connection);
+        config.insert("802-11-wireless", wireless);
+
+        let key_mgmt = match network.security {
+            SecurityType::None => None,
+            SecurityType::WEP => Some("none"),
+            SecurityType::WPA | SecurityType::WPA2 => Some("wpa-psk"),
+            SecurityType::WPA3 => Some("sae"),
+            SecurityType::Enterprise => {
+                return Err(zbus::Error::Address("Enterprise networks cannot be imported".to_string()));
+            }
+        };
+        if let Some(key_mgmt) = key_mgmt {
+            let mut wsec: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+            wsec.insert("key-mgmt", key_mgmt.into());
+            if let (Some(key), Some(psk)) = (secret_key(&network.security), network.psk.as_deref()) {
+                wsec.insert(key, psk.into());
+            }
+            config.insert("802-11-wireless-security", wsec);
+        }
+
+        self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                "/org/freedesktop/NetworkManager/Settings",
+                Some("org.freedesktop.NetworkManager.Settings"),
+                "AddConnection",
+                &(&config),
+            )
+            .await?;
+        Ok(true)
+    }
+
     async fn get_connection_settings_raw(&self, path: &zbus::zvariant::OwnedObjectPath) -> zbus::Result<HashMap<String, HashMap<String, zbus::zvariant::OwnedValue>>> {
         self.conn
             .call_method(
@@ -675,10 +1678,204 @@ impl NetworkManager {
     }
     
     pub async fn set_autoconnect(&self, path: &str, autoconnect: bool) -> zbus::Result<()> {
+        self.update_connection_settings(path, |settings| {
+            if let Some(conn_group) = settings.get_mut("connection") {
+                conn_group.insert("autoconnect".to_string(), zbus::zvariant::Value::Bool(autoconnect));
+            }
+        }).await
+    }
+
+    pub async fn set_metered(&self, path: &str, metered: bool) -> zbus::Result<()> {
+        let value: i32 = if metered { 1 } else { 2 };
+        self.update_connection_settings(path, move |settings| {
+            if let Some(conn_group) = settings.get_mut("connection") {
+                conn_group.insert("metered".to_string(), zbus::zvariant::Value::I32(value));
+            }
+        }).await
+    }
+
+    pub async fn set_autoconnect_priority(&self, path: &str, priority: i32) -> zbus::Result<()> {
+        self.update_connection_settings(path, move |settings| {
+            if let Some(conn_group) = settings.get_mut("connection") {
+                conn_group.insert("autoconnect-priority".to_string(), zbus::zvariant::Value::I32(priority));
+            }
+        }).await
+    }
+
+    pub async fn set_mac_randomization(&self, path: &str, mode: MacRandomMode) -> zbus::Result<()> {
+        self.update_connection_settings(path, move |settings| {
+            let wireless = settings.entry("802-11-wireless".to_string()).or_default();
+            // Drop the legacy byte-array and randomization keys so they can't override the new mode
+            wireless.remove("cloned-mac-address");
+            wireless.remove("mac-address-randomization");
+            match mode.as_nm_str() {
+                Some(value) => {
+                    wireless.insert("assigned-mac-address".to_string(), zbus::zvariant::Value::from(value));
+                }
+                None => {
+                    wireless.remove("assigned-mac-address");
+                }
+            }
+        }).await
+    }
+
+    pub async fn set_ipv4_manual(&self, path: &str, address: &str, prefix: u32, gateway: &str, dns: Vec<String>) -> zbus::Result<()> {
+        let address: std::net::Ipv4Addr = address.trim().parse()
+            .map_err(|_| zbus::Error::Address(format!("Invalid IPv4 address: {}", address)))?;
+        if !(1..=32).contains(&prefix) {
+            return Err(zbus::Error::Address(format!("Invalid prefix length: {}", prefix)));
+        }
+        let gateway = gateway.trim();
+        if !gateway.is_empty() && gateway.parse::<std::net::Ipv4Addr>().is_err() {
+            return Err(zbus::Error::Address(format!("Invalid gateway: {}", gateway)));
+        }
+        // NM stores IPv4 nameservers as u32 in network byte order
+        let mut dns_servers: Vec<u32> = Vec::new();
+        for server in &dns {
+            let ip: std::net::Ipv4Addr = server.trim().parse()
+                .map_err(|_| zbus::Error::Address(format!("Invalid DNS server: {}", server)))?;
+            dns_servers.push(u32::from_ne_bytes(ip.octets()));
+        }
+
+        let gateway = gateway.to_string();
+        self.update_connection_settings(path, move |settings| {
+            let ipv4 = settings.entry("ipv4".to_string()).or_default();
+            // The deprecated "addresses" key takes precedence over "address-data" when present
+            ipv4.remove("addresses");
+            ipv4.remove("dns-data");
+            ipv4.insert("method".to_string(), zbus::zvariant::Value::from("manual"));
+
+            let mut address_entry: HashMap<String, zbus::zvariant::Value> = HashMap::new();
+            address_entry.insert("address".to_string(), zbus::zvariant::Value::from(address.to_string()));
+            address_entry.insert("prefix".to_string(), zbus::zvariant::Value::U32(prefix));
+            ipv4.insert("address-data".to_string(), zbus::zvariant::Value::from(vec![address_entry]));
+
+            if gateway.is_empty() {
+                ipv4.remove("gateway");
+            } else {
+                ipv4.insert("gateway".to_string(), zbus::zvariant::Value::from(gateway));
+            }
+            ipv4.insert("dns".to_string(), zbus::zvariant::Value::from(dns_servers));
+        }).await
+    }
+
+    /// The IPv4 addressing saved on a connection, in the form `set_ipv4_manual` takes.
+    /// Methods other than "manual" read as `Auto`; only the first address is returned.
+    pub async fn get_ipv4_settings(&self, path: &str) -> zbus::Result<Ipv4Method> {
+        let path = zbus::zvariant::ObjectPath::try_from(path)?;
+        let settings = self.get_connection_settings_from_path(&path).await?;
+        let ipv4 = match settings.get("ipv4") {
+            Some(ipv4) => ipv4,
+            None => return Ok(Ipv4Method::Auto),
+        };
+        let method = ipv4.get("method").and_then(|v| <&str>::try_from(&**v).ok());
+        if method != Some("manual") {
+            return Ok(Ipv4Method::Auto);
+        }
+
+        let mut address = String::new();
+        let mut prefix = 24;
+        if let Some(zbus::zvariant::Value::Array(entries)) = ipv4.get("address-data").map(|v| &**v) {
+            let first = entries.iter()
+                .filter_map(|entry| zbus::zvariant::OwnedValue::try_from(entry).ok())
+                .find_map(|entry| HashMap::<String, zbus::zvariant::OwnedValue>::try_from(entry).ok());
+            if let Some(entry) = first {
+                if let Some(addr) = entry.get("address").and_then(|v| <&str>::try_from(&**v).ok()) {
+                    address = addr.to_string();
+                }
+                if let Some(p) = entry.get("prefix").and_then(|v| u32::try_from(&**v).ok()) {
+                    prefix = p;
+                }
+            }
+        }
+        let gateway = ipv4.get("gateway")
+            .and_then(|v| <&str>::try_from(&**v).ok())
+            .unwrap_or_default()
+            .to_string();
+        // NM stores IPv4 nameservers as u32 in network byte order
+        let mut dns = Vec::new();
+        if let Some(zbus::zvariant::Value::Array(servers)) = ipv4.get("dns").map(|v| &**v) {
+            for server in servers.iter() {
+                if let Ok(server) = u32::try_from(server) {
+                    dns.push(std::net::Ipv4Addr::from(server.to_ne_bytes()).to_string());
+                }
+            }
+        }
+        Ok(Ipv4Method::Manual { address, prefix, gateway, dns })
+    }
+
+    pub async fn set_ipv4_auto(&self, path: &str) -> zbus::Result<()> {
+        self.update_connection_settings(path, |settings| {
+            let ipv4 = settings.entry("ipv4".to_string()).or_default();
+            for key in ["addresses", "address-data", "gateway", "dns", "dns-data"] {
+                ipv4.remove(key);
+            }
+            ipv4.insert("method".to_string(), zbus::zvariant::Value::from("auto"));
+        }).await
+    }
+
+    /// Overrides the DNS servers of a saved connection, ignoring those handed out by DHCP.
+    pub async fn set_dns(&self, path: &str, servers: Vec<String>) -> zbus::Result<()> {
+        if servers.is_empty() {
+            return Err(zbus::Error::Address("No DNS servers given".to_string()));
+        }
+        let mut dns_servers: Vec<u32> = Vec::new();
+        for server in &servers {
+            let ip: std::net::Ipv4Addr = server.trim().parse()
+                .map_err(|_| zbus::Error::Address(format!("Invalid DNS server: {}", server)))?;
+            dns_servers.push(u32::from_ne_bytes(ip.octets()));
+        }
+
+        self.update_connection_settings(path, move |settings| {
+            let ipv4 = settings.entry("ipv4".to_string()).or_default();
+            ipv4.remove("dns-data");
+            ipv4.insert("dns".to_string(), zbus::zvariant::Value::from(dns_servers));
+            ipv4.insert("ignore-auto-dns".to_string(), zbus::zvariant::Value::from(true));
+        }).await
+    }
+
+    /// Drops a DNS override set by `set_dns`, going back to the servers from DHCP.
+    pub async fn clear_dns(&self, path: &str) -> zbus::Result<()> {
+        self.update_connection_settings(path, |settings| {
+            let ipv4 = settings.entry("ipv4".to_string()).or_default();
+            ipv4.remove("dns");
+            ipv4.remove("dns-data");
+            ipv4.insert("ignore-auto-dns".to_string(), zbus::zvariant::Value::from(false));
+        }).await
+    }
+
+    /// Path of the saved connection for `ssid`, if there is one.
+    pub async fn get_connection_path(&self, ssid: &str) -> Option<String> {
+        self.find_connection_by_ssid(ssid).await
+    }
+
+    /// Applies the device's updated connection profile without reconnecting.
+    pub async fn reapply(&self, device_path: &str) -> zbus::Result<()> {
+        let dev_path: zbus::zvariant::ObjectPath = device_path.try_into()
+            .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
+        // An empty settings dict tells NM to reapply the saved profile
+        let settings: HashMap<&str, HashMap<&str, zbus::zvariant::Value>> = HashMap::new();
+        self.conn
+            .call_method(
+                Some("org.freedesktop.NetworkManager"),
+                &dev_path,
+                Some("org.freedesktop.NetworkManager.Device"),
+                "Reapply",
+                &(&settings, 0u64, 0u32),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Reads a saved connection's settings, lets `edit` modify them and writes them back.
+    async fn update_connection_settings<F>(&self, path: &str, edit: F) -> zbus::Result<()>
+    where
+        F: FnOnce(&mut HashMap<String, HashMap<String, zbus::zvariant::Value<'static>>>),
+    {
         let path_obj: zbus::zvariant::ObjectPath = path.try_into()
             .map_err(|e: zbus::zvariant::Error| zbus::Error::Variant(e))?;
         let current_settings = self.get_connection_settings_from_path(&path_obj).await?;
-        let mut new_settings: HashMap<String, HashMap<String, zbus::zvariant::Value>> = HashMap::new();
+        let mut new_settings: HashMap<String, HashMap<String, zbus::zvariant::Value<'static>>> = HashMap::new();
         for (group_name, group_settings) in current_settings {
             let mut new_group: HashMap<String, zbus::zvariant::Value> = HashMap::new();
             for (key, value) in group_settings {
@@ -686,9 +1883,7 @@ impl NetworkManager {
             }
             new_settings.insert(group_name, new_group);
         }
-        if let Some(conn_group) = new_settings.get_mut("connection") {
-            conn_group.insert("autoconnect".to_string(), zbus::zvariant::Value::Bool(autoconnect));
-        }
+        edit(&mut new_settings);
         self.conn
             .call_method(
                 Some("org.freedesktop.NetworkManager"),
@@ -728,6 +1923,26 @@ impl NetworkManager {
             
             if id == ssid {
                 details.is_connected = true;
+
+                let ap_reply: zbus::zvariant::OwnedValue = self.conn
+                    .call_method(
+                        Some("org.freedesktop.NetworkManager"),
+                        &path,
+                        Some("org.freedesktop.DBus.Properties"),
+                        "Get",
+                        &("org.freedesktop.NetworkManager.Connection.Active", "SpecificObject"),
+                    )
+                    .await?
+                    .body()
+                    .deserialize()?;
+                if let Ok(ap_path) = zbus::zvariant::OwnedObjectPath::try_from(ap_reply) {
+                    if ap_path.as_str() != "/" {
+                        details.frequency = self.get_ap_property(ap_path.as_str(), "Frequency").await
+                            .ok()
+                            .and_then(|ov| u32::try_from(zbus::zvariant::Value::from(ov)).ok())
+                            .unwrap_or(0);
+                    }
+                }
                 let ip4_val_reply: zbus::zvariant::OwnedValue = self.conn
                     .call_method(
                         Some("org.freedesktop.NetworkManager"),
@@ -809,6 +2024,54 @@ impl NetworkManager {
                     }
                 }
                 
+                let ip6_val_reply: zbus::zvariant::OwnedValue = self.conn
+                    .call_method(
+                        Some("org.freedesktop.NetworkManager"),
+                        &path,
+                        Some("org.freedesktop.DBus.Properties"),
+                        "Get",
+                        &("org.freedesktop.NetworkManager.Connection.Active", "Ip6Config"),
+                    )
+                    .await?
+                    .body()
+                    .deserialize()?;
+                
+                let ip6_path = zbus::zvariant::OwnedObjectPath::try_from(ip6_val_reply).unwrap_or_else(|_| "/".try_into().unwrap());
+                
+                if ip6_path.as_str() != "/" {
+                    let addr_reply_val: zbus::zvariant::OwnedValue = self.conn
+                        .call_method(
+                            Some("org.freedesktop.NetworkManager"),
+                            &ip6_path,
+                            Some("org.freedesktop.DBus.Properties"),
+                            "Get",
+                            &("org.freedesktop.NetworkManager.IP6Config", "AddressData"),
+                        )
+                        .await?
+                        .body()
+                        .deserialize()?;
+                    
+                    let mut addresses: Vec<String> = Vec::new();
+                    let val: zbus::zvariant::Value = addr_reply_val.into();
+                    if let zbus::zvariant::Value::Array(a) = val {
+                        for iv in a.iter() {
+                            let owned_iv = zbus::zvariant::OwnedValue::try_from(iv).expect("Value should be convertible to OwnedValue");
+                            if let Ok(map) = HashMap::<String, zbus::zvariant::OwnedValue>::try_from(owned_iv) {
+                                if let Some(address_v) = map.get("address") {
+                                    if let Ok(addr_str) = <&str>::try_from(&**address_v) {
+                                        addresses.push(addr_str.to_string());
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    details.ip6_address = addresses.iter()
+                        .find(|addr| !addr.to_lowercase().starts_with("fe80:"))
+                        .or(addresses.first())
+                        .cloned()
+                        .unwrap_or_default();
+                }
+                
                 let dev_reply_val: zbus::zvariant::OwnedValue = self.conn
                     .call_method(
                         Some("org.freedesktop.NetworkManager"),
@@ -838,6 +2101,26 @@ impl NetworkManager {
                                 .body()
                                 .deserialize()?;
                             details.mac_address = String::try_from(zbus::zvariant::Value::from(hw_val_reply)).unwrap_or_default();
+                            details.device_path = device_path.to_string();
+                            
+                            // Bitrate is reported in kbit/s
+                            let bitrate_reply = self.conn
+                                .call_method(
+                                    Some("org.freedesktop.NetworkManager"),
+                                    &device_path,
+                                    Some("org.freedesktop.DBus.Properties"),
+                                    "Get",
+                                    &("org.freedesktop.NetworkManager.Device.Wireless", "Bitrate"),
+                                )
+                                .await;
+                            if let Ok(reply) = bitrate_reply {
+                                if let Ok(bitrate_val) = reply.body().deserialize::<zbus::zvariant::OwnedValue>() {
+                                    let kbps = u32::try_from(zbus::zvariant::Value::from(bitrate_val)).unwrap_or(0);
+                                    if kbps > 0 {
+                                        details.connection_speed = format!("{} Mbps", kbps / 1000);
+                                    }
+                                }
+                            }
                             break;
                         }
                     }
@@ -847,4 +2130,221 @@ impl NetworkManager {
         }
         Ok(details)
     }
+
+    /// Calls `on_change` for each NetworkManager signal that affects what Orbit shows.
+    /// Runs until the bus connection closes.
+    pub async fn watch_changes<F: Fn(NetworkChange)>(&self, on_change: F) -> zbus::Result<()> {
+        let rule = zbus::MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .sender("org.freedesktop.NetworkManager")?
+            .build();
+        let mut signals = zbus::MessageStream::for_match_rule(rule, &self.conn, None).await?;
+
+        while let Some(msg) = signals.next().await {
+            let msg = match msg {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let header = msg.header();
+            let interface = header.interface().map(|i| i.as_str()).unwrap_or_default();
+            let member = header.member().map(|m| m.as_str()).unwrap_or_default();
+
+            let change = match (interface, member) {
+                ("org.freedesktop.NetworkManager.Device.Wireless", "AccessPointAdded" | "AccessPointRemoved") => {
+                    Some(NetworkChange::AccessPoints)
+                }
+                ("org.freedesktop.NetworkManager", "DeviceAdded" | "DeviceRemoved") => Some(NetworkChange::AccessPoints),
+                ("org.freedesktop.NetworkManager.Settings", "NewConnection" | "ConnectionRemoved")
+                | ("org.freedesktop.NetworkManager.Settings.Connection", "Updated") => {
+                    Some(NetworkChange::SavedConnections)
+                }
+                ("org.freedesktop.DBus.Properties", "PropertiesChanged") => {
+                    match msg.body().deserialize::<(String, HashMap<String, zbus::zvariant::OwnedValue>, Vec<String>)>() {
+                        Ok((iface, changed, _)) => NetworkChange::from_properties(&iface, &changed),
+                        Err(_) => None,
+                    }
+                }
+                _ => None,
+            };
+            if let Some(change) = change {
+                on_change(change);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a NetworkManager signal invalidated, as reported by `watch_changes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NetworkChange {
+    /// Access points appeared or went away, or the device changed state.
+    AccessPoints,
+    /// Only access point signal strength changed. These arrive constantly.
+    SignalStrength,
+    /// The active connection changed, which affects both the WiFi and saved lists.
+    ActiveConnection,
+    SavedConnections,
+    /// The WiFi radio was switched on or off.
+    Radio,
+    /// NetworkManager's global state or connectivity changed.
+    State,
+}
+
+impl NetworkChange {
+    fn from_properties(interface: &str, changed: &HashMap<String, zbus::zvariant::OwnedValue>) -> Option<Self> {
+        match interface {
+            "org.freedesktop.NetworkManager" if changed.contains_key("WirelessEnabled") => Some(NetworkChange::Radio),
+            "org.freedesktop.NetworkManager" if changed.contains_key("ActiveConnections") => {
+                Some(NetworkChange::ActiveConnection)
+            }
+            "org.freedesktop.NetworkManager" if changed.contains_key("State") || changed.contains_key("Connectivity") => {
+                Some(NetworkChange::State)
+            }
+            "org.freedesktop.NetworkManager.Device" if changed.contains_key("State") => Some(NetworkChange::AccessPoints),
+            "org.freedesktop.NetworkManager.AccessPoint" if changed.contains_key("Strength") => {
+                Some(NetworkChange::SignalStrength)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Setting in `802-11-wireless-security` that holds the key for `security`.
+fn secret_key(security: &SecurityType) -> Option<&'static str> {
+    match security {
+        SecurityType::WEP => Some("wep-key0"),
+        SecurityType::WPA | SecurityType::WPA2 | SecurityType::WPA3 => Some("psk"),
+        SecurityType::None | SecurityType::Enterprise => None,
+    }
+}
+
+/// Parses a colon-separated MAC address ("AA:BB:CC:DD:EE:FF") into its 6 raw bytes.
+fn parse_mac(mac: &str) -> Option<Vec<u8>> {
+    let bytes: Vec<u8> = mac.split(':')
+        .map(|part| u8::from_str_radix(part, 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    if bytes.len() == 6 { Some(bytes) } else { None }
+}
+
+/// Maps an AP frequency in MHz to its WiFi band label.
+pub fn frequency_band(frequency: u32) -> Option<&'static str> {
+    match frequency {
+        2400..=2500 => Some("2.4GHz"),
+        4900..=5900 => Some("5GHz"),
+        5925..=7125 => Some("6GHz"),
+        _ => None,
+    }
+}
+
+/// Maps an AP frequency in MHz to its channel number within its band.
+pub fn frequency_channel(frequency: u32) -> Option<u32> {
+    match frequency {
+        2484 => Some(14),
+        2412..=2472 => Some((frequency - 2407) / 5),
+        // 4.9 GHz public-safety channels count from 4000 MHz, e.g. 4920 is ch184
+        4900..=4999 => Some((frequency - 4000) / 5),
+        5000..=5900 => Some((frequency - 5000) / 5),
+        5955..=7115 => Some((frequency - 5950) / 5),
+        _ => None,
+    }
+}
+
+/// Short band/channel label such as "5GHz ch36", or `None` for unknown frequencies.
+pub fn frequency_label(frequency: u32) -> Option<String> {
+    let band = frequency_band(frequency)?;
+    match frequency_channel(frequency) {
+        Some(channel) => Some(format!("{} ch{}", band, channel)),
+        None => Some(band.to_string()),
+    }
+}
+
+/// Approximate RSSI for a 0–100 strength, on a linear scale from -100 dBm (0%) to -40 dBm
+/// (100%). `dbm_to_strength` is its inverse.
+pub fn strength_to_dbm(strength: u8) -> i32 {
+    -40 - (100 - strength.min(100) as i32) * 60 / 100
+}
+
+/// Strength percentage for an RSSI, on the same scale as `strength_to_dbm`.
+pub fn dbm_to_strength(dbm: i32) -> u8 {
+    ((dbm + 100) * 100 / 60).clamp(0, 100) as u8
+}
+
+/// Header status line for NetworkManager's global `state` and `connectivity`, or `None`
+/// when the state is unknown.
+pub fn state_label(state: u32, connectivity: u32) -> Option<&'static str> {
+    match state {
+        NM_STATE_ASLEEP => Some("Networking off"),
+        NM_STATE_DISCONNECTED => Some("Disconnected"),
+        NM_STATE_DISCONNECTING => Some("Disconnecting…"),
+        NM_STATE_CONNECTING => Some("Connecting…"),
+        NM_STATE_CONNECTED_LOCAL | NM_STATE_CONNECTED_SITE if connectivity == CONNECTIVITY_PORTAL => {
+            Some("Sign-in required")
+        }
+        NM_STATE_CONNECTED_LOCAL | NM_STATE_CONNECTED_SITE => Some("No internet"),
+        NM_STATE_CONNECTED_GLOBAL => Some("Online"),
+        _ => None,
+    }
+}
+
+/// Badge for the connected row when NetworkManager's `connectivity` says the network is up
+/// but the internet isn't reachable through it.
+pub fn connectivity_badge(connectivity: u32) -> Option<&'static str> {
+    match connectivity {
+        CONNECTIVITY_PORTAL => Some("Sign-in required"),
+        CONNECTIVITY_LIMITED => Some("Connected, no internet"),
+        _ => None,
+    }
+}
+
+/// Requests `uri` without following redirects and returns the `Location` a captive portal
+/// answers with. Portals can only intercept plain HTTP, so other schemes are skipped.
+async fn portal_redirect(uri: &str) -> Option<String> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let rest = uri.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let addr = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    let request = format!("GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n", path, authority);
+
+    let fetch_head = async {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.ok()?;
+        stream.write_all(request.as_bytes()).await.ok()?;
+        let mut head = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < 16 * 1024 {
+            let n = stream.read(&mut buf).await.ok()?;
+            if n == 0 {
+                break;
+            }
+            head.extend_from_slice(&buf[..n]);
+        }
+        Some(String::from_utf8_lossy(&head).into_owned())
+    };
+    let head = tokio::time::timeout(std::time::Duration::from_secs(5), fetch_head).await.ok()??;
+
+    let mut lines = head.lines();
+    let status = lines.next()?.split_whitespace().nth(1)?;
+    if !status.starts_with('3') {
+        return None;
+    }
+    let location = lines
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim().eq_ignore_ascii_case("location").then(|| value.trim().to_string())
+        })
+        .filter(|location| !location.is_empty())?;
+    if location.starts_with('/') {
+        Some(format!("http://{}{}", authority, location))
+    } else {
+        Some(location)
+    }
 }
@@ -0,0 +1,108 @@
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use zbus::Connection;
+
+const OBEX: &str = "org.bluez.obex";
+
+/// Object Push (OPP) through BlueZ's obexd, which lives on the session bus and is
+/// started on demand by D-Bus activation.
+#[derive(Clone)]
+pub struct ObexClient {
+    conn: Connection,
+}
+
+impl ObexClient {
+    pub async fn new() -> zbus::Result<Self> {
+        let conn = Connection::session().await?;
+        Ok(Self { conn })
+    }
+
+    /// Pushes `file` to the device at Bluetooth `address`, calling `on_progress` with
+    /// (transferred, size) in bytes. Returns once obexd reports the transfer finished.
+    pub async fn send_file<F: Fn(u64, u64)>(&self, address: &str, file: &str, on_progress: F) -> zbus::Result<()> {
+        let mut args: HashMap<&str, zbus::zvariant::Value> = HashMap::new();
+        args.insert("Target", "opp".into());
+        let session: zbus::zvariant::OwnedObjectPath = self.conn
+            .call_method(
+                Some(OBEX),
+                "/org/bluez/obex",
+                Some("org.bluez.obex.Client1"),
+                "CreateSession",
+                &(address, args),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+
+        let result = self.push(&session, file, &on_progress).await;
+
+        if let Err(e) = self.conn
+            .call_method(
+                Some(OBEX),
+                "/org/bluez/obex",
+                Some("org.bluez.obex.Client1"),
+                "RemoveSession",
+                &(&session),
+            )
+            .await
+        {
+            log::warn!("Failed to close OBEX session: {}", e);
+        }
+        result
+    }
+
+    async fn push<F: Fn(u64, u64)>(&self, session: &zbus::zvariant::OwnedObjectPath, file: &str, on_progress: &F) -> zbus::Result<()> {
+        // Subscribe first so a small file that completes immediately isn't missed
+        let rule = zbus::MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .sender(OBEX)?
+            .interface("org.freedesktop.DBus.Properties")?
+            .member("PropertiesChanged")?
+            .build();
+        let mut signals = zbus::MessageStream::for_match_rule(rule, &self.conn, None).await?;
+
+        let (transfer, properties): (zbus::zvariant::OwnedObjectPath, HashMap<String, zbus::zvariant::OwnedValue>) = self.conn
+            .call_method(
+                Some(OBEX),
+                session,
+                Some("org.bluez.obex.ObjectPush1"),
+                "SendFile",
+                &(file),
+            )
+            .await?
+            .body()
+            .deserialize()?;
+        let size = properties.get("Size")
+            .and_then(|v| u64::try_from(v).ok())
+            .unwrap_or(0);
+        on_progress(0, size);
+
+        while let Some(msg) = signals.next().await {
+            let msg = match msg {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            if msg.header().path().map(|p| p.as_str() != transfer.as_str()).unwrap_or(true) {
+                continue;
+            }
+            let (interface, changed, _) = match msg.body().deserialize::<(String, HashMap<String, zbus::zvariant::OwnedValue>, Vec<String>)>() {
+                Ok(body) => body,
+                Err(_) => continue,
+            };
+            if interface != "org.bluez.obex.Transfer1" {
+                continue;
+            }
+            if let Some(transferred) = changed.get("Transferred").and_then(|v| u64::try_from(v).ok()) {
+                on_progress(transferred, size);
+            }
+            match changed.get("Status").and_then(|v| <&str>::try_from(v).ok()) {
+                Some("complete") => return Ok(()),
+                Some("error") => {
+                    return Err(zbus::Error::Address("The device declined or aborted the transfer".to_string()));
+                }
+                _ => {}
+            }
+        }
+        Err(zbus::Error::Address("Lost contact with obexd during the transfer".to_string()))
+    }
+}
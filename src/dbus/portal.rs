@@ -0,0 +1,164 @@
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use zbus::Connection;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+
+const PORTAL_DEST: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SETTINGS_IFACE: &str = "org.freedesktop.portal.Settings";
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const COLOR_SCHEME_KEY: &str = "color-scheme";
+const GLOBAL_SHORTCUTS_IFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+const REQUEST_IFACE: &str = "org.freedesktop.portal.Request";
+
+/// Id of the panel toggle within Orbit's global shortcuts session.
+pub const TOGGLE_SHORTCUT_ID: &str = "toggle";
+
+/// Reads desktop preferences from the XDG settings portal on the session bus.
+#[derive(Clone)]
+pub struct SettingsPortal {
+    conn: Connection,
+}
+
+impl SettingsPortal {
+    pub async fn new() -> zbus::Result<Self> {
+        let conn = Connection::session().await?;
+        Ok(Self { conn })
+    }
+
+    /// Returns the raw `color-scheme` value: 0 = no preference, 1 = dark, 2 = light.
+    pub async fn color_scheme(&self) -> zbus::Result<u32> {
+        let reply = self.conn
+            .call_method(
+                Some(PORTAL_DEST),
+                PORTAL_PATH,
+                Some(SETTINGS_IFACE),
+                "ReadOne",
+                &(APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY),
+            )
+            .await;
+
+        let value: OwnedValue = match reply {
+            Ok(r) => r.body().deserialize()?,
+            // Portals before version 2 only have Read, which wraps the value in an extra variant
+            Err(_) => self.conn
+                .call_method(
+                    Some(PORTAL_DEST),
+                    PORTAL_PATH,
+                    Some(SETTINGS_IFACE),
+                    "Read",
+                    &(APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY),
+                )
+                .await?
+                .body()
+                .deserialize()?,
+        };
+
+        let scheme = match &*value {
+            Value::Value(inner) => u32::try_from(&**inner)?,
+            v => u32::try_from(v)?,
+        };
+        Ok(scheme)
+    }
+
+    /// Calls `on_change` with the new `color-scheme` value whenever the desktop changes it.
+    /// Runs until the portal goes away.
+    pub async fn watch_color_scheme<F: Fn(u32)>(&self, on_change: F) -> zbus::Result<()> {
+        let proxy = zbus::Proxy::new(&self.conn, PORTAL_DEST, PORTAL_PATH, SETTINGS_IFACE).await?;
+        let mut changes = proxy.receive_signal("SettingChanged").await?;
+        while let Some(msg) = changes.next().await {
+            if let Ok((namespace, key, value)) = msg.body().deserialize::<(String, String, OwnedValue)>() {
+                if namespace != APPEARANCE_NAMESPACE || key != COLOR_SCHEME_KEY {
+                    continue;
+                }
+                if let Ok(scheme) = u32::try_from(&*value) {
+                    on_change(scheme);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Registers Orbit's own key binding through the XDG global shortcuts portal, for
+/// compositors where binding `orbit toggle` externally is awkward.
+pub struct GlobalShortcutsPortal {
+    conn: Connection,
+}
+
+impl GlobalShortcutsPortal {
+    pub async fn new() -> zbus::Result<Self> {
+        let conn = Connection::session().await?;
+        Ok(Self { conn })
+    }
+
+    /// Calls a portal method that answers through a Request object and returns the
+    /// results carried by its `Response` signal.
+    async fn request<B>(&self, method: &str, token: &str, body: &B) -> zbus::Result<HashMap<String, OwnedValue>>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        let sender = self.conn.unique_name()
+            .ok_or_else(|| zbus::Error::Address("Session bus connection has no name".to_string()))?
+            .trim_start_matches(':')
+            .replace('.', "_");
+        // Subscribe before calling so a quick answer is not missed
+        let request_path = format!("{}/request/{}/{}", PORTAL_PATH, sender, token);
+        let request = zbus::Proxy::new(&self.conn, PORTAL_DEST, request_path, REQUEST_IFACE).await?;
+        let mut responses = request.receive_signal("Response").await?;
+
+        self.conn
+            .call_method(Some(PORTAL_DEST), PORTAL_PATH, Some(GLOBAL_SHORTCUTS_IFACE), method, body)
+            .await?;
+
+        let msg = responses.next().await
+            .ok_or_else(|| zbus::Error::Address(format!("{} got no response", method)))?;
+        let (response, results): (u32, HashMap<String, OwnedValue>) = msg.body().deserialize()?;
+        match response {
+            0 => Ok(results),
+            1 => Err(zbus::Error::Address(format!("{} was cancelled", method))),
+            _ => Err(zbus::Error::Address(format!("{} failed", method))),
+        }
+    }
+
+    /// Opens a shortcuts session and binds the toggle shortcut, suggesting `trigger`
+    /// (e.g. "LOGO+W"). The compositor may ask the user to confirm or pick another key.
+    /// Returns the session handle to pass to `watch_activated`.
+    pub async fn bind_toggle(&self, trigger: &str) -> zbus::Result<OwnedObjectPath> {
+        let mut options: HashMap<&str, Value> = HashMap::new();
+        options.insert("handle_token", Value::from("orbit_session"));
+        options.insert("session_handle_token", Value::from("orbit"));
+        let results = self.request("CreateSession", "orbit_session", &(options,)).await?;
+
+        // The spec says string, but some portals send an object path
+        let session = match results.get("session_handle").map(|v| &**v) {
+            Some(Value::Str(s)) => OwnedObjectPath::try_from(s.as_str())?,
+            Some(Value::ObjectPath(p)) => OwnedObjectPath::from(p.to_owned()),
+            _ => return Err(zbus::Error::Address("Portal returned no shortcuts session".to_string())),
+        };
+
+        let mut shortcut: HashMap<&str, Value> = HashMap::new();
+        shortcut.insert("description", Value::from("Toggle Orbit"));
+        shortcut.insert("preferred_trigger", Value::from(trigger));
+        let shortcuts = vec![(TOGGLE_SHORTCUT_ID, shortcut)];
+        let mut options: HashMap<&str, Value> = HashMap::new();
+        options.insert("handle_token", Value::from("orbit_bind"));
+        self.request("BindShortcuts", "orbit_bind", &(ObjectPath::from(&session), shortcuts, "", options)).await?;
+        Ok(session)
+    }
+
+    /// Calls `on_activate` with the shortcut id each time one of `session`'s shortcuts is
+    /// pressed. Runs until the portal goes away.
+    pub async fn watch_activated<F: Fn(&str)>(&self, session: &OwnedObjectPath, on_activate: F) -> zbus::Result<()> {
+        let proxy = zbus::Proxy::new(&self.conn, PORTAL_DEST, PORTAL_PATH, GLOBAL_SHORTCUTS_IFACE).await?;
+        let mut activations = proxy.receive_signal("Activated").await?;
+        while let Some(msg) = activations.next().await {
+            if let Ok((handle, id, _, _)) = msg.body().deserialize::<(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>)>() {
+                if handle == *session {
+                    on_activate(&id);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use zbus::{interface, Connection};
+
+const WATCHER_DEST: &str = "org.kde.StatusNotifierWatcher";
+const WATCHER_PATH: &str = "/StatusNotifierWatcher";
+/// Path hosts look the item up at when it registers by bus name.
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const ICON_NAME: &str = "network-wireless-symbolic";
+
+/// StatusNotifierItem exported on the session bus. Clicking it (either button) calls
+/// `on_activate`; there is no menu.
+struct StatusNotifierItem {
+    on_activate: Box<dyn Fn() + Send + Sync>,
+}
+
+#[interface(name = "org.kde.StatusNotifierItem")]
+impl StatusNotifierItem {
+    fn activate(&self, _x: i32, _y: i32) {
+        (self.on_activate)();
+    }
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {
+        (self.on_activate)();
+    }
+
+    fn context_menu(&self, _x: i32, _y: i32) {}
+
+    fn scroll(&self, _delta: i32, _orientation: String) {}
+
+    #[zbus(property)]
+    fn category(&self) -> &str {
+        "Hardware"
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> &str {
+        "orbit"
+    }
+
+    #[zbus(property)]
+    fn title(&self) -> &str {
+        "Orbit"
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[zbus(property)]
+    fn icon_name(&self) -> &str {
+        ICON_NAME
+    }
+
+    #[zbus(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    /// (icon name, icon pixmaps, title, description)
+    #[zbus(property)]
+    fn tool_tip(&self) -> (String, Vec<(i32, i32, Vec<u8>)>, String, String) {
+        (ICON_NAME.to_string(), Vec::new(), "Orbit".to_string(), "WiFi and Bluetooth".to_string())
+    }
+}
+
+/// Exports Orbit's tray icon and registers it with the desktop's StatusNotifierWatcher.
+/// The icon lives as long as the returned connection.
+pub async fn serve<F: Fn() + Send + Sync + 'static>(on_activate: F) -> zbus::Result<Connection> {
+    let name = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+    let item = StatusNotifierItem { on_activate: Box::new(on_activate) };
+    let conn = zbus::connection::Builder::session()?
+        .name(name.as_str())?
+        .serve_at(ITEM_PATH, item)?
+        .build()
+        .await?;
+
+    conn.call_method(
+        Some(WATCHER_DEST),
+        WATCHER_PATH,
+        Some(WATCHER_DEST),
+        "RegisterStatusNotifierItem",
+        &(name.as_str(),),
+    )
+    .await?;
+    Ok(conn)
+}
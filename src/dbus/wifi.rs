@@ -0,0 +1,355 @@
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use super::iwd::Iwd;
+use super::network_manager::{AccessPoint, ConnectError, EnterpriseCredentials, ExportedNetwork, Ipv4Method, MacRandomMode, NetworkChange, NetworkDetails, SavedNetwork};
+use super::NetworkManager;
+
+fn unsupported_error(backend: &str) -> zbus::Error {
+    zbus::Error::Address(format!("Not supported with {}", backend))
+}
+
+fn unsupported<'a, T: Send + 'a>(backend: &str) -> BoxFuture<'a, zbus::Result<T>> {
+    let err = unsupported_error(backend);
+    Box::pin(std::future::ready(Err(err)))
+}
+
+/// Optional groups of `WifiBackend` methods, so the UI can hide controls a backend
+/// would only answer with "Not supported".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// `get_network_details` and what the details pane offers: sharing and DNS overrides.
+    Details,
+    /// Per-profile IPv4, password, MAC address and metered settings.
+    ProfileSettings,
+    /// `set_autoconnect_priority`.
+    Priority,
+    /// `connect_to_bssid`.
+    AccessPointChoice,
+    Wps,
+    Hidden,
+    /// `import_network`, i.e. saving a profile without connecting.
+    AddNetwork,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 7] = [
+        Feature::Details,
+        Feature::ProfileSettings,
+        Feature::Priority,
+        Feature::AccessPointChoice,
+        Feature::Wps,
+        Feature::Hidden,
+        Feature::AddNetwork,
+    ];
+}
+
+/// WiFi operations shared by the supported daemons. Everything past `set_autoconnect`
+/// maps to NetworkManager settings and fails on backends that have no equivalent;
+/// `supports` says which of those a backend implements.
+pub trait WifiBackend: Send + Sync {
+    /// Daemon name shown in logs and errors.
+    fn name(&self) -> &'static str;
+    fn supports(&self, _feature: Feature) -> bool {
+        false
+    }
+    /// Every `Feature` this backend implements.
+    fn features(&self) -> Vec<Feature> {
+        Feature::ALL.into_iter().filter(|f| self.supports(*f)).collect()
+    }
+    /// A second handle on the same bus connection, e.g. for the signal watcher.
+    fn clone_box(&self) -> Box<dyn WifiBackend>;
+
+    fn set_connect_retries(&mut self, _retries: u32) {}
+    fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>);
+
+    fn is_wifi_enabled(&self) -> BoxFuture<'_, zbus::Result<bool>>;
+    fn set_wifi_enabled(&self, enabled: bool) -> BoxFuture<'_, zbus::Result<()>>;
+    fn scan(&self) -> BoxFuture<'_, zbus::Result<()>>;
+    fn get_wireless_devices(&self) -> BoxFuture<'_, zbus::Result<Vec<String>>>;
+    fn get_access_points(&self) -> BoxFuture<'_, zbus::Result<Vec<AccessPoint>>>;
+    fn get_active_ssid(&self) -> BoxFuture<'_, Option<String>>;
+    fn has_saved_connection<'a>(&'a self, ssid: &'a str) -> BoxFuture<'a, bool>;
+    /// `autoconnect` applies to a profile created by this connect; existing ones keep theirs.
+    fn connect_to_network<'a>(&'a self, ssid: &'a str, password: Option<&'a str>, device_path: &'a str, autoconnect: bool) -> BoxFuture<'a, Result<(), ConnectError>>;
+    fn disconnect_ap<'a>(&'a self, ssid: &'a str, ap_path: &'a str) -> BoxFuture<'a, zbus::Result<()>>;
+    fn get_saved_networks(&self) -> BoxFuture<'_, zbus::Result<Vec<SavedNetwork>>>;
+    fn forget_network<'a>(&'a self, path: &'a str) -> BoxFuture<'a, zbus::Result<()>>;
+    fn set_autoconnect<'a>(&'a self, path: &'a str, autoconnect: bool) -> BoxFuture<'a, zbus::Result<()>>;
+    fn watch_changes<'a>(&'a self, on_change: Box<dyn Fn(NetworkChange) + Send + Sync + 'a>) -> BoxFuture<'a, zbus::Result<()>>;
+
+    fn check_connectivity(&self) -> BoxFuture<'_, zbus::Result<u32>> {
+        unsupported(self.name())
+    }
+    fn wait_for_connectivity(&self, _timeout_secs: u64) -> BoxFuture<'_, zbus::Result<u32>> {
+        unsupported(self.name())
+    }
+    fn network_state(&self) -> BoxFuture<'_, zbus::Result<(u32, u32)>> {
+        unsupported(self.name())
+    }
+    fn get_wifi_device_state(&self) -> BoxFuture<'_, zbus::Result<u32>> {
+        unsupported(self.name())
+    }
+    fn captive_portal_url(&self) -> BoxFuture<'_, Option<String>> {
+        Box::pin(std::future::ready(None))
+    }
+    fn get_device_statistics<'a>(&'a self, _device_path: &'a str) -> BoxFuture<'a, zbus::Result<(u64, u64)>> {
+        unsupported(self.name())
+    }
+    fn get_saved_psk<'a>(&'a self, _ssid: &'a str) -> BoxFuture<'a, zbus::Result<Option<String>>> {
+        unsupported(self.name())
+    }
+    fn connect_to_bssid<'a>(&'a self, _ssid: &'a str, _bssid: &'a str, _ap_path: &'a str, _password: Option<&'a str>, _device_path: &'a str, _autoconnect: bool) -> BoxFuture<'a, Result<(), ConnectError>> {
+        Box::pin(std::future::ready(Err(unsupported_error(self.name()).into())))
+    }
+    fn connect_enterprise<'a>(&'a self, _ssid: &'a str, _credentials: &'a EnterpriseCredentials, _device_path: &'a str) -> BoxFuture<'a, Result<(), ConnectError>> {
+        Box::pin(std::future::ready(Err(unsupported_error(self.name()).into())))
+    }
+    fn connect_wps<'a>(&'a self, _ssid: &'a str, _device_path: &'a str) -> BoxFuture<'a, Result<(), ConnectError>> {
+        Box::pin(std::future::ready(Err(unsupported_error(self.name()).into())))
+    }
+    fn connect_hidden<'a>(&'a self, _ssid: &'a str, _password: Option<&'a str>, _device_path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn set_metered<'a>(&'a self, _path: &'a str, _metered: bool) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn update_psk<'a>(&'a self, _path: &'a str, _new_password: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn set_autoconnect_priority<'a>(&'a self, _path: &'a str, _priority: i32) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn set_mac_randomization<'a>(&'a self, _path: &'a str, _mode: MacRandomMode) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn set_ipv4_manual<'a>(&'a self, _path: &'a str, _address: &'a str, _prefix: u32, _gateway: &'a str, _dns: Vec<String>) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn set_ipv4_auto<'a>(&'a self, _path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn get_ipv4_settings<'a>(&'a self, _path: &'a str) -> BoxFuture<'a, zbus::Result<Ipv4Method>> {
+        unsupported(self.name())
+    }
+    fn set_dns<'a>(&'a self, _path: &'a str, _servers: Vec<String>) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn clear_dns<'a>(&'a self, _path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn get_connection_path<'a>(&'a self, _ssid: &'a str) -> BoxFuture<'a, Option<String>> {
+        Box::pin(std::future::ready(None))
+    }
+    fn reapply<'a>(&'a self, _device_path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        unsupported(self.name())
+    }
+    fn get_network_details<'a>(&'a self, _ssid: &'a str) -> BoxFuture<'a, zbus::Result<NetworkDetails>> {
+        unsupported(self.name())
+    }
+    /// Saves a profile without activating it; `false` when `network.ssid` is already saved.
+    fn import_network<'a>(&'a self, _network: &'a ExportedNetwork) -> BoxFuture<'a, zbus::Result<bool>> {
+        unsupported(self.name())
+    }
+}
+
+/// Connects to whichever WiFi daemon owns its bus name, preferring NetworkManager.
+pub async fn connect() -> zbus::Result<Box<dyn WifiBackend>> {
+    match NetworkManager::new().await {
+        Ok(nm) => return Ok(Box::new(nm)),
+        Err(e) => log::debug!("NetworkManager backend unavailable: {}", e),
+    }
+    match Iwd::new().await {
+        Ok(iwd) => Ok(Box::new(iwd)),
+        Err(e) => {
+            log::debug!("iwd backend unavailable: {}", e);
+            Err(zbus::Error::Address("Neither NetworkManager nor iwd is running".to_string()))
+        }
+    }
+}
+
+impl WifiBackend for NetworkManager {
+    fn name(&self) -> &'static str {
+        "NetworkManager"
+    }
+    fn supports(&self, _feature: Feature) -> bool {
+        true
+    }
+    fn clone_box(&self) -> Box<dyn WifiBackend> {
+        Box::new(self.clone())
+    }
+
+    fn set_connect_retries(&mut self, retries: u32) {
+        NetworkManager::set_connect_retries(self, retries)
+    }
+    fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        NetworkManager::set_cancel_flag(self, flag)
+    }
+
+    fn is_wifi_enabled(&self) -> BoxFuture<'_, zbus::Result<bool>> {
+        Box::pin(NetworkManager::is_wifi_enabled(self))
+    }
+    fn set_wifi_enabled(&self, enabled: bool) -> BoxFuture<'_, zbus::Result<()>> {
+        Box::pin(NetworkManager::set_wifi_enabled(self, enabled))
+    }
+    fn scan(&self) -> BoxFuture<'_, zbus::Result<()>> {
+        Box::pin(NetworkManager::scan(self))
+    }
+    fn get_wireless_devices(&self) -> BoxFuture<'_, zbus::Result<Vec<String>>> {
+        Box::pin(NetworkManager::get_wireless_devices(self))
+    }
+    fn get_access_points(&self) -> BoxFuture<'_, zbus::Result<Vec<AccessPoint>>> {
+        Box::pin(NetworkManager::get_access_points(self))
+    }
+    fn get_active_ssid(&self) -> BoxFuture<'_, Option<String>> {
+        Box::pin(NetworkManager::get_active_ssid(self))
+    }
+    fn has_saved_connection<'a>(&'a self, ssid: &'a str) -> BoxFuture<'a, bool> {
+        Box::pin(NetworkManager::has_saved_connection(self, ssid))
+    }
+    fn connect_to_network<'a>(&'a self, ssid: &'a str, password: Option<&'a str>, device_path: &'a str, autoconnect: bool) -> BoxFuture<'a, Result<(), ConnectError>> {
+        Box::pin(NetworkManager::connect_to_network(self, ssid, password, device_path, autoconnect))
+    }
+    fn disconnect_ap<'a>(&'a self, ssid: &'a str, ap_path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::disconnect_ap(self, ssid, ap_path))
+    }
+    fn get_saved_networks(&self) -> BoxFuture<'_, zbus::Result<Vec<SavedNetwork>>> {
+        Box::pin(NetworkManager::get_saved_networks(self))
+    }
+    fn forget_network<'a>(&'a self, path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::forget_network(self, path))
+    }
+    fn set_autoconnect<'a>(&'a self, path: &'a str, autoconnect: bool) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::set_autoconnect(self, path, autoconnect))
+    }
+    fn watch_changes<'a>(&'a self, on_change: Box<dyn Fn(NetworkChange) + Send + Sync + 'a>) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::watch_changes(self, on_change))
+    }
+
+    fn check_connectivity(&self) -> BoxFuture<'_, zbus::Result<u32>> {
+        Box::pin(NetworkManager::check_connectivity(self))
+    }
+    fn wait_for_connectivity(&self, timeout_secs: u64) -> BoxFuture<'_, zbus::Result<u32>> {
+        Box::pin(NetworkManager::wait_for_connectivity(self, timeout_secs))
+    }
+    fn network_state(&self) -> BoxFuture<'_, zbus::Result<(u32, u32)>> {
+        Box::pin(NetworkManager::network_state(self))
+    }
+    fn captive_portal_url(&self) -> BoxFuture<'_, Option<String>> {
+        Box::pin(NetworkManager::captive_portal_url(self))
+    }
+    fn get_wifi_device_state(&self) -> BoxFuture<'_, zbus::Result<u32>> {
+        Box::pin(NetworkManager::get_wifi_device_state(self))
+    }
+    fn get_device_statistics<'a>(&'a self, device_path: &'a str) -> BoxFuture<'a, zbus::Result<(u64, u64)>> {
+        Box::pin(NetworkManager::get_device_statistics(self, device_path))
+    }
+    fn get_saved_psk<'a>(&'a self, ssid: &'a str) -> BoxFuture<'a, zbus::Result<Option<String>>> {
+        Box::pin(NetworkManager::get_saved_psk(self, ssid))
+    }
+    fn connect_to_bssid<'a>(&'a self, ssid: &'a str, bssid: &'a str, ap_path: &'a str, password: Option<&'a str>, device_path: &'a str, autoconnect: bool) -> BoxFuture<'a, Result<(), ConnectError>> {
+        Box::pin(NetworkManager::connect_to_bssid(self, ssid, bssid, ap_path, password, device_path, autoconnect))
+    }
+    fn connect_enterprise<'a>(&'a self, ssid: &'a str, credentials: &'a EnterpriseCredentials, device_path: &'a str) -> BoxFuture<'a, Result<(), ConnectError>> {
+        Box::pin(NetworkManager::connect_enterprise(self, ssid, credentials, device_path))
+    }
+    fn connect_wps<'a>(&'a self, ssid: &'a str, device_path: &'a str) -> BoxFuture<'a, Result<(), ConnectError>> {
+        Box::pin(NetworkManager::connect_wps(self, ssid, device_path))
+    }
+    fn connect_hidden<'a>(&'a self, ssid: &'a str, password: Option<&'a str>, device_path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::connect_hidden(self, ssid, password, device_path))
+    }
+    fn set_metered<'a>(&'a self, path: &'a str, metered: bool) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::set_metered(self, path, metered))
+    }
+    fn update_psk<'a>(&'a self, path: &'a str, new_password: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::update_psk(self, path, new_password))
+    }
+    fn set_autoconnect_priority<'a>(&'a self, path: &'a str, priority: i32) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::set_autoconnect_priority(self, path, priority))
+    }
+    fn set_mac_randomization<'a>(&'a self, path: &'a str, mode: MacRandomMode) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::set_mac_randomization(self, path, mode))
+    }
+    fn set_ipv4_manual<'a>(&'a self, path: &'a str, address: &'a str, prefix: u32, gateway: &'a str, dns: Vec<String>) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::set_ipv4_manual(self, path, address, prefix, gateway, dns))
+    }
+    fn set_ipv4_auto<'a>(&'a self, path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::set_ipv4_auto(self, path))
+    }
+    fn get_ipv4_settings<'a>(&'a self, path: &'a str) -> BoxFuture<'a, zbus::Result<Ipv4Method>> {
+        Box::pin(NetworkManager::get_ipv4_settings(self, path))
+    }
+    fn set_dns<'a>(&'a self, path: &'a str, servers: Vec<String>) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::set_dns(self, path, servers))
+    }
+    fn clear_dns<'a>(&'a self, path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::clear_dns(self, path))
+    }
+    fn get_connection_path<'a>(&'a self, ssid: &'a str) -> BoxFuture<'a, Option<String>> {
+        Box::pin(NetworkManager::get_connection_path(self, ssid))
+    }
+    fn reapply<'a>(&'a self, device_path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(NetworkManager::reapply(self, device_path))
+    }
+    fn get_network_details<'a>(&'a self, ssid: &'a str) -> BoxFuture<'a, zbus::Result<NetworkDetails>> {
+        Box::pin(NetworkManager::get_network_details(self, ssid))
+    }
+    fn import_network<'a>(&'a self, network: &'a ExportedNetwork) -> BoxFuture<'a, zbus::Result<bool>> {
+        Box::pin(NetworkManager::import_network(self, network))
+    }
+}
+
+impl WifiBackend for Iwd {
+    fn name(&self) -> &'static str {
+        "iwd"
+    }
+    fn clone_box(&self) -> Box<dyn WifiBackend> {
+        Box::new(self.clone())
+    }
+
+    fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        Iwd::set_cancel_flag(self, flag)
+    }
+
+    fn is_wifi_enabled(&self) -> BoxFuture<'_, zbus::Result<bool>> {
+        Box::pin(Iwd::is_wifi_enabled(self))
+    }
+    fn set_wifi_enabled(&self, enabled: bool) -> BoxFuture<'_, zbus::Result<()>> {
+        Box::pin(Iwd::set_wifi_enabled(self, enabled))
+    }
+    fn scan(&self) -> BoxFuture<'_, zbus::Result<()>> {
+        Box::pin(Iwd::scan(self))
+    }
+    fn get_wireless_devices(&self) -> BoxFuture<'_, zbus::Result<Vec<String>>> {
+        Box::pin(Iwd::get_wireless_devices(self))
+    }
+    fn get_access_points(&self) -> BoxFuture<'_, zbus::Result<Vec<AccessPoint>>> {
+        Box::pin(Iwd::get_access_points(self))
+    }
+    fn get_active_ssid(&self) -> BoxFuture<'_, Option<String>> {
+        Box::pin(Iwd::get_active_ssid(self))
+    }
+    fn has_saved_connection<'a>(&'a self, ssid: &'a str) -> BoxFuture<'a, bool> {
+        Box::pin(Iwd::has_saved_connection(self, ssid))
+    }
+    fn connect_to_network<'a>(&'a self, ssid: &'a str, password: Option<&'a str>, device_path: &'a str, autoconnect: bool) -> BoxFuture<'a, Result<(), ConnectError>> {
+        Box::pin(Iwd::connect_to_network(self, ssid, password, device_path, autoconnect))
+    }
+    fn disconnect_ap<'a>(&'a self, ssid: &'a str, ap_path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(Iwd::disconnect_ap(self, ssid, ap_path))
+    }
+    fn get_saved_networks(&self) -> BoxFuture<'_, zbus::Result<Vec<SavedNetwork>>> {
+        Box::pin(Iwd::get_saved_networks(self))
+    }
+    fn forget_network<'a>(&'a self, path: &'a str) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(Iwd::forget_network(self, path))
+    }
+    fn set_autoconnect<'a>(&'a self, path: &'a str, autoconnect: bool) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(Iwd::set_autoconnect(self, path, autoconnect))
+    }
+    fn watch_changes<'a>(&'a self, on_change: Box<dyn Fn(NetworkChange) + Send + Sync + 'a>) -> BoxFuture<'a, zbus::Result<()>> {
+        Box::pin(Iwd::watch_changes(self, on_change))
+    }
+}
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 
 mod config;
 mod theme;
@@ -7,7 +7,7 @@ mod ui;
 mod app;
 
 use config::Config;
-use app::daemon::{DaemonClient, DaemonCommand};
+use app::daemon::{json_escape, DaemonClient, DaemonCommand, Margins};
 
 #[derive(Parser)]
 #[command(name = "orbit")]
@@ -22,12 +22,74 @@ struct Cli {
 enum Commands {
     /// List available WiFi networks
     List,
+    /// Connect to a WiFi network
+    Connect {
+        /// Network name
+        ssid: String,
+        /// Network password (omit for open or already saved networks)
+        #[arg(long)]
+        password: Option<String>,
+        /// The network does not broadcast its SSID
+        #[arg(long)]
+        hidden: bool,
+    },
+    /// Disconnect from the active WiFi network
+    Disconnect,
+    /// Bluetooth actions
+    Bt {
+        #[command(subcommand)]
+        action: BtAction,
+    },
+    /// Turn WiFi and Bluetooth off together, or restore the ones that were on
+    Airplane {
+        state: OnOff,
+    },
+    /// Write saved WiFi networks to a TOML file
+    Export {
+        /// Destination file
+        path: std::path::PathBuf,
+        /// Include passwords in plain text (the file is made readable only by you)
+        #[arg(long)]
+        with_secrets: bool,
+    },
+    /// Recreate saved WiFi networks from a file written by `orbit export`
+    Import {
+        /// File written by `orbit export`
+        path: std::path::PathBuf,
+    },
+    /// Delete every saved WiFi network
+    ForgetAll {
+        /// Required: confirms that all saved networks should be deleted
+        #[arg(long)]
+        confirm: bool,
+    },
     /// Run as background daemon
     Daemon,
     /// Toggle daemon window visibility
     Toggle {
         /// Optional position override (top-left, top-center, top-right, center-left, center, center-right, bottom-left, bottom-center, bottom-right)
         position: Option<String>,
+        /// Pin the window to a monitor by connector name (e.g. DP-1)
+        #[arg(long)]
+        monitor: Option<String>,
+        /// Tab to open on (wifi, saved, bluetooth)
+        #[arg(long, value_parser = app::daemon::TABS)]
+        tab: Option<String>,
+        /// Margin in pixels for every edge, overriding the config
+        #[arg(long, allow_negative_numbers = true)]
+        margin: Option<i32>,
+        /// Top margin in pixels (takes precedence over --margin)
+        #[arg(long, allow_negative_numbers = true)]
+        margin_top: Option<i32>,
+        /// Right margin in pixels (takes precedence over --margin)
+        #[arg(long, allow_negative_numbers = true)]
+        margin_right: Option<i32>,
+        /// Bottom margin in pixels (takes precedence over --margin)
+        #[arg(long, allow_negative_numbers = true)]
+        margin_bottom: Option<i32>,
+        /// Left margin in pixels (takes precedence over --margin)
+        #[arg(long, allow_negative_numbers = true)]
+        margin_left: Option<i32>,
     },
     /// Reload theme from configuration
     ReloadTheme,
@@ -35,6 +97,29 @@ enum Commands {
     ReloadConfig,
     /// Output status in JSON format for Waybar
     WaybarStatus,
+    /// Print daemon WiFi/Bluetooth status as JSON
+    Status,
+    /// Print the directory Orbit reads config.toml, theme.toml and style.css from
+    ConfigPath,
+    /// Write commented default config.toml and theme.toml files if they don't exist
+    ConfigInit,
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Target shell
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Subcommand)]
+enum BtAction {
+    /// Disconnect every connected Bluetooth device
+    DisconnectAll,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum OnOff {
+    On,
+    Off,
 }
 
 fn main() {
@@ -45,11 +130,30 @@ fn main() {
     
     match cli.command {
         Some(Commands::List) => list_networks(),
+        Some(Commands::Connect { ssid, password, hidden }) => connect_network(ssid, password, hidden, config.connect_retries),
+        Some(Commands::Disconnect) => disconnect_network(),
+        Some(Commands::Bt { action: BtAction::DisconnectAll }) => bt_disconnect_all(),
+        Some(Commands::Airplane { state }) => set_airplane_mode(matches!(state, OnOff::On)),
+        Some(Commands::Export { path, with_secrets }) => export_networks(&path, with_secrets),
+        Some(Commands::Import { path }) => import_networks(&path),
+        Some(Commands::ForgetAll { confirm }) => forget_all_networks(confirm),
         Some(Commands::Daemon) => run_daemon(config),
-        Some(Commands::Toggle { position }) => toggle_daemon(position),
+        Some(Commands::Toggle { position, monitor, tab, margin, margin_top, margin_right, margin_bottom, margin_left }) => {
+            let margins = Margins {
+                top: margin_top.or(margin),
+                right: margin_right.or(margin),
+                bottom: margin_bottom.or(margin),
+                left: margin_left.or(margin),
+            };
+            toggle_daemon(position, monitor, tab, margins)
+        }
         Some(Commands::ReloadTheme) => reload_theme(),
         Some(Commands::ReloadConfig) => reload_config(),
         Some(Commands::WaybarStatus) => waybar_status(),
+        Some(Commands::Status) => daemon_status(),
+        Some(Commands::ConfigPath) => print_config_path(),
+        Some(Commands::ConfigInit) => init_config(),
+        Some(Commands::Completions { shell }) => print_completions(shell),
         None => run_gui(config),
     }
 }
@@ -69,13 +173,20 @@ fn run_daemon(config: Config) {
     app.run();
 }
 
-fn toggle_daemon(position: Option<String>) {
+fn toggle_daemon(position: Option<String>, monitor: Option<String>, tab: Option<String>, margins: Margins) {
     if !DaemonClient::is_daemon_running() {
         eprintln!("Daemon is not running. Start it with: orbit daemon");
         std::process::exit(1);
     }
     
-    match DaemonClient::send_command(DaemonCommand::Toggle(position)) {
+    if let Some(ref pos) = position {
+        if Config::parse_position(pos).is_none() {
+            eprintln!("Unknown position '{}'. Valid positions: {}", pos, config::POSITIONS.join(", "));
+            std::process::exit(1);
+        }
+    }
+    
+    match DaemonClient::send_command(DaemonCommand::Toggle(position, monitor, tab, margins)) {
         Ok(response) => {
             println!("Daemon response: {}", response);
         }
@@ -122,25 +233,326 @@ fn reload_config() {
     }
 }
 
+fn daemon_status() {
+    if !DaemonClient::is_daemon_running() {
+        eprintln!("Daemon is not running. Start it with: orbit daemon");
+        std::process::exit(1);
+    }
+    
+    match DaemonClient::send_command(DaemonCommand::Status) {
+        Ok(response) => {
+            println!("{}", response);
+        }
+        Err(e) => {
+            eprintln!("Failed to query daemon status: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_config_path() {
+    match Config::config_dir() {
+        Some(dir) => println!("{}", dir.display()),
+        None => {
+            eprintln!("Neither XDG_CONFIG_HOME nor HOME is set");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn init_config() {
+    let mut failed = false;
+    for result in [Config::write_template(), theme::Theme::write_template()] {
+        match result {
+            Ok((path, true)) => println!("Wrote {}", path.display()),
+            Ok((path, false)) => println!("Kept existing {}", path.display()),
+            Err(e) => {
+                eprintln!("{}", e);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn print_completions(shell: clap_complete::Shell) {
+    let mut cmd = Cli::command();
+    clap_complete::generate(shell, &mut cmd, "orbit", &mut std::io::stdout());
+}
+
 fn waybar_status() {
     let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
     rt.block_on(async {
-        let mut status = "Disconnected".to_string();
-        if let Ok(nm) = dbus::NetworkManager::new().await {
+        let mut active: Option<(String, u8)> = None;
+        let mut ip = String::new();
+        if let Ok(nm) = dbus::wifi::connect().await {
             if let Ok(aps) = nm.get_access_points().await {
-                if let Some(active) = aps.iter().find(|ap| ap.is_connected) {
-                    status = active.ssid.clone();
+                if let Some(ap) = aps.iter().find(|ap| ap.is_connected) {
+                    active = Some((ap.ssid.clone(), ap.signal_strength));
+                    if let Ok(details) = nm.get_network_details(&ap.ssid).await {
+                        ip = details.ip4_address;
+                    }
                 }
             }
         }
-        println!(r#"{{"text": "󱗿", "tooltip": "{}"}}"#, status);
+        
+        let mut bt_connected = 0;
+        if let Ok(bt) = dbus::BluetoothManager::new().await {
+            if let Ok(devices) = bt.get_devices().await {
+                bt_connected = devices.iter().filter(|d| d.is_connected).count();
+            }
+        }
+        
+        let (icon, class) = match active.as_ref().map(|(_, signal)| *signal) {
+            None => ("󰤮", "disconnected"),
+            Some(0..=25) => ("󰤟", "weak"),
+            Some(26..=50) => ("󰤢", "ok"),
+            Some(51..=75) => ("󰤥", "good"),
+            Some(_) => ("󰤨", "excellent"),
+        };
+        
+        let mut text = icon.to_string();
+        if bt_connected > 0 {
+            text.push_str(" 󰂱");
+        }
+        
+        let mut tooltip = match &active {
+            Some((ssid, signal)) => {
+                let ip = if ip.is_empty() { "N/A" } else { ip.as_str() };
+                format!("{}\nSignal: {}%\nIP: {}", ssid, signal, ip)
+            }
+            None => "Disconnected".to_string(),
+        };
+        if bt_connected > 0 {
+            tooltip.push_str(&format!("\nBluetooth: {} connected", bt_connected));
+        }
+        
+        // `percentage` drives Waybar's {icon} and bar formats; `alt` picks format-icons by state
+        let percentage = active.as_ref().map(|(_, signal)| *signal).unwrap_or(0);
+        let alt = if active.is_some() { "connected" } else { "disconnected" };
+        
+        println!(
+            r#"{{"text": "{}", "tooltip": "{}", "class": "{}", "alt": "{}", "percentage": {}}}"#,
+            json_escape(&text),
+            json_escape(&tooltip),
+            class,
+            alt,
+            percentage
+        );
     });
 }
 
+fn connect_network(ssid: String, password: Option<String>, hidden: bool, connect_retries: u32) {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let result = rt.block_on(async {
+        let mut nm = dbus::wifi::connect().await?;
+        nm.set_connect_retries(connect_retries);
+        let devices = nm.get_wireless_devices().await?;
+        let device_path = devices.first()
+            .ok_or_else(|| zbus::Error::Address("No WiFi device found".to_string()))?;
+        if hidden {
+            nm.connect_hidden(&ssid, password.as_deref(), device_path).await
+                .map_err(dbus::network_manager::ConnectError::from)
+        } else {
+            nm.connect_to_network(&ssid, password.as_deref(), device_path, true).await
+        }
+    });
+    
+    match result {
+        Ok(()) => {
+            println!("Connected to {}", ssid);
+        }
+        Err(e) => {
+            eprintln!("Failed to connect to {}: {}", ssid, ui::sanitize_error_message(&e.to_string()));
+            std::process::exit(1);
+        }
+    }
+}
+
+fn disconnect_network() {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let result = rt.block_on(async {
+        let nm = dbus::wifi::connect().await?;
+        match nm.get_active_ssid().await {
+            Some(ssid) => nm.disconnect_ap(&ssid, "").await.map(|_| Some(ssid)),
+            None => Ok(None),
+        }
+    });
+    
+    match result {
+        Ok(Some(ssid)) => {
+            println!("Disconnected from {}", ssid);
+        }
+        Ok(None) => {
+            println!("Not connected to any WiFi network.");
+        }
+        Err(e) => {
+            eprintln!("Failed to disconnect: {}", ui::sanitize_error_message(&e.to_string()));
+            std::process::exit(1);
+        }
+    }
+}
+
+fn export_networks(path: &std::path::Path, with_secrets: bool) {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let networks = match rt.block_on(async { dbus::NetworkManager::new().await?.export_networks(with_secrets).await }) {
+        Ok(n) => n,
+        Err(e) => {
+            eprintln!("Failed to read saved networks: {}", ui::sanitize_error_message(&e.to_string()));
+            std::process::exit(1);
+        }
+    };
+    
+    let count = networks.len();
+    if let Err(e) = app::profiles::write(path, networks, with_secrets) {
+        eprintln!("Failed to write {}: {}", path.display(), e);
+        std::process::exit(1);
+    }
+    println!("Exported {} networks to {}", count, path.display());
+    if with_secrets {
+        println!("The file contains plain-text passwords and is readable only by you. Keep it private.");
+    }
+}
+
+fn import_networks(path: &std::path::Path) {
+    let networks = match app::profiles::read(path) {
+        Ok(n) => n,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    };
+    
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let nm = match rt.block_on(async { dbus::NetworkManager::new().await }) {
+        Ok(nm) => nm,
+        Err(e) => {
+            eprintln!("Failed to connect to NetworkManager: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    let mut failed = false;
+    for network in &networks {
+        match rt.block_on(async { nm.import_network(network).await }) {
+            Ok(true) => println!("Imported {}", network.ssid),
+            Ok(false) => println!("Skipped {} (already saved)", network.ssid),
+            Err(e) => {
+                eprintln!("Failed to import {}: {}", network.ssid, ui::sanitize_error_message(&e.to_string()));
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn forget_all_networks(confirm: bool) {
+    if !confirm {
+        eprintln!("This deletes every saved WiFi network. Run again with --confirm to proceed.");
+        std::process::exit(1);
+    }
+    
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let nm = match rt.block_on(async { dbus::wifi::connect().await }) {
+        Ok(nm) => nm,
+        Err(e) => {
+            eprintln!("Failed to connect to a WiFi backend: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let networks = match rt.block_on(async { nm.get_saved_networks().await }) {
+        Ok(n) => n,
+        Err(e) => {
+            eprintln!("Failed to list saved networks: {}", ui::sanitize_error_message(&e.to_string()));
+            std::process::exit(1);
+        }
+    };
+    
+    let mut removed = 0;
+    let mut failed = false;
+    for network in &networks {
+        match rt.block_on(async { nm.forget_network(&network.path).await }) {
+            Ok(()) => removed += 1,
+            Err(e) => {
+                eprintln!("Failed to forget {}: {}", network.ssid, ui::sanitize_error_message(&e.to_string()));
+                failed = true;
+            }
+        }
+    }
+    println!("Removed {} of {} saved networks", removed, networks.len());
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn bt_disconnect_all() {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let bt = match rt.block_on(async { dbus::BluetoothManager::new().await }) {
+        Ok(bt) => bt,
+        Err(e) => {
+            eprintln!("Failed to connect to BlueZ: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let devices = match rt.block_on(async { bt.get_devices().await }) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("Failed to list Bluetooth devices: {}", ui::sanitize_error_message(&e.to_string()));
+            std::process::exit(1);
+        }
+    };
+    
+    let connected: Vec<_> = devices.iter().filter(|d| d.is_connected).collect();
+    if connected.is_empty() {
+        println!("No Bluetooth devices are connected.");
+        return;
+    }
+    let mut failed = false;
+    for device in connected {
+        match rt.block_on(async { bt.disconnect_device(&device.path).await }) {
+            Ok(()) => println!("Disconnected {}", device.name),
+            Err(e) => {
+                eprintln!("Failed to disconnect {}: {}", device.name, ui::sanitize_error_message(&e.to_string()));
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn set_airplane_mode(on: bool) {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+    let result = rt.block_on(async {
+        let nm = dbus::wifi::connect().await.ok();
+        let bt = dbus::BluetoothManager::new().await.ok();
+        if on {
+            app::airplane::enable(nm.as_deref(), bt.as_ref()).await
+        } else {
+            app::airplane::disable(nm.as_deref(), bt.as_ref()).await
+        }
+    });
+    
+    match result {
+        Ok(()) => {
+            println!("Airplane mode {}", if on { "on" } else { "off" });
+        }
+        Err(e) => {
+            eprintln!("Failed to switch airplane mode: {}", ui::sanitize_error_message(&e.to_string()));
+            std::process::exit(1);
+        }
+    }
+}
+
 fn list_networks() {
     let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
     rt.block_on(async {
-        match dbus::NetworkManager::new().await {
+        match dbus::wifi::connect().await {
             Ok(nm) => {
                 match nm.get_access_points().await {
                     Ok(aps) => {
@@ -152,6 +564,7 @@ fn list_networks() {
                                 dbus::SecurityType::WPA => "WPA",
                                 dbus::SecurityType::WPA2 => "WPA2",
                                 dbus::SecurityType::WPA3 => "WPA3",
+                                dbus::SecurityType::Enterprise => "802.1X",
                             };
                             let connected = if ap.is_connected { " [Connected]" } else { "" };
                             println!("  {} ({}%) {}{}", ap.ssid, ap.signal_strength, security, connected);
@@ -163,7 +576,7 @@ fn list_networks() {
                 }
             }
             Err(e) => {
-                eprintln!("Failed to connect to NetworkManager: {}", e);
+                eprintln!("Failed to connect to a WiFi backend: {}", e);
             }
         }
     });
@@ -1,5 +1,10 @@
 use serde::Deserialize;
 
+use crate::config::{self, Config};
+
+/// Commented-out palette that `orbit config-init` writes.
+const THEME_TEMPLATE: &str = include_str!("../config/theme.example.toml");
+
 #[derive(Debug, Clone, Deserialize)]
 struct ThemeFile {
     accent_primary: Option<String>,
@@ -9,6 +14,25 @@ struct ThemeFile {
     destructive: Option<String>,
 }
 
+/// The desktop's `org.freedesktop.appearance` `color-scheme` preference.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ColorScheme {
+    #[default]
+    NoPreference,
+    Dark,
+    Light,
+}
+
+impl ColorScheme {
+    pub fn from_portal(value: u32) -> Self {
+        match value {
+            1 => ColorScheme::Dark,
+            2 => ColorScheme::Light,
+            _ => ColorScheme::NoPreference,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Theme {
     pub accent_primary: String,
@@ -16,6 +40,8 @@ pub struct Theme {
     pub background: String,
     pub foreground: String,
     pub destructive: String,
+    /// The system preference this theme was loaded for.
+    pub color_scheme: ColorScheme,
 }
 
 impl Default for Theme {
@@ -26,15 +52,34 @@ impl Default for Theme {
             background: "#1e1e2e".to_string(),
             foreground: "#d4d4d8".to_string(),
             destructive: "#ef4444".to_string(),
+            color_scheme: ColorScheme::NoPreference,
         }
     }
 }
 
 impl Theme {
-    pub fn load() -> Self {
+    pub fn light() -> Self {
+        Theme {
+            accent_primary: "#7c3aed".to_string(),
+            accent_secondary: "#0891b2".to_string(),
+            background: "#f4f4f5".to_string(),
+            foreground: "#27272a".to_string(),
+            destructive: "#dc2626".to_string(),
+            color_scheme: ColorScheme::Light,
+        }
+    }
+
+    /// Loads theme.toml when present; otherwise picks the built-in palette matching
+    /// the desktop color scheme.
+    pub fn load(color_scheme: ColorScheme) -> Self {
+        let builtin = match color_scheme {
+            ColorScheme::Light => Self::light(),
+            _ => Self { color_scheme, ..Self::default() },
+        };
+        
         let theme_path = match Self::theme_path() {
             Some(p) => p,
-            None => return Self::default(),
+            None => return builtin,
         };
         
         if theme_path.exists() {
@@ -42,12 +87,26 @@ impl Theme {
                 Ok(content) => {
                     match toml::from_str::<ThemeFile>(&content) {
                         Ok(theme_file) => {
-                            let mut theme = Self::default();
-                            if let Some(c) = theme_file.accent_primary { theme.accent_primary = c; }
-                            if let Some(c) = theme_file.accent_secondary { theme.accent_secondary = c; }
-                            if let Some(c) = theme_file.background { theme.background = c; }
-                            if let Some(c) = theme_file.foreground { theme.foreground = c; }
-                            if let Some(c) = theme_file.destructive { theme.destructive = c; }
+                            // Keys the file leaves out follow the desktop's palette
+                            let mut theme = builtin.clone();
+                            let fields = [
+                                ("accent_primary", theme_file.accent_primary, &mut theme.accent_primary),
+                                ("accent_secondary", theme_file.accent_secondary, &mut theme.accent_secondary),
+                                ("background", theme_file.background, &mut theme.background),
+                                ("foreground", theme_file.foreground, &mut theme.foreground),
+                                ("destructive", theme_file.destructive, &mut theme.destructive),
+                            ];
+                            for (key, value, field) in fields {
+                                match value {
+                                    // CSS needs the '#', which the file may leave out
+                                    Some(c) if is_valid_hex(&c) => *field = format!("#{}", c.trim_start_matches('#')),
+                                    Some(c) => log::warn!(
+                                        "theme.toml: {} = {:?} is not a valid hex color (expected #rgb, #rrggbb or #rrggbbaa), keeping {}",
+                                        key, c, field
+                                    ),
+                                    None => {}
+                                }
+                            }
                             return theme;
                         }
                         Err(e) => {
@@ -61,33 +120,28 @@ impl Theme {
             }
         }
         
-        Self::default()
+        builtin
     }
     
     pub fn theme_path() -> Option<std::path::PathBuf> {
-        let home = std::env::var("HOME").ok()?;
-        Some(std::path::PathBuf::from(home)
-            .join(".config")
-            .join("orbit")
-            .join("theme.toml"))
+        Some(Config::config_dir()?.join("theme.toml"))
+    }
+
+    /// Writes a commented theme.toml unless one exists. Returns its path and whether it
+    /// was written.
+    pub fn write_template() -> Result<(std::path::PathBuf, bool), String> {
+        let path = Self::theme_path()
+            .ok_or("Cannot determine config path: neither XDG_CONFIG_HOME nor HOME is set")?;
+        let written = config::write_if_missing(&path, THEME_TEMPLATE)?;
+        Ok((path, written))
     }
 
     pub fn style_css_path() -> Option<std::path::PathBuf> {
-        let home = std::env::var("HOME").ok()?;
-        Some(std::path::PathBuf::from(home)
-            .join(".config")
-            .join("orbit")
-            .join("style.css"))
+        Some(Config::config_dir()?.join("style.css"))
     }
 
     fn hex_to_rgb(&self, hex: &str) -> (u8, u8, u8) {
-        let hex = hex.trim_start_matches('#');
-        if hex.len() != 6 {
-            return (0, 0, 0);
-        }
-        let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(0);
-        let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or(0);
-        let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or(0);
+        let (r, g, b, _) = parse_hex(hex).unwrap_or((0, 0, 0, 255));
         (r, g, b)
     }
 
@@ -106,11 +160,17 @@ impl Theme {
         let ng = (g as f32 * new_factor).clamp(0.0, 255.0) as u8;
         let nb = (b as f32 * new_factor).clamp(0.0, 255.0) as u8;
         
-        format!("#{:02x}{:02x}{:02x}", nr, ng, nb)
+        // Carry the color's own alpha through so translucent themes stay translucent
+        match parse_hex(hex) {
+            Some((_, _, _, a)) if a < 255 => format!("#{:02x}{:02x}{:02x}{:02x}", nr, ng, nb, a),
+            _ => format!("#{:02x}{:02x}{:02x}", nr, ng, nb),
+        }
     }
 
+    /// `alpha` is scaled by the color's own alpha when it has one (`#rrggbbaa`).
     fn hex_to_rgba(&self, hex: &str, alpha: f32) -> String {
-        let (r, g, b) = self.hex_to_rgb(hex);
+        let (r, g, b, a) = parse_hex(hex).unwrap_or((0, 0, 0, 255));
+        let alpha = alpha * a as f32 / 255.0;
         format!("rgba({}, {}, {}, {})", r, g, b, alpha)
     }
     
@@ -230,7 +290,8 @@ window {{
 /* Overlays - Opaque with padding */
 .orbit-details-overlay, 
 .orbit-password-overlay, 
-.orbit-error-overlay {{
+.orbit-error-overlay,
+.orbit-transfer-overlay {{
     background-color: {opaque_bg};
     border: 2px solid {accent};
     border-radius: 16px;
@@ -390,6 +451,52 @@ window {{
     color: {fg};
 }}
 
+/* Section headers double as fold toggles */
+.orbit-section-toggle {{
+    background: none;
+    border: none;
+    box-shadow: none;
+    padding: 0;
+    min-height: 0;
+}}
+
+.orbit-section-toggle image.orbit-section-header {{
+    padding: 0;
+}}
+
+/* Small tag next to a name, e.g. "hidden" */
+.orbit-badge {{
+    font-size: 10px;
+    color: {fg};
+    border: 1px solid {separator};
+    border-radius: 6px;
+    padding: 0 6px;
+    opacity: 0.7;
+}}
+
+/* Connected, but no internet or a captive portal */
+.orbit-badge-warning {{
+    color: {gold};
+    border-color: {gold};
+    opacity: 1;
+}}
+
+/* "N APs" chip that unfolds the access point list */
+.orbit-ap-chip {{
+    min-height: 0;
+    background: none;
+}}
+
+.orbit-ap-chip:hover {{
+    opacity: 1;
+}}
+
+.orbit-ap-entry {{
+    font-size: 11px;
+    padding: 2px 4px;
+    min-height: 0;
+}}
+
 .orbit-detail-label {{
     font-size: 10px;
     color: {fg};
@@ -433,6 +540,12 @@ entry:focus, password-entry:focus {{
     font-weight: 500;
 }}
 
+/* Caps Lock warning under the password field */
+.orbit-caps-lock-warning {{
+    color: {gold};
+    font-size: 12px;
+}}
+
 /* Connecting state */
 .orbit-button.connecting {{
     opacity: 0.7;
@@ -522,3 +635,27 @@ entry:focus, password-entry:focus {{
         )
     }
 }
+
+/// Whether `value` is a `rgb`, `rrggbb` or `rrggbbaa` hex color, with or without a leading `#`.
+fn is_valid_hex(value: &str) -> bool {
+    parse_hex(value).is_some()
+}
+
+/// Parses `#rgb`, `#rrggbb` and `#rrggbbaa` (the `#` is optional) into RGBA components.
+fn parse_hex(value: &str) -> Option<(u8, u8, u8, u8)> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    match hex.len() {
+        3 => {
+            // Shorthand: each nibble is doubled, so "f80" becomes "ff8800"
+            let nibble = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|n| n * 17);
+            Some((nibble(0)?, nibble(1)?, nibble(2)?, 255))
+        }
+        6 => Some((channel(0)?, channel(2)?, channel(4)?, 255)),
+        8 => Some((channel(0)?, channel(2)?, channel(4)?, channel(6)?)),
+        _ => None,
+    }
+}
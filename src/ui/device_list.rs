@@ -2,8 +2,9 @@ use gtk4::prelude::*;
 use gtk4::{self as gtk, Orientation};
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::collections::HashMap;
-use crate::dbus::bluez::{BluetoothDevice, DeviceType};
+use std::collections::{HashMap, HashSet};
+use super::section::{collapsible_section, CollapsedSections, SectionToggled};
+use crate::dbus::bluez::{AudioProfile, BluetoothAdapter, BluetoothDevice, CardProfiles, DeviceType, MediaCommand, MediaPlayer};
 
 #[derive(Clone)]
 pub enum DeviceAction {
@@ -11,6 +12,12 @@ pub enum DeviceAction {
     Disconnect,
     Pair,
     Forget,
+    Trust(bool),
+    SetProfile(String),
+    Rename(String),
+    /// Push the file at this path over OBEX.
+    SendFile(String),
+    Media(MediaCommand),
 }
 
 #[derive(Clone)]
@@ -18,11 +25,34 @@ pub struct DeviceList {
     container: gtk::Box,
     list_box: gtk::Box,
     scan_button: gtk::Button,
+    scanning: Rc<RefCell<bool>>,
+    discoverable_button: gtk::ToggleButton,
+    disconnect_all_button: gtk::Button,
+    on_disconnect_all: Rc<RefCell<Option<Rc<dyn Fn()>>>>,
+    is_updating_discoverable: Rc<RefCell<bool>>,
+    on_discoverable_toggle: Rc<RefCell<Option<Rc<dyn Fn(bool)>>>>,
+    adapter_info: gtk::Box,
+    adapter_name_label: gtk::Label,
+    adapter_address_label: gtk::Label,
+    adapter_rename_button: gtk::Button,
+    on_adapter_renamed: Rc<RefCell<Option<Rc<dyn Fn(String, String)>>>>,
+    adapter_row: gtk::Box,
+    adapter_dropdown: gtk::DropDown,
+    adapters: Rc<RefCell<Vec<BluetoothAdapter>>>,
+    is_updating_adapters: Rc<RefCell<bool>>,
+    on_adapter_selected: Rc<RefCell<Option<Rc<dyn Fn(String)>>>>,
     devices: Rc<RefCell<Vec<BluetoothDevice>>>,
     row_actions: Rc<RefCell<HashMap<String, gtk::Box>>>,
     on_action: Rc<RefCell<Option<Rc<dyn Fn(String, DeviceAction)>>>>,
     action_path: Rc<RefCell<Option<String>>>,
     action_type: Rc<RefCell<Option<DeviceAction>>>,
+    editing_path: Rc<RefCell<Option<String>>>,
+    /// Devices whose audio profile fold-out is open, with their profiles once looked up.
+    audio_profiles: Rc<RefCell<HashMap<String, Option<CardProfiles>>>>,
+    on_profiles_requested: Rc<RefCell<Option<Rc<dyn Fn(String)>>>>,
+    readonly: Rc<RefCell<bool>>,
+    collapsed_sections: CollapsedSections,
+    on_section_toggled: SectionToggled,
 }
 
 impl DeviceList {
@@ -33,6 +63,139 @@ impl DeviceList {
             .hexpand(true)
             .build();
         
+        // This machine's adapter, as other devices see it while discoverable
+        let adapter_info = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .margin_bottom(8)
+            .visible(false)
+            .build();
+        
+        let adapter_name_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(4)
+            .hexpand(true)
+            .build();
+        
+        let adapter_name_label = gtk::Label::builder()
+            .css_classes(["orbit-device-name"])
+            .halign(gtk::Align::Start)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .build();
+        
+        let adapter_rename_button = gtk::Button::builder()
+            .icon_name("document-edit-symbolic")
+            .css_classes(["orbit-button", "flat"])
+            .tooltip_text("Rename this computer")
+            .valign(gtk::Align::Center)
+            .build();
+        
+        let adapter_address_label = gtk::Label::builder()
+            .css_classes(["orbit-status"])
+            .halign(gtk::Align::End)
+            .selectable(true)
+            .build();
+        
+        let adapter_name_entry = gtk::Entry::builder()
+            .placeholder_text("Adapter name")
+            .hexpand(true)
+            .visible(false)
+            .build();
+        
+        adapter_name_row.append(&adapter_name_label);
+        adapter_name_row.append(&adapter_rename_button);
+        adapter_info.append(&adapter_name_row);
+        adapter_info.append(&adapter_name_entry);
+        adapter_info.append(&adapter_address_label);
+        container.append(&adapter_info);
+        
+        let adapter_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .margin_bottom(8)
+            .visible(false)
+            .build();
+        
+        let adapter_label = gtk::Label::builder()
+            .label("Adapter")
+            .css_classes(["orbit-status"])
+            .build();
+        
+        let adapter_dropdown = gtk::DropDown::from_strings(&[]);
+        adapter_dropdown.set_hexpand(true);
+        
+        adapter_row.append(&adapter_label);
+        adapter_row.append(&adapter_dropdown);
+        container.append(&adapter_row);
+        
+        let adapters: Rc<RefCell<Vec<BluetoothAdapter>>> = Rc::new(RefCell::new(Vec::new()));
+        let is_updating_adapters = Rc::new(RefCell::new(false));
+        let on_adapter_selected: Rc<RefCell<Option<Rc<dyn Fn(String)>>>> = Rc::new(RefCell::new(None));
+        
+        let on_adapter_renamed: Rc<RefCell<Option<Rc<dyn Fn(String, String)>>>> = Rc::new(RefCell::new(None));
+        
+        let name_row_edit = adapter_name_row.clone();
+        let entry_edit = adapter_name_entry.clone();
+        let name_label_edit = adapter_name_label.clone();
+        adapter_rename_button.connect_clicked(move |_| {
+            entry_edit.set_text(&name_label_edit.label());
+            name_row_edit.set_visible(false);
+            entry_edit.set_visible(true);
+            entry_edit.grab_focus();
+        });
+        
+        let name_row_commit = adapter_name_row.clone();
+        let name_label_commit = adapter_name_label.clone();
+        let adapters_commit = adapters.clone();
+        let adapter_dropdown_commit = adapter_dropdown.clone();
+        let on_adapter_renamed_clone = on_adapter_renamed.clone();
+        adapter_name_entry.connect_activate(move |entry| {
+            entry.set_visible(false);
+            name_row_commit.set_visible(true);
+            let alias = entry.text().trim().to_string();
+            if alias.is_empty() || alias == name_label_commit.label() {
+                return;
+            }
+            let path = adapters_commit.borrow()
+                .get(adapter_dropdown_commit.selected() as usize)
+                .map(|a| a.path.clone());
+            if let (Some(path), Some(callback)) = (path, on_adapter_renamed_clone.borrow().as_ref()) {
+                callback(path, alias);
+            }
+        });
+        
+        let name_row_cancel = adapter_name_row.clone();
+        let key_controller = gtk::EventControllerKey::new();
+        key_controller.connect_key_pressed(move |controller, key, _, _| {
+            if key == gtk4::gdk::Key::Escape {
+                if let Some(entry) = controller.widget() {
+                    entry.set_visible(false);
+                }
+                name_row_cancel.set_visible(true);
+                gtk4::glib::Propagation::Stop
+            } else {
+                gtk4::glib::Propagation::Proceed
+            }
+        });
+        adapter_name_entry.add_controller(key_controller);
+        
+        let adapters_select = adapters.clone();
+        let name_label_select = adapter_name_label.clone();
+        let address_label_select = adapter_address_label.clone();
+        let is_updating_select = is_updating_adapters.clone();
+        let on_adapter_selected_clone = on_adapter_selected.clone();
+        adapter_dropdown.connect_selected_notify(move |dropdown| {
+            if *is_updating_select.borrow() {
+                return;
+            }
+            let adapter = adapters_select.borrow().get(dropdown.selected() as usize).cloned();
+            show_adapter_info(&name_label_select, &address_label_select, adapter.as_ref());
+            let path = adapter.map(|a| a.path);
+            if let (Some(path), Some(callback)) = (path, on_adapter_selected_clone.borrow().as_ref()) {
+                callback(path);
+            }
+        });
+        
         let scrolled = gtk::ScrolledWindow::builder()
             .vexpand(true)
             .hexpand(true)
@@ -60,18 +223,79 @@ impl DeviceList {
             .hexpand(true)
             .build();
         
+        let discoverable_button = gtk::ToggleButton::builder()
+            .label("Discoverable")
+            .css_classes(["orbit-button", "flat"])
+            .tooltip_text("Let other devices find and pair with this computer")
+            .margin_start(8)
+            .build();
+        
+        let disconnect_all_button = gtk::Button::builder()
+            .label("Disconnect All")
+            .css_classes(["orbit-button", "flat"])
+            .tooltip_text("Disconnect every connected device")
+            .margin_start(8)
+            .visible(false)
+            .build();
+        
         footer.append(&scan_button);
+        footer.append(&discoverable_button);
+        footer.append(&disconnect_all_button);
         container.append(&footer);
         
+        let on_disconnect_all: Rc<RefCell<Option<Rc<dyn Fn()>>>> = Rc::new(RefCell::new(None));
+        let on_disconnect_all_clone = on_disconnect_all.clone();
+        disconnect_all_button.connect_clicked(move |_| {
+            if let Some(callback) = on_disconnect_all_clone.borrow().as_ref() {
+                callback();
+            }
+        });
+        
+        let is_updating_discoverable = Rc::new(RefCell::new(false));
+        let on_discoverable_toggle: Rc<RefCell<Option<Rc<dyn Fn(bool)>>>> = Rc::new(RefCell::new(None));
+        
+        let is_updating_toggle = is_updating_discoverable.clone();
+        let on_discoverable_clone = on_discoverable_toggle.clone();
+        discoverable_button.connect_toggled(move |button| {
+            if *is_updating_toggle.borrow() {
+                return;
+            }
+            if let Some(callback) = on_discoverable_clone.borrow().as_ref() {
+                callback(button.is_active());
+            }
+        });
+        
         let list = Self {
             container,
             list_box,
             scan_button,
+            scanning: Rc::new(RefCell::new(false)),
+            discoverable_button,
+            disconnect_all_button,
+            on_disconnect_all,
+            is_updating_discoverable,
+            on_discoverable_toggle,
+            adapter_info,
+            adapter_name_label,
+            adapter_address_label,
+            adapter_rename_button,
+            on_adapter_renamed,
+            adapter_row,
+            adapter_dropdown,
+            adapters,
+            is_updating_adapters,
+            on_adapter_selected,
             devices: Rc::new(RefCell::new(Vec::new())),
             row_actions: Rc::new(RefCell::new(HashMap::new())),
             on_action: Rc::new(RefCell::new(None)),
             action_path: Rc::new(RefCell::new(None)),
             action_type: Rc::new(RefCell::new(None)),
+            editing_path: Rc::new(RefCell::new(None)),
+            audio_profiles: Rc::new(RefCell::new(HashMap::new())),
+            on_profiles_requested: Rc::new(RefCell::new(None)),
+            readonly: Rc::new(RefCell::new(false)),
+            collapsed_sections: Rc::new(RefCell::new(HashSet::new())),
+            on_section_toggled: Rc::new(RefCell::new(None)),
         };
         
         list.show_loading();
@@ -86,6 +310,38 @@ impl DeviceList {
         self.list_box.append(&placeholder);
     }
     
+    fn clear(&self) {
+        self.row_actions.borrow_mut().clear();
+        self.devices.borrow_mut().clear();
+        self.disconnect_all_button.set_visible(false);
+        while let Some(child) = self.list_box.first_child() {
+            self.list_box.remove(&child);
+        }
+    }
+    
+    /// Shown while the startup thread is still waiting for BlueZ to come up.
+    pub fn show_initializing(&self) {
+        self.clear();
+        let placeholder = gtk::Label::builder()
+            .label("Initializing Bluetooth…")
+            .css_classes(["orbit-placeholder"])
+            .build();
+        self.list_box.append(&placeholder);
+    }
+    
+    /// Shown once every attempt to reach BlueZ has failed.
+    pub fn show_no_adapter(&self) {
+        self.clear();
+        let placeholder = gtk::Label::builder()
+            .label("No Bluetooth adapter found.\nCheck that bluetoothd is running (e.g. systemctl start bluetooth) and reopen Orbit.")
+            .css_classes(["orbit-placeholder"])
+            .wrap(true)
+            .justify(gtk::Justification::Center)
+            .build();
+        self.list_box.append(&placeholder);
+        self.scan_button.set_sensitive(false);
+    }
+    
     fn show_placeholder(&self) {
         let placeholder = gtk::Label::builder()
             .label("Click 'Scan' to find devices")
@@ -132,15 +388,66 @@ impl DeviceList {
         }
     }
     
+    pub fn set_discoverable(&self, discoverable: bool) {
+        *self.is_updating_discoverable.borrow_mut() = true;
+        self.discoverable_button.set_active(discoverable);
+        *self.is_updating_discoverable.borrow_mut() = false;
+    }
+    
+    /// Populates the adapter picker, which is only shown when more than one adapter exists.
+    pub fn set_adapters(&self, adapters: Vec<BluetoothAdapter>, selected: Option<String>) {
+        *self.is_updating_adapters.borrow_mut() = true;
+        let names: Vec<&str> = adapters.iter().map(|a| a.name.as_str()).collect();
+        self.adapter_dropdown.set_model(Some(&gtk::StringList::new(&names)));
+        if let Some(index) = selected.and_then(|s| adapters.iter().position(|a| a.path == s)) {
+            self.adapter_dropdown.set_selected(index as u32);
+        }
+        self.adapter_row.set_visible(adapters.len() > 1);
+        let current = adapters.get(self.adapter_dropdown.selected() as usize);
+        show_adapter_info(&self.adapter_name_label, &self.adapter_address_label, current);
+        self.adapter_info.set_visible(current.is_some());
+        *self.adapters.borrow_mut() = adapters;
+        *self.is_updating_adapters.borrow_mut() = false;
+    }
+    
+    pub fn set_readonly(&self, readonly: bool) {
+        *self.readonly.borrow_mut() = readonly;
+        self.discoverable_button.set_visible(!readonly);
+        self.adapter_rename_button.set_visible(!readonly);
+        let devices = self.devices.borrow().clone();
+        if !devices.is_empty() {
+            self.render_devices(&devices);
+        }
+    }
+    
     pub fn set_devices(&self, devices: Vec<BluetoothDevice>) {
+        self.audio_profiles.borrow_mut().retain(|path, _| devices.iter().any(|d| d.is_connected && &d.path == path));
         *self.devices.borrow_mut() = devices.clone();
         *self.action_path.borrow_mut() = None;
         *self.action_type.borrow_mut() = None;
+        // Don't throw away a rename in progress when the list refreshes
+        if self.editing_path.borrow().is_some() {
+            return;
+        }
+        self.render_devices(&devices);
+    }
+    
+    /// Shows the profiles looked up for `path`, if its fold-out is still open.
+    pub fn set_audio_profiles(&self, path: &str, profiles: CardProfiles) {
+        match self.audio_profiles.borrow_mut().get_mut(path) {
+            Some(slot) => *slot = Some(profiles),
+            None => return,
+        }
+        if self.editing_path.borrow().is_some() {
+            return;
+        }
+        let devices = self.devices.borrow().clone();
         self.render_devices(&devices);
     }
     
     fn render_devices(&self, devices: &[BluetoothDevice]) {
         self.row_actions.borrow_mut().clear();
+        self.disconnect_all_button.set_visible(devices.iter().any(|d| d.is_connected));
 
         while let Some(child) = self.list_box.first_child() {
             self.list_box.remove(&child);
@@ -156,44 +463,26 @@ impl DeviceList {
         let available_devices: Vec<&BluetoothDevice> = devices.iter().filter(|d| !d.is_paired).collect();
         
         if !connected_devices.is_empty() {
-            let section_header = gtk::Label::builder()
-                .label("CONNECTED")
-                .css_classes(["orbit-section-header"])
-                .halign(gtk::Align::Start)
-                .build();
-            self.list_box.append(&section_header);
-            
+            let section = collapsible_section(&self.list_box, "CONNECTED", "bluetooth-connected", &self.collapsed_sections, &self.on_section_toggled);
             for device in connected_devices {
                 let row = self.create_device_row(device);
-                self.list_box.append(&row);
+                section.append(&row);
             }
         }
         
         if !paired_devices.is_empty() {
-            let section_header = gtk::Label::builder()
-                .label("PAIRED")
-                .css_classes(["orbit-section-header"])
-                .halign(gtk::Align::Start)
-                .build();
-            self.list_box.append(&section_header);
-            
+            let section = collapsible_section(&self.list_box, "PAIRED", "bluetooth-paired", &self.collapsed_sections, &self.on_section_toggled);
             for device in paired_devices {
                 let row = self.create_device_row(device);
-                self.list_box.append(&row);
+                section.append(&row);
             }
         }
         
         if !available_devices.is_empty() {
-            let section_header = gtk::Label::builder()
-                .label("AVAILABLE")
-                .css_classes(["orbit-section-header"])
-                .halign(gtk::Align::Start)
-                .build();
-            self.list_box.append(&section_header);
-            
+            let section = collapsible_section(&self.list_box, "AVAILABLE", "bluetooth-available", &self.collapsed_sections, &self.on_section_toggled);
             for device in available_devices {
                 let row = self.create_device_row(device);
-                self.list_box.append(&row);
+                section.append(&row);
             }
         }
     }
@@ -220,11 +509,16 @@ impl DeviceList {
         row.add_controller(focus_out);
 
         let icon_name = match device.device_type {
-            Some(DeviceType::Audio) => "audio-headphones-symbolic",
+            Some(DeviceType::Audio) => "audio-speakers-symbolic",
+            Some(DeviceType::Headphones) => "audio-headphones-symbolic",
             Some(DeviceType::Keyboard) => "input-keyboard-symbolic",
             Some(DeviceType::Mouse) => "input-mouse-symbolic",
             Some(DeviceType::Phone) => "phone-symbolic",
-            _ => "bluetooth-symbolic",
+            Some(DeviceType::Computer) => "computer-symbolic",
+            Some(DeviceType::Gamepad) => "input-gaming-symbolic",
+            Some(DeviceType::Printer) => "printer-symbolic",
+            Some(DeviceType::Watch) => "preferences-system-time-symbolic",
+            None => "bluetooth-symbolic",
         };
         
         let icon = gtk::Image::builder()
@@ -241,12 +535,78 @@ impl DeviceList {
             .valign(gtk::Align::Center)
             .build();
         
+        let name_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(4)
+            .build();
+        
         let name = gtk::Label::builder()
             .label(&device.name)
+            .tooltip_text(&device.name)
             .css_classes(["orbit-device-name"])
             .halign(gtk::Align::Start)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
             .build();
-        info_box.append(&name);
+        name_row.append(&name);
+        info_box.append(&name_row);
+        
+        if device.is_paired && !*self.readonly.borrow() {
+            let rename_btn = gtk::Button::builder()
+                .icon_name("document-edit-symbolic")
+                .css_classes(["orbit-button", "flat"])
+                .tooltip_text("Rename")
+                .valign(gtk::Align::Center)
+                .build();
+            name_row.append(&rename_btn);
+            
+            let name_entry = gtk::Entry::builder()
+                .text(&device.name)
+                .placeholder_text("Device name")
+                .visible(false)
+                .build();
+            info_box.append(&name_entry);
+            
+            let name_row_edit = name_row.clone();
+            let entry_edit = name_entry.clone();
+            let editing_edit = self.editing_path.clone();
+            let path_edit = device.path.clone();
+            rename_btn.connect_clicked(move |_| {
+                *editing_edit.borrow_mut() = Some(path_edit.clone());
+                name_row_edit.set_visible(false);
+                entry_edit.set_visible(true);
+                entry_edit.grab_focus();
+            });
+            
+            let list = self.clone();
+            let original_name = device.name.clone();
+            let path_commit = device.path.clone();
+            name_entry.connect_activate(move |entry| {
+                let alias = entry.text().trim().to_string();
+                *list.editing_path.borrow_mut() = None;
+                if alias.is_empty() || alias == original_name {
+                    let devices = list.devices.borrow().clone();
+                    list.render_devices(&devices);
+                    return;
+                }
+                if let Some(callback) = list.on_action.borrow().as_ref() {
+                    callback(path_commit.clone(), DeviceAction::Rename(alias));
+                }
+            });
+            
+            let list_cancel = self.clone();
+            let key_controller = gtk::EventControllerKey::new();
+            key_controller.connect_key_pressed(move |_, key, _, _| {
+                if key == gtk4::gdk::Key::Escape {
+                    *list_cancel.editing_path.borrow_mut() = None;
+                    let devices = list_cancel.devices.borrow().clone();
+                    list_cancel.render_devices(&devices);
+                    gtk4::glib::Propagation::Stop
+                } else {
+                    gtk4::glib::Propagation::Proceed
+                }
+            });
+            name_entry.add_controller(key_controller);
+        }
         
         let status_text = if device.is_connected {
             if let Some(ref battery) = device.battery_percentage {
@@ -256,6 +616,8 @@ impl DeviceList {
             }
         } else if device.is_paired {
             "Paired".to_string()
+        } else if let Some(rssi) = device.rssi {
+            format!("Available · {} ({} dBm)", rssi_proximity(rssi), rssi)
         } else {
             "Available".to_string()
         };
@@ -267,6 +629,14 @@ impl DeviceList {
             .build();
         info_box.append(&status);
         
+        if let (true, Some(player)) = (device.is_connected, device.media_player.as_ref()) {
+            info_box.append(&self.media_controls(&device.path, player));
+        }
+        
+        if device.is_connected && device.device_type.is_some_and(DeviceType::is_audio) {
+            info_box.append(&self.profile_expander(&device.path));
+        }
+        
         row.append(&info_box);
         
         let actions_box = gtk::Box::builder()
@@ -282,6 +652,133 @@ impl DeviceList {
         row
     }
 
+    /// "Audio profile" fold-out. Listing a card's profiles runs `pactl`, so that only
+    /// happens when it is opened.
+    fn profile_expander(&self, path: &str) -> gtk::Expander {
+        let state = self.audio_profiles.borrow().get(path).cloned();
+        let expander = gtk::Expander::builder()
+            .label("Audio profile")
+            .expanded(state.is_some())
+            .css_classes(["orbit-status"])
+            .build();
+        if let Some(profiles) = state {
+            expander.set_child(Some(&self.profile_picker(path, profiles.as_ref())));
+        }
+        
+        let list = self.clone();
+        let path = path.to_string();
+        expander.connect_expanded_notify(move |expander| {
+            if expander.is_expanded() {
+                list.audio_profiles.borrow_mut().insert(path.clone(), None);
+                expander.set_child(Some(&list.profile_picker(&path, None)));
+                if let Some(callback) = list.on_profiles_requested.borrow().as_ref() {
+                    callback(path.clone());
+                }
+            } else {
+                list.audio_profiles.borrow_mut().remove(&path);
+                expander.set_child(gtk::Widget::NONE);
+            }
+        });
+        expander
+    }
+    
+    /// Dropdown of `card`'s profiles, or a note while they load or when there is no choice.
+    fn profile_picker(&self, path: &str, card: Option<&CardProfiles>) -> gtk::Widget {
+        let note = |text: &str| -> gtk::Widget {
+            gtk::Label::builder()
+                .label(text)
+                .css_classes(["orbit-status"])
+                .halign(gtk::Align::Start)
+                .build()
+                .upcast()
+        };
+        let Some(card) = card else {
+            return note("Loading…");
+        };
+        let active = card.profiles.iter().position(|p| Some(&p.name) == card.active.as_ref());
+        if card.profiles.is_empty() || (card.profiles.len() == 1 && active.is_some()) {
+            return note("No other profiles available");
+        }
+        
+        let mut labels: Vec<String> = card.profiles.iter().map(profile_label).collect();
+        // A profile that isn't listed (e.g. "off") must not pass for the first entry
+        let offset = if active.is_none() {
+            labels.insert(0, "Unknown".to_string());
+            1
+        } else {
+            0
+        };
+        let label_refs: Vec<&str> = labels.iter().map(|s| s.as_str()).collect();
+        let dropdown = gtk::DropDown::from_strings(&label_refs);
+        dropdown.set_halign(gtk::Align::Start);
+        dropdown.set_tooltip_text(Some("Audio profile"));
+        dropdown.set_selected(active.unwrap_or(0) as u32);
+        
+        let path = path.to_string();
+        let profiles = card.profiles.clone();
+        let on_action = self.on_action.clone();
+        dropdown.connect_selected_notify(move |dropdown| {
+            let selected = (dropdown.selected() as usize).checked_sub(offset);
+            if let Some(profile) = selected.and_then(|i| profiles.get(i)) {
+                if let Some(callback) = on_action.borrow().as_ref() {
+                    callback(path.clone(), DeviceAction::SetProfile(profile.name.clone()));
+                }
+            }
+        });
+        dropdown.upcast()
+    }
+
+    /// Now-playing line with previous, play/pause and next buttons.
+    fn media_controls(&self, path: &str, player: &MediaPlayer) -> gtk::Box {
+        let media_box = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(2)
+            .build();
+        
+        let playing = player.status == "playing";
+        let buttons = [
+            ("media-skip-backward-symbolic", "Previous", MediaCommand::Previous),
+            if playing {
+                ("media-playback-pause-symbolic", "Pause", MediaCommand::Pause)
+            } else {
+                ("media-playback-start-symbolic", "Play", MediaCommand::Play)
+            },
+            ("media-skip-forward-symbolic", "Next", MediaCommand::Next),
+        ];
+        for (icon, tooltip, command) in buttons {
+            let btn = gtk::Button::builder()
+                .icon_name(icon)
+                .tooltip_text(tooltip)
+                .css_classes(["orbit-button", "flat"])
+                .valign(gtk::Align::Center)
+                .build();
+            let path = path.to_string();
+            let on_action = self.on_action.clone();
+            btn.connect_clicked(move |_| {
+                if let Some(callback) = on_action.borrow().as_ref() {
+                    callback(path.clone(), DeviceAction::Media(command));
+                }
+            });
+            media_box.append(&btn);
+        }
+        
+        let track_text = match (player.title.is_empty(), player.artist.is_empty()) {
+            (true, _) => "Nothing playing".to_string(),
+            (false, true) => player.title.clone(),
+            (false, false) => format!("{} — {}", player.title, player.artist),
+        };
+        let track = gtk::Label::builder()
+            .label(&track_text)
+            .tooltip_text(&track_text)
+            .css_classes(["orbit-status"])
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .max_width_chars(24)
+            .halign(gtk::Align::Start)
+            .build();
+        media_box.append(&track);
+        media_box
+    }
+    
     fn build_actions_box_content(&self, actions_box: &gtk::Box, device: &BluetoothDevice) {
         let is_busy = self.action_path.borrow().as_deref() == Some(&device.path);
         
@@ -302,6 +799,11 @@ impl DeviceList {
                 Some(DeviceAction::Disconnect) => "Disconnecting...",
                 Some(DeviceAction::Pair) => "Pairing...",
                 Some(DeviceAction::Forget) => "Removing...",
+                Some(DeviceAction::Trust(_)) => "Updating...",
+                Some(DeviceAction::SetProfile(_)) => "Switching...",
+                Some(DeviceAction::Rename(_)) => "Renaming...",
+                Some(DeviceAction::SendFile(_)) => "Sending...",
+                Some(DeviceAction::Media(_)) => "Working...",
                 None => "Working...",
             };
             
@@ -314,6 +816,8 @@ impl DeviceList {
             working_box.append(&label);
             actions_box.append(&working_box);
         } else {
+            let readonly = *self.readonly.borrow();
+            
             let (action_label, action) = if device.is_connected {
                 ("Disconnect", DeviceAction::Disconnect)
             } else if device.is_paired {
@@ -329,6 +833,7 @@ impl DeviceList {
                 } else {
                     vec!["orbit-button", "flat"]
                 })
+                .visible(!(readonly && matches!(action, DeviceAction::Pair)))
                 .build();
             
             let path = device.path.clone();
@@ -341,7 +846,74 @@ impl DeviceList {
             
             actions_box.append(&action_btn);
             
-            if device.is_paired {
+            if device.is_paired && device.device_type == Some(DeviceType::Phone) {
+                let send_btn = gtk::Button::builder()
+                    .label("Send File")
+                    .css_classes(["orbit-button", "flat"])
+                    .build();
+                
+                let path = device.path.clone();
+                let on_action = self.on_action.clone();
+                send_btn.connect_clicked(move |btn| {
+                    let parent = btn.root().and_then(|root| root.downcast::<gtk::Window>().ok());
+                    let chooser = gtk::FileChooserNative::new(
+                        Some("Send File"),
+                        parent.as_ref(),
+                        gtk::FileChooserAction::Open,
+                        Some("Send"),
+                        Some("Cancel"),
+                    );
+                    // GTK doesn't keep native dialogs alive; hold it until it answers
+                    let keep_alive = Rc::new(RefCell::new(Some(chooser.clone())));
+                    let path = path.clone();
+                    let on_action = on_action.clone();
+                    chooser.connect_response(move |chooser, response| {
+                        if response == gtk::ResponseType::Accept {
+                            let file = chooser.file().and_then(|f| f.path());
+                            if let (Some(file), Some(callback)) = (file, on_action.borrow().as_ref()) {
+                                callback(path.clone(), DeviceAction::SendFile(file.to_string_lossy().to_string()));
+                            }
+                        }
+                        chooser.destroy();
+                        keep_alive.borrow_mut().take();
+                    });
+                    chooser.show();
+                });
+                
+                actions_box.append(&send_btn);
+            }
+            
+            if device.is_paired && !readonly {
+                let trust_box = gtk::Box::builder()
+                    .orientation(Orientation::Horizontal)
+                    .spacing(4)
+                    .valign(gtk::Align::Center)
+                    .tooltip_text("Trusted devices can reconnect without confirmation")
+                    .build();
+                
+                let trust_label = gtk::Label::builder()
+                    .label("Trust")
+                    .css_classes(["orbit-status"])
+                    .build();
+                
+                let trust_switch = gtk::Switch::builder()
+                    .active(device.is_trusted)
+                    .css_classes(["orbit-toggle-switch"])
+                    .valign(gtk::Align::Center)
+                    .build();
+                
+                let path = device.path.clone();
+                let on_action = self.on_action.clone();
+                trust_switch.connect_active_notify(move |switch| {
+                    if let Some(callback) = on_action.borrow().as_ref() {
+                        callback(path.clone(), DeviceAction::Trust(switch.is_active()));
+                    }
+                });
+                
+                trust_box.append(&trust_label);
+                trust_box.append(&trust_switch);
+                actions_box.append(&trust_box);
+                
                 let forget_btn = gtk::Button::builder()
                     .label("Forget")
                     .css_classes(["orbit-button", "destructive", "flat"])
@@ -360,6 +932,15 @@ impl DeviceList {
         }
     }
     
+    /// Sections to show folded; takes effect on the next render.
+    pub fn set_collapsed_sections(&self, keys: &[String]) {
+        *self.collapsed_sections.borrow_mut() = keys.iter().cloned().collect();
+    }
+    
+    pub fn set_on_section_toggled<F: Fn(String, bool) + 'static>(&self, callback: F) {
+        *self.on_section_toggled.borrow_mut() = Some(Rc::new(callback));
+    }
+    
     pub fn widget(&self) -> &gtk::Box {
         &self.container
     }
@@ -367,8 +948,93 @@ impl DeviceList {
     pub fn scan_button(&self) -> &gtk::Button {
         &self.scan_button
     }
+
+    pub fn is_scanning(&self) -> bool {
+        *self.scanning.borrow()
+    }
+
+    /// Marks discovery as running. The scan button stays clickable to stop it early.
+    pub fn begin_scan(&self) {
+        *self.scanning.borrow_mut() = true;
+        self.scan_button.set_sensitive(true);
+        self.scan_button.set_label(" Stop Scanning");
+        self.show_scanning();
+    }
+
+    /// Disables the scan button until discovery has actually stopped.
+    pub fn stopping_scan(&self) {
+        self.scan_button.set_sensitive(false);
+        self.scan_button.set_label(" Stopping...");
+    }
+
+    pub fn finish_scan(&self) {
+        *self.scanning.borrow_mut() = false;
+        self.scan_button.set_sensitive(true);
+        self.scan_button.set_label(" Scan for Devices");
+    }
+    
+    pub fn device(&self, path: &str) -> Option<BluetoothDevice> {
+        self.devices.borrow().iter().find(|d| d.path == path).cloned()
+    }
     
     pub fn set_on_action<F: Fn(String, DeviceAction) + 'static>(&self, callback: F) {
         *self.on_action.borrow_mut() = Some(Rc::new(callback));
     }
+    
+    /// Called with a device path when its audio profile fold-out is opened.
+    pub fn set_on_profiles_requested<F: Fn(String) + 'static>(&self, callback: F) {
+        *self.on_profiles_requested.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    pub fn set_on_disconnect_all<F: Fn() + 'static>(&self, callback: F) {
+        *self.on_disconnect_all.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    pub fn set_on_discoverable_toggle<F: Fn(bool) + 'static>(&self, callback: F) {
+        *self.on_discoverable_toggle.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    pub fn set_on_adapter_renamed<F: Fn(String, String) + 'static>(&self, callback: F) {
+        *self.on_adapter_renamed.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    pub fn set_on_adapter_selected<F: Fn(String) + 'static>(&self, callback: F) {
+        *self.on_adapter_selected.borrow_mut() = Some(Rc::new(callback));
+    }
+}
+
+/// Rough distance hint for an RSSI reading in dBm.
+fn rssi_proximity(rssi: i16) -> &'static str {
+    if rssi >= -60 {
+        "Very close"
+    } else if rssi >= -75 {
+        "Nearby"
+    } else {
+        "Far"
+    }
+}
+
+/// Short dropdown label for a card profile, e.g. "Hi-Fi (AAC)" or "Headset".
+fn profile_label(profile: &AudioProfile) -> String {
+    let (base, rest) = if let Some(rest) = profile.name.strip_prefix("a2dp-sink") {
+        ("Hi-Fi", rest)
+    } else if let Some(rest) = profile.name.strip_prefix("headset-head-unit") {
+        ("Headset", rest)
+    } else if let Some(rest) = profile.name.strip_prefix("handsfree-head-unit") {
+        ("Headset", rest)
+    } else {
+        return profile.description.clone();
+    };
+    match rest.strip_prefix('-') {
+        Some(codec) if !codec.is_empty() => format!("{} ({})", base, codec.to_uppercase()),
+        _ => base.to_string(),
+    }
+}
+
+/// Fills the adapter line with the adapter's alias and address.
+fn show_adapter_info(name_label: &gtk::Label, address_label: &gtk::Label, adapter: Option<&BluetoothAdapter>) {
+    let name = adapter.map(|a| a.name.as_str()).unwrap_or_default();
+    name_label.set_label(name);
+    name_label.set_tooltip_text(Some(name));
+    address_label.set_label(adapter.map(|a| a.address.as_str()).unwrap_or_default());
 }
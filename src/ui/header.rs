@@ -10,9 +10,13 @@ pub struct Header {
     saved_tab: gtk::Button,
     bluetooth_tab: gtk::Button,
     power_switch: gtk::Switch,
+    airplane_button: gtk::ToggleButton,
     power_box: gtk::Box,
     power_label: gtk::Label,
+    state_label: gtk::Label,
     is_programmatic_update: Rc<RefCell<bool>>,
+    readonly: Rc<RefCell<bool>>,
+    wifi_available: Rc<RefCell<bool>>,
 }
 
 impl Header {
@@ -37,10 +41,24 @@ impl Header {
         let title = gtk::Label::builder()
             .label("Orbit")
             .css_classes(["orbit-title"])
-            .hexpand(true)
             .halign(gtk::Align::Start)
             .build();
         
+        // NetworkManager's overall state, e.g. "Connecting…" or "No internet"
+        let state_label = gtk::Label::builder()
+            .css_classes(["orbit-status", "orbit-network-state"])
+            .halign(gtk::Align::Start)
+            .visible(false)
+            .build();
+        
+        let title_box = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .hexpand(true)
+            .valign(gtk::Align::Center)
+            .build();
+        title_box.append(&title);
+        title_box.append(&state_label);
+        
         let power_switch = gtk::Switch::builder()
             .css_classes(["orbit-toggle-switch"])
             .active(false)
@@ -52,6 +70,13 @@ impl Header {
             .css_classes(["orbit-status"])
             .build();
         
+        let airplane_button = gtk::ToggleButton::builder()
+            .icon_name("airplane-mode-symbolic")
+            .css_classes(["orbit-button", "flat"])
+            .tooltip_text("Airplane mode: turn WiFi and Bluetooth off")
+            .valign(gtk::Align::Center)
+            .build();
+        
         let power_box = gtk::Box::builder()
             .orientation(Orientation::Horizontal)
             .spacing(8)
@@ -61,7 +86,8 @@ impl Header {
         power_box.append(&power_switch);
         
         title_row.append(&orbit_icon);
-        title_row.append(&title);
+        title_row.append(&title_box);
+        title_row.append(&airplane_button);
         title_row.append(&power_box);
         
         let tab_bar = gtk::Box::builder()
@@ -101,9 +127,13 @@ impl Header {
             saved_tab,
             bluetooth_tab,
             power_switch,
+            airplane_button,
             power_box,
             power_label,
+            state_label,
             is_programmatic_update: Rc::new(RefCell::new(false)),
+            readonly: Rc::new(RefCell::new(false)),
+            wifi_available: Rc::new(RefCell::new(true)),
         }
     }
     
@@ -113,11 +143,40 @@ impl Header {
     
     pub fn set_power_state(&self, enabled: bool) {
         *self.is_programmatic_update.borrow_mut() = true;
-        self.power_switch.set_sensitive(true);
+        self.power_switch.set_sensitive(!*self.readonly.borrow());
         self.power_switch.set_active(enabled);
         *self.is_programmatic_update.borrow_mut() = false;
     }
     
+    /// Without a WiFi backend the WiFi switch stays off and insensitive on the WiFi tab.
+    pub fn set_wifi_available(&self, available: bool) {
+        *self.wifi_available.borrow_mut() = available;
+        if !available && self.wifi_tab.has_css_class("active") {
+            self.set_power_state(false);
+            self.power_switch.set_sensitive(false);
+        }
+    }
+    
+    /// Shows NetworkManager's state under the title; `None` hides the line.
+    pub fn set_network_state(&self, label: Option<&str>) {
+        self.state_label.set_visible(label.is_some());
+        self.state_label.set_label(label.unwrap_or_default());
+    }
+    
+    pub fn set_airplane_state(&self, enabled: bool) {
+        *self.is_programmatic_update.borrow_mut() = true;
+        self.airplane_button.set_active(enabled);
+        *self.is_programmatic_update.borrow_mut() = false;
+    }
+    
+    pub fn set_readonly(&self, readonly: bool) {
+        *self.readonly.borrow_mut() = readonly;
+        self.airplane_button.set_sensitive(!readonly);
+        // Leaving read-only mode doesn't revive the WiFi switch when there is no backend
+        let wifi_missing = self.wifi_tab.has_css_class("active") && !*self.wifi_available.borrow();
+        self.power_switch.set_sensitive(!readonly && !wifi_missing);
+    }
+    
     pub fn is_programmatic_update(&self) -> bool {
         *self.is_programmatic_update.borrow()
     }
@@ -126,6 +185,10 @@ impl Header {
         &self.power_switch
     }
 
+    pub fn airplane_button(&self) -> &gtk::ToggleButton {
+        &self.airplane_button
+    }
+
     pub fn wifi_tab(&self) -> &gtk::Button {
         &self.wifi_tab
     }
@@ -148,6 +211,10 @@ impl Header {
                 self.wifi_tab.add_css_class("active");
                 self.power_box.set_visible(true);
                 self.power_label.set_label("WiFi");
+                if !*self.wifi_available.borrow() {
+                    self.set_power_state(false);
+                    self.power_switch.set_sensitive(false);
+                }
             }
             "saved" => {
                 self.saved_tab.add_css_class("active");
@@ -3,6 +3,7 @@ pub mod header;
 pub mod network_list;
 pub mod device_list;
 pub mod saved_networks_list;
+pub mod section;
 
-pub use window::OrbitWindow;
+pub use window::{OrbitWindow, sanitize_error_message};
 pub use device_list::DeviceAction;
@@ -1,22 +1,65 @@
 use gtk4::prelude::*;
-use gtk4::{self as gtk, Orientation};
+use gtk4::{self as gtk, glib, Orientation};
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::collections::HashMap;
-use crate::dbus::network_manager::{AccessPoint, SecurityType};
+use std::collections::{HashMap, HashSet};
+use super::section::{collapsible_section, CollapsedSections, SectionToggled};
+use crate::dbus::network_manager::{self, AccessPoint, SecurityType};
+use crate::dbus::Feature;
+
+/// Widgets of a rendered network row, kept so a rescan can refresh it in place.
+struct NetworkRow {
+    row: gtk::Box,
+    signal_bars: gtk::Box,
+    status: gtk::Label,
+    actions: gtk::Box,
+    /// Entries of the "N APs" list, by BSSID.
+    ap_entries: Vec<(String, gtk::Button)>,
+    /// The network as last rendered, for the right-click BSSID menu.
+    network: Rc<RefCell<AccessPoint>>,
+}
 
 #[derive(Clone)]
 pub struct NetworkList {
     container: gtk::Box,
     list_box: gtk::Box,
+    scrolled: gtk::ScrolledWindow,
     scan_button: gtk::Button,
+    hidden_button: gtk::Button,
+    scanning: Rc<RefCell<bool>>,
     networks: Rc<RefCell<Vec<AccessPoint>>>,
-    row_actions: Rc<RefCell<HashMap<String, gtk::Box>>>,
+    rows: Rc<RefCell<HashMap<String, NetworkRow>>>,
     on_connect: Rc<RefCell<Option<Rc<dyn Fn(AccessPoint)>>>>,
+    on_connect_bssid: Rc<RefCell<Option<Rc<dyn Fn(AccessPoint)>>>>,
+    on_connect_wps: Rc<RefCell<Option<Rc<dyn Fn(AccessPoint)>>>>,
     on_connect_hidden: Rc<RefCell<Option<Rc<dyn Fn()>>>>,
     on_details: Rc<RefCell<Option<Rc<dyn Fn(String)>>>>,
+    on_forget: Rc<RefCell<Option<Rc<dyn Fn(String)>>>>,
+    readonly: Rc<RefCell<bool>>,
+    /// What the WiFi backend implements; rows leave out the rest.
+    features: Rc<RefCell<HashSet<Feature>>>,
+    on_cancel_connect: Rc<RefCell<Option<Rc<dyn Fn(String)>>>>,
     connecting_ssid: Rc<RefCell<Option<String>>>,
     disconnecting_ssid: Rc<RefCell<Option<String>>>,
+    /// SSID and reason of the last failed connection attempt, flagged on its row until
+    /// the next attempt.
+    failed_connect: Rc<RefCell<Option<(String, String)>>>,
+    use_dbm: Rc<RefCell<bool>>,
+    accent_signal_bars: Rc<RefCell<bool>>,
+    /// NetworkManager's last reported connectivity (NM_CONNECTIVITY_*), flagged on the
+    /// connected row when it is a portal or limited.
+    connectivity: Rc<RefCell<u32>>,
+    show_interface: Rc<RefCell<bool>>,
+    /// When each connected SSID was first seen connected. NetworkManager only records the
+    /// last activation in `connection.timestamp` and refreshes it while connected, so the
+    /// session length is tracked here instead.
+    connected_since: Rc<RefCell<HashMap<String, std::time::Instant>>>,
+    /// SSIDs with a saved profile, marked on in-range rows.
+    saved_ssids: Rc<RefCell<HashSet<String>>>,
+    /// SSIDs whose access point sub-list is unfolded; kept across rescans.
+    expanded_groups: Rc<RefCell<HashSet<String>>>,
+    collapsed_sections: CollapsedSections,
+    on_section_toggled: SectionToggled,
 }
 
 impl NetworkList {
@@ -67,14 +110,33 @@ impl NetworkList {
         let list = Self {
             container,
             list_box,
+            scrolled,
             scan_button,
+            hidden_button: hidden_button.clone(),
+            scanning: Rc::new(RefCell::new(false)),
             networks: Rc::new(RefCell::new(Vec::new())),
-            row_actions: Rc::new(RefCell::new(HashMap::new())),
+            rows: Rc::new(RefCell::new(HashMap::new())),
             on_connect: Rc::new(RefCell::new(None)),
+            on_connect_bssid: Rc::new(RefCell::new(None)),
+            on_connect_wps: Rc::new(RefCell::new(None)),
             on_connect_hidden: Rc::new(RefCell::new(None)),
             on_details: Rc::new(RefCell::new(None)),
+            on_forget: Rc::new(RefCell::new(None)),
+            readonly: Rc::new(RefCell::new(false)),
+            features: Rc::new(RefCell::new(Feature::ALL.into_iter().collect())),
+            on_cancel_connect: Rc::new(RefCell::new(None)),
             connecting_ssid: Rc::new(RefCell::new(None)),
             disconnecting_ssid: Rc::new(RefCell::new(None)),
+            failed_connect: Rc::new(RefCell::new(None)),
+            use_dbm: Rc::new(RefCell::new(false)),
+            accent_signal_bars: Rc::new(RefCell::new(true)),
+            connectivity: Rc::new(RefCell::new(0)),
+            show_interface: Rc::new(RefCell::new(false)),
+            connected_since: Rc::new(RefCell::new(HashMap::new())),
+            saved_ssids: Rc::new(RefCell::new(HashSet::new())),
+            expanded_groups: Rc::new(RefCell::new(HashSet::new())),
+            collapsed_sections: Rc::new(RefCell::new(HashSet::new())),
+            on_section_toggled: Rc::new(RefCell::new(None)),
         };
 
         let on_connect_hidden_cb = list.on_connect_hidden.clone();
@@ -96,6 +158,24 @@ impl NetworkList {
         self.list_box.append(&placeholder);
     }
     
+    /// Replaces the list with an explanation when no WiFi backend can be reached.
+    pub fn show_unavailable(&self) {
+        self.rows.borrow_mut().clear();
+        self.networks.borrow_mut().clear();
+        while let Some(child) = self.list_box.first_child() {
+            self.list_box.remove(&child);
+        }
+        
+        let placeholder = gtk::Label::builder()
+            .label("Neither NetworkManager nor iwd is running.\nOrbit manages WiFi through one of them; start it (e.g. systemctl start NetworkManager) and reopen Orbit.")
+            .css_classes(["orbit-placeholder"])
+            .wrap(true)
+            .justify(gtk::Justification::Center)
+            .build();
+        self.list_box.append(&placeholder);
+        self.scan_button.set_sensitive(false);
+    }
+    
     fn show_placeholder(&self) {
         let placeholder = gtk::Label::builder()
             .label("Click 'Scan' to find networks")
@@ -106,15 +186,15 @@ impl NetworkList {
     
     fn signal_bar_count(strength: u8) -> u8 {
         match strength {
-            0..=24 => 1,
+            0 => 0,
+            1..=24 => 1,
             25..=49 => 2,
             50..=74 => 3,
             _ => 4,
         }
     }
     
-    fn build_signal_bars(strength: u8, is_connected: bool) -> gtk::Box {
-        let active_bars = Self::signal_bar_count(strength);
+    fn build_signal_bars(strength: u8, accent: bool, use_dbm: bool) -> gtk::Box {
         let heights = [4, 8, 12, 16];
         
         let container = gtk::Box::builder()
@@ -124,36 +204,85 @@ impl NetworkList {
             .halign(gtk::Align::Center)
             .build();
         
-        for (i, &h) in heights.iter().enumerate() {
-            let bar_num = (i + 1) as u8;
-            let active = bar_num <= active_bars;
-            
+        for &h in heights.iter() {
             let bar = gtk::Box::builder()
                 .width_request(3)
                 .height_request(h)
                 .valign(gtk::Align::End)
                 .build();
-            
-            if active {
-                if is_connected {
-                    bar.add_css_class("orbit-signal-bar-active-accent");
-                } else {
-                    bar.add_css_class("orbit-signal-bar-active");
-                }
-            } else {
-                bar.add_css_class("orbit-signal-bar-inactive");
-            }
-            
             container.append(&bar);
         }
         
+        Self::paint_signal_bars(&container, strength, accent, use_dbm);
         container
     }
     
+    /// Lights the bars built by `build_signal_bars` for `strength` and updates the tooltip.
+    fn paint_signal_bars(container: &gtk::Box, strength: u8, accent: bool, use_dbm: bool) {
+        let active_bars = Self::signal_bar_count(strength);
+        
+        let tooltip = if strength == 0 {
+            "Signal: not measured yet".to_string()
+        } else if use_dbm {
+            format!("Signal: ≈{} dBm ({}%)", network_manager::strength_to_dbm(strength), strength)
+        } else {
+            format!("Signal: {}%", strength)
+        };
+        container.set_tooltip_text(Some(&tooltip));
+        
+        let mut child = container.first_child();
+        let mut bar_num = 1;
+        while let Some(bar) = child {
+            let class = if bar_num > active_bars {
+                "orbit-signal-bar-inactive"
+            } else if accent {
+                "orbit-signal-bar-active-accent"
+            } else {
+                "orbit-signal-bar-active"
+            };
+            bar.set_css_classes(&[class]);
+            child = bar.next_sibling();
+            bar_num += 1;
+        }
+    }
+    
+    pub fn set_signal_unit(&self, use_dbm: bool) {
+        *self.use_dbm.borrow_mut() = use_dbm;
+        let networks = self.networks.borrow().clone();
+        if !networks.is_empty() {
+            self.render_networks(&networks);
+        }
+    }
+    
+    pub fn set_accent_signal_bars(&self, accent: bool) {
+        *self.accent_signal_bars.borrow_mut() = accent;
+        let networks = self.networks.borrow().clone();
+        if !networks.is_empty() {
+            self.render_networks(&networks);
+        }
+    }
+    
+    pub fn set_connectivity(&self, connectivity: u32) {
+        if *self.connectivity.borrow() == connectivity {
+            return;
+        }
+        *self.connectivity.borrow_mut() = connectivity;
+        let networks = self.networks.borrow().clone();
+        if networks.iter().any(|n| n.is_connected) {
+            self.render_networks(&networks);
+        }
+    }
+    
     pub fn set_connecting_ssid(&self, ssid: Option<String>) {
         let old_ssid = self.connecting_ssid.borrow().clone();
         *self.connecting_ssid.borrow_mut() = ssid.clone();
         
+        // A new attempt clears the failure mark, which lives outside the actions box
+        if ssid.is_some() && self.failed_connect.borrow_mut().take().is_some() {
+            let networks = self.networks.borrow().clone();
+            self.render_networks(&networks);
+            return;
+        }
         if let Some(ref s) = ssid {
             self.update_single_row_actions(s);
         }
@@ -174,28 +303,171 @@ impl NetworkList {
         }
     }
     
+    /// Ends the pending connection attempt as failed and flags its row with `reason`.
+    pub fn fail_connect(&self, reason: &str) {
+        let ssid = match self.connecting_ssid.borrow_mut().take() {
+            Some(ssid) => ssid,
+            None => return,
+        };
+        *self.failed_connect.borrow_mut() = Some((ssid, reason.to_string()));
+        let networks = self.networks.borrow().clone();
+        if !networks.is_empty() {
+            self.render_networks(&networks);
+        }
+    }
+    
+    fn failure_for(&self, ssid: &str) -> Option<String> {
+        self.failed_connect.borrow().as_ref()
+            .filter(|(failed, _)| failed == ssid)
+            .map(|(_, reason)| reason.clone())
+    }
+    
     fn update_single_row_actions(&self, ssid: &str) {
         let networks = self.networks.borrow();
         if let Some(network) = networks.iter().find(|n| n.ssid == ssid) {
-            let actions_map = self.row_actions.borrow();
-            if let Some(actions_box) = actions_map.get(ssid) {
-                while let Some(child) = actions_box.first_child() {
-                    actions_box.remove(&child);
+            let rows = self.rows.borrow();
+            if let Some(row) = rows.get(ssid) {
+                while let Some(child) = row.actions.first_child() {
+                    row.actions.remove(&child);
                 }
-                self.build_actions_box_content(actions_box, network);
+                self.build_actions_box_content(&row.actions, network);
             }
         }
     }
 
     pub fn set_networks(&self, networks: Vec<AccessPoint>) {
-        *self.networks.borrow_mut() = networks.clone();
-        *self.connecting_ssid.borrow_mut() = None;
-        *self.disconnecting_ssid.borrow_mut() = None;
-        self.render_networks(&networks);
+        {
+            let mut since = self.connected_since.borrow_mut();
+            since.retain(|ssid, _| networks.iter().any(|n| n.is_connected && &n.ssid == ssid));
+            for network in networks.iter().filter(|n| n.is_connected) {
+                since.entry(network.ssid.clone()).or_insert_with(std::time::Instant::now);
+            }
+        }
+        let now_connected = self.failed_connect.borrow().as_ref()
+            .is_some_and(|(ssid, _)| networks.iter().any(|n| n.is_connected && &n.ssid == ssid));
+        if now_connected {
+            *self.failed_connect.borrow_mut() = None;
+        }
+        let previous = self.networks.replace(networks.clone());
+        let connecting = self.connecting_ssid.borrow_mut().take();
+        let disconnecting = self.disconnecting_ssid.borrow_mut().take();
+        if self.same_rows(&previous, &networks) {
+            // Only signal and order moved, so keep the rows (and focus and scroll) alive
+            self.update_rows(&networks);
+            for ssid in connecting.iter().chain(disconnecting.iter()) {
+                self.update_single_row_actions(ssid);
+            }
+        } else {
+            self.render_networks(&networks);
+        }
+    }
+    
+    /// Whether `new` lists the same rows as the rendered `old`, differing only in signal
+    /// strength and order.
+    fn same_rows(&self, old: &[AccessPoint], new: &[AccessPoint]) -> bool {
+        fn shape(n: &AccessPoint) -> (&str, bool, &SecurityType, &str, Vec<&str>) {
+            let mut siblings: Vec<&str> = n.siblings.iter().map(|ap| ap.bssid.as_str()).collect();
+            siblings.sort_unstable();
+            (&n.ssid, n.is_connected, &n.security, &n.bssid, siblings)
+        }
+        let rows = self.rows.borrow();
+        !new.is_empty()
+            && old.len() == new.len()
+            && rows.len() == new.len()
+            && multiple_interfaces(old) == multiple_interfaces(new)
+            && new.iter().all(|n| rows.contains_key(&n.ssid) && old.iter().any(|o| shape(o) == shape(n)))
+    }
+    
+    /// Refreshes signal bars, status lines and AP entries of the rendered rows and moves
+    /// them into the new order.
+    fn update_rows(&self, networks: &[AccessPoint]) {
+        let use_dbm = *self.use_dbm.borrow();
+        let accent = *self.accent_signal_bars.borrow();
+        let show_interface = *self.show_interface.borrow();
+        let rows = self.rows.borrow();
+        
+        for network in networks {
+            let Some(row) = rows.get(&network.ssid) else { continue };
+            *row.network.borrow_mut() = network.clone();
+            Self::paint_signal_bars(&row.signal_bars, network.signal_strength, accent && network.is_connected, use_dbm);
+            row.status.set_label(&self.status_text(network, use_dbm));
+            for (bssid, entry) in &row.ap_entries {
+                if let Some(ap) = std::iter::once(network).chain(network.siblings.iter()).find(|ap| &ap.bssid == bssid) {
+                    entry.set_label(&ap_label(ap, use_dbm, show_interface));
+                }
+            }
+        }
+        
+        // Connected and available rows live in separate sections, so order each on its own
+        for connected in [true, false] {
+            let mut previous: Option<gtk::Box> = None;
+            for network in networks.iter().filter(|n| n.is_connected == connected) {
+                let Some(row) = rows.get(&network.ssid) else { continue };
+                if let Some(section) = row.row.parent().and_downcast::<gtk::Box>() {
+                    section.reorder_child_after(&row.row, previous.as_ref());
+                }
+                previous = Some(row.row.clone());
+            }
+        }
+    }
+    
+    pub fn set_readonly(&self, readonly: bool) {
+        *self.readonly.borrow_mut() = readonly;
+        let networks = self.networks.borrow().clone();
+        if !networks.is_empty() {
+            self.render_networks(&networks);
+        }
+    }
+    
+    /// Hides the controls of backend features that aren't in `features`.
+    pub fn set_features(&self, features: &[Feature]) {
+        let features: HashSet<Feature> = features.iter().copied().collect();
+        if *self.features.borrow() == features {
+            return;
+        }
+        self.hidden_button.set_visible(features.contains(&Feature::Hidden));
+        *self.features.borrow_mut() = features;
+        let networks = self.networks.borrow().clone();
+        if !networks.is_empty() {
+            self.render_networks(&networks);
+        }
+    }
+    
+    fn supports(&self, feature: Feature) -> bool {
+        self.features.borrow().contains(&feature)
+    }
+    
+    /// Whether `ssid` had a saved profile at the last `set_saved_ssids`.
+    pub fn is_saved(&self, ssid: &str) -> bool {
+        self.saved_ssids.borrow().contains(ssid)
+    }
+
+    /// Updates which SSIDs have saved profiles and re-renders if the marks changed.
+    pub fn set_saved_ssids(&self, ssids: HashSet<String>) {
+        if *self.saved_ssids.borrow() == ssids {
+            return;
+        }
+        *self.saved_ssids.borrow_mut() = ssids;
+        let networks = self.networks.borrow().clone();
+        if !networks.is_empty() {
+            self.render_networks(&networks);
+        }
+    }
+    
+    /// How long `ssid` has been connected, counted from when Orbit first saw it connected.
+    pub fn connected_for(&self, ssid: &str) -> Option<std::time::Duration> {
+        self.connected_since.borrow().get(ssid).map(|since| since.elapsed())
     }
     
     fn render_networks(&self, networks: &[AccessPoint]) {
-        self.row_actions.borrow_mut().clear();
+        // Emptying the list clamps the scroll to the top; put it back once the new rows are laid out
+        let adjustment = self.scrolled.vadjustment();
+        let scroll = adjustment.value();
+        if scroll > 0.0 {
+            glib::idle_add_local_once(move || adjustment.set_value(scroll));
+        }
+        
+        self.rows.borrow_mut().clear();
 
         while let Some(child) = self.list_box.first_child() {
             self.list_box.remove(&child);
@@ -206,34 +478,24 @@ impl NetworkList {
             return;
         }
         
+        *self.show_interface.borrow_mut() = multiple_interfaces(networks);
+        
         let connected_networks: Vec<&AccessPoint> = networks.iter().filter(|n| n.is_connected).collect();
         let available_networks: Vec<&AccessPoint> = networks.iter().filter(|n| !n.is_connected).collect();
         
         if !connected_networks.is_empty() {
-            let section_header = gtk::Label::builder()
-                .label("ACTIVE CONNECTION")
-                .css_classes(["orbit-section-header"])
-                .halign(gtk::Align::Start)
-                .build();
-            self.list_box.append(&section_header);
-            
+            let section = collapsible_section(&self.list_box, "ACTIVE CONNECTION", "wifi-active", &self.collapsed_sections, &self.on_section_toggled);
             for network in connected_networks {
                 let row = self.create_network_row(network);
-                self.list_box.append(&row);
+                section.append(&row);
             }
         }
         
         if !available_networks.is_empty() {
-            let section_header = gtk::Label::builder()
-                .label("AVAILABLE NETWORKS")
-                .css_classes(["orbit-section-header"])
-                .halign(gtk::Align::Start)
-                .build();
-            self.list_box.append(&section_header);
-            
+            let section = collapsible_section(&self.list_box, "AVAILABLE NETWORKS", "wifi-available", &self.collapsed_sections, &self.on_section_toggled);
             for network in available_networks {
                 let row = self.create_network_row(network);
-                self.list_box.append(&row);
+                section.append(&row);
             }
         }
     }
@@ -259,22 +521,41 @@ impl NetworkList {
         row.add_controller(focus_in);
         row.add_controller(focus_out);
 
-        if network.is_connected {
+        // Right-click lists every BSSID for this SSID so the user can pick one
+        let secondary_click = gtk::GestureClick::new();
+        secondary_click.set_button(3);
+        let row_menu = row.clone();
+        let network_menu = Rc::new(RefCell::new(network.clone()));
+        let network_row = network_menu.clone();
+        let on_connect_bssid = self.on_connect_bssid.clone();
+        let use_dbm_menu = self.use_dbm.clone();
+        let show_interface_menu = self.show_interface.clone();
+        secondary_click.connect_pressed(move |_, _, _, _| {
+            Self::show_bssid_menu(&row_menu, &network_menu.borrow(), *use_dbm_menu.borrow(), *show_interface_menu.borrow(), on_connect_bssid.clone());
+        });
+        if self.supports(Feature::AccessPointChoice) {
+            row.add_controller(secondary_click);
+        }
+
+        let use_dbm = *self.use_dbm.borrow();
+        let signal_bars = if network.is_connected {
             let icon_container = gtk::Box::builder()
                 .css_classes(["orbit-icon-container"])
                 .halign(gtk::Align::Center)
                 .valign(gtk::Align::Center)
                 .build();
             
-            let signal_bars = Self::build_signal_bars(network.signal_strength, true);
+            let signal_bars = Self::build_signal_bars(network.signal_strength, *self.accent_signal_bars.borrow(), use_dbm);
             icon_container.append(&signal_bars);
             row.append(&icon_container);
+            signal_bars
         } else {
-            let signal_bars = Self::build_signal_bars(network.signal_strength, false);
+            let signal_bars = Self::build_signal_bars(network.signal_strength, false, use_dbm);
             signal_bars.set_valign(gtk::Align::Center);
             signal_bars.add_css_class("orbit-signal-bars-pad");
             row.append(&signal_bars);
-        }
+            signal_bars
+        };
         
         let info_box = gtk::Box::builder()
             .orientation(Orientation::Vertical)
@@ -283,27 +564,68 @@ impl NetworkList {
             .valign(gtk::Align::Center)
             .build();
         
+        let ssid_box = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .build();
         let ssid = gtk::Label::builder()
             .label(&network.ssid)
+            .tooltip_text(&network.ssid)
             .css_classes(["orbit-ssid"])
             .halign(gtk::Align::Start)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
             .build();
-        info_box.append(&ssid);
-        
-        let status_text = if network.is_connected {
-            format!("Connected · {}%", network.signal_strength)
+        ssid_box.append(&ssid);
+        if !network.is_connected && self.saved_ssids.borrow().contains(&network.ssid) {
+            let badge = gtk::Label::builder()
+                .label("saved")
+                .css_classes(["orbit-badge"])
+                .valign(gtk::Align::Center)
+                .tooltip_text("Saved network, joins without asking for a password")
+                .build();
+            ssid_box.append(&badge);
+        }
+        if let Some(label) = network.is_connected
+            .then(|| network_manager::connectivity_badge(*self.connectivity.borrow()))
+            .flatten()
+        {
+            let badge = gtk::Label::builder()
+                .label(label)
+                .css_classes(["orbit-badge", "orbit-badge-warning"])
+                .valign(gtk::Align::Center)
+                .tooltip_text("NetworkManager can't reach the internet through this network")
+                .build();
+            ssid_box.append(&badge);
+        }
+        info_box.append(&ssid_box);
+        let (ap_group, ap_entries) = if network.siblings.is_empty() {
+            (None, Vec::new())
         } else {
-            let security = if network.security != SecurityType::None { "Secure" } else { "Open" };
-            format!("{}% Signal · {}", network.signal_strength, security)
+            let (group, entries) = self.build_ap_group(&ssid_box, network, use_dbm);
+            (Some(group), entries)
         };
         
         let status = gtk::Label::builder()
-            .label(&status_text)
+            .label(self.status_text(network, use_dbm))
             .css_classes(["orbit-status"])
             .halign(gtk::Align::Start)
             .build();
         info_box.append(&status);
         
+        if let Some(reason) = self.failure_for(&network.ssid).filter(|_| !network.is_connected) {
+            let failed = gtk::Label::builder()
+                .label("Failed — retry")
+                .tooltip_text(&reason)
+                .css_classes(["orbit-status-error"])
+                .halign(gtk::Align::Start)
+                .build();
+            info_box.append(&failed);
+        }
+        
+        if let Some(ap_group) = ap_group {
+            info_box.append(&ap_group);
+        }
+        
         row.append(&info_box);
         
         let actions_box = gtk::Box::builder()
@@ -312,12 +634,154 @@ impl NetworkList {
             .build();
         
         self.build_actions_box_content(&actions_box, network);
-        
-        self.row_actions.borrow_mut().insert(network.ssid.clone(), actions_box.clone());
-
         row.append(&actions_box);
+        
+        self.rows.borrow_mut().insert(network.ssid.clone(), NetworkRow {
+            row: row.clone(),
+            signal_bars,
+            status,
+            actions: actions_box,
+            ap_entries,
+            network: network_row,
+        });
         row
     }
+    
+    /// "Connected · signal · band" for the active network, "signal · band · security" otherwise.
+    fn status_text(&self, network: &AccessPoint, use_dbm: bool) -> String {
+        let band = network_manager::frequency_label(network.frequency)
+            .map(|label| format!(" · {}", label))
+            .unwrap_or_default();
+        let band = if *self.show_interface.borrow() && !network.interface.is_empty() {
+            format!("{} · {}", band, network.interface)
+        } else {
+            band
+        };
+        if network.is_connected {
+            format!("Connected · {}{}", format_signal(network.signal_strength, use_dbm), band)
+        } else {
+            let security = if network.security != SecurityType::None { "Secure" } else { "Open" };
+            let signal = match network.signal_strength {
+                0 => "Signal —".to_string(),
+                strength => format!("{} Signal", format_signal(strength, use_dbm)),
+            };
+            format!("{}{} · {}", signal, band, security)
+        }
+    }
+
+    fn show_bssid_menu(row: &gtk::Box, network: &AccessPoint, use_dbm: bool, show_interface: bool, on_connect_bssid: Rc<RefCell<Option<Rc<dyn Fn(AccessPoint)>>>>) {
+        let aps: Vec<AccessPoint> = std::iter::once(network.clone())
+            .chain(network.siblings.iter().cloned())
+            .filter(|ap| !ap.bssid.is_empty())
+            .collect();
+        if aps.is_empty() {
+            return;
+        }
+
+        let menu_box = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(4)
+            .build();
+
+        let title = gtk::Label::builder()
+            .label("Connect to access point")
+            .css_classes(["orbit-detail-label"])
+            .halign(gtk::Align::Start)
+            .build();
+        menu_box.append(&title);
+
+        let popover = gtk::Popover::builder()
+            .child(&menu_box)
+            .has_arrow(true)
+            .build();
+        popover.set_parent(row);
+        popover.connect_closed(|popover| {
+            popover.unparent();
+        });
+
+        for ap in aps {
+            let btn = gtk::Button::builder()
+                .label(ap_label(&ap, use_dbm, show_interface))
+                .css_classes(["orbit-button", "flat"])
+                .build();
+
+            let popover_btn = popover.clone();
+            let on_connect_bssid = on_connect_bssid.clone();
+            btn.connect_clicked(move |_| {
+                popover_btn.popdown();
+                if let Some(callback) = on_connect_bssid.borrow().as_ref() {
+                    callback(ap.clone());
+                }
+            });
+            menu_box.append(&btn);
+        }
+
+        popover.popup();
+    }
+
+    /// Adds an "N APs" chip to `ssid_box` for a network seen through several access points,
+    /// and returns the folded list of them it toggles along with its entries by BSSID.
+    /// Clicking an entry connects to that BSSID.
+    fn build_ap_group(&self, ssid_box: &gtk::Box, network: &AccessPoint, use_dbm: bool) -> (gtk::Revealer, Vec<(String, gtk::Button)>) {
+        let aps: Vec<AccessPoint> = std::iter::once(network.clone())
+            .chain(network.siblings.iter().cloned())
+            .collect();
+        let expanded = self.expanded_groups.borrow().contains(&network.ssid);
+
+        let chip = gtk::Button::builder()
+            .label(format!("{} APs", aps.len()))
+            .css_classes(["orbit-badge", "orbit-ap-chip", "flat"])
+            .valign(gtk::Align::Center)
+            .tooltip_text("Show each access point")
+            .build();
+        ssid_box.append(&chip);
+
+        let ap_list = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .css_classes(["orbit-ap-list"])
+            .build();
+        let show_interface = *self.show_interface.borrow();
+        let pickable = self.supports(Feature::AccessPointChoice);
+        let mut entries = Vec::new();
+        for ap in aps.into_iter().filter(|ap| !ap.bssid.is_empty()) {
+            let btn = gtk::Button::builder()
+                .label(ap_label(&ap, use_dbm, show_interface))
+                .css_classes(["orbit-button", "flat", "orbit-ap-entry"])
+                .halign(gtk::Align::Start)
+                .tooltip_text("Connect to this access point")
+                .sensitive(pickable)
+                .build();
+            let bssid = ap.bssid.clone();
+            let on_connect_bssid = self.on_connect_bssid.clone();
+            btn.connect_clicked(move |_| {
+                if let Some(callback) = on_connect_bssid.borrow().as_ref() {
+                    callback(ap.clone());
+                }
+            });
+            ap_list.append(&btn);
+            entries.push((bssid, btn));
+        }
+
+        let revealer = gtk::Revealer::builder()
+            .child(&ap_list)
+            .reveal_child(expanded)
+            .transition_type(gtk::RevealerTransitionType::SlideDown)
+            .build();
+
+        let revealer_toggle = revealer.clone();
+        let expanded_groups = self.expanded_groups.clone();
+        let ssid = network.ssid.clone();
+        chip.connect_clicked(move |_| {
+            let expand = !revealer_toggle.reveals_child();
+            revealer_toggle.set_reveal_child(expand);
+            if expand {
+                expanded_groups.borrow_mut().insert(ssid.clone());
+            } else {
+                expanded_groups.borrow_mut().remove(&ssid);
+            }
+        });
+        (revealer, entries)
+    }
 
     fn build_actions_box_content(&self, actions_box: &gtk::Box, network: &AccessPoint) {
         if network.security != SecurityType::None && !network.is_connected {
@@ -353,10 +817,28 @@ impl NetworkList {
             
             working_box.append(&spinner);
             working_box.append(&label);
+            
+            if is_connecting {
+                let cancel_btn = gtk::Button::builder()
+                    .label("Cancel")
+                    .css_classes(["orbit-button", "flat"])
+                    .build();
+                let ssid = network.ssid.clone();
+                let on_cancel_connect = self.on_cancel_connect.clone();
+                cancel_btn.connect_clicked(move |btn| {
+                    btn.set_sensitive(false);
+                    if let Some(callback) = on_cancel_connect.borrow().as_ref() {
+                        callback(ssid.clone());
+                    }
+                });
+                working_box.append(&cancel_btn);
+            }
             actions_box.append(&working_box);
         } else {
             let action_label = if network.is_connected {
                 "Disconnect"
+            } else if self.failure_for(&network.ssid).is_some() {
+                "Retry"
             } else {
                 "Connect"
             };
@@ -381,10 +863,47 @@ impl NetworkList {
                 }
             });
             
+            // Push-button WPS only makes sense before a key is known
+            let wps_capable = matches!(network.security, SecurityType::WPA | SecurityType::WPA2);
+            if wps_capable && self.supports(Feature::Wps) && !network.is_connected && !self.saved_ssids.borrow().contains(&network.ssid) {
+                let wps_btn = gtk::Button::builder()
+                    .label("WPS")
+                    .tooltip_text("Join by pressing the WPS button on the router")
+                    .css_classes(["orbit-button", "flat"])
+                    .sensitive(!any_connecting)
+                    .build();
+                let network_wps = network.clone();
+                let on_connect_wps = self.on_connect_wps.clone();
+                wps_btn.connect_clicked(move |_| {
+                    if let Some(callback) = on_connect_wps.borrow().as_ref() {
+                        callback(network_wps.clone());
+                    }
+                });
+                actions_box.append(&wps_btn);
+            }
+            
+            // Saves a trip to the Saved tab when a stored password has gone stale
+            if !network.is_connected && self.saved_ssids.borrow().contains(&network.ssid) && !*self.readonly.borrow() {
+                let forget_btn = gtk::Button::builder()
+                    .icon_name("user-trash-symbolic")
+                    .tooltip_text("Forget this network")
+                    .css_classes(["orbit-button", "destructive", "flat"])
+                    .sensitive(!any_connecting)
+                    .build();
+                let ssid = network.ssid.clone();
+                let on_forget = self.on_forget.clone();
+                forget_btn.connect_clicked(move |_| {
+                    if let Some(callback) = on_forget.borrow().as_ref() {
+                        callback(ssid.clone());
+                    }
+                });
+                actions_box.append(&forget_btn);
+            }
+            
             actions_box.append(&action_btn);
         }
         
-        if network.is_connected && !is_disconnecting {
+        if network.is_connected && !is_disconnecting && self.supports(Feature::Details) {
             let details_btn = gtk::Button::builder()
                 .label("Details")
                 .css_classes(["orbit-button", "flat"])
@@ -402,6 +921,15 @@ impl NetworkList {
         }
     }
     
+    /// Sections to show folded; takes effect on the next render.
+    pub fn set_collapsed_sections(&self, keys: &[String]) {
+        *self.collapsed_sections.borrow_mut() = keys.iter().cloned().collect();
+    }
+    
+    pub fn set_on_section_toggled<F: Fn(String, bool) + 'static>(&self, callback: F) {
+        *self.on_section_toggled.borrow_mut() = Some(Rc::new(callback));
+    }
+    
     pub fn widget(&self) -> &gtk::Box {
         &self.container
     }
@@ -409,11 +937,48 @@ impl NetworkList {
     pub fn scan_button(&self) -> &gtk::Button {
         &self.scan_button
     }
+
+    /// Marks a scan as running; returns false if one is already in progress.
+    pub fn begin_scan(&self) -> bool {
+        if *self.scanning.borrow() {
+            return false;
+        }
+        *self.scanning.borrow_mut() = true;
+        self.scan_button.set_sensitive(false);
+        self.scan_button.set_label(" Scanning...");
+        true
+    }
+
+    pub fn finish_scan(&self) {
+        *self.scanning.borrow_mut() = false;
+        self.scan_button.set_sensitive(true);
+        self.scan_button.set_label(" Scan for Networks");
+    }
     
     pub fn set_on_connect<F: Fn(AccessPoint) + 'static>(&self, callback: F) {
         *self.on_connect.borrow_mut() = Some(Rc::new(callback));
     }
     
+    pub fn set_on_connect_bssid<F: Fn(AccessPoint) + 'static>(&self, callback: F) {
+        *self.on_connect_bssid.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    pub fn set_on_connect_wps<F: Fn(AccessPoint) + 'static>(&self, callback: F) {
+        *self.on_connect_wps.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    pub fn set_on_cancel_connect<F: Fn(String) + 'static>(&self, callback: F) {
+        *self.on_cancel_connect.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    /// Aborts the connection currently shown as "Connecting...", if any.
+    pub fn cancel_connect(&self) {
+        let ssid = self.connecting_ssid.borrow().clone();
+        if let (Some(ssid), Some(callback)) = (ssid, self.on_cancel_connect.borrow().as_ref()) {
+            callback(ssid);
+        }
+    }
+    
     pub fn set_on_connect_hidden<F: Fn() + 'static>(&self, callback: F) {
         *self.on_connect_hidden.borrow_mut() = Some(Rc::new(callback));
     }
@@ -421,4 +986,44 @@ impl NetworkList {
     pub fn set_on_details<F: Fn(String) + 'static>(&self, callback: F) {
         *self.on_details.borrow_mut() = Some(Rc::new(callback));
     }
+    
+    pub fn set_on_forget<F: Fn(String) + 'static>(&self, callback: F) {
+        *self.on_forget.borrow_mut() = Some(Rc::new(callback));
+    }
+}
+
+/// True when access points from more than one adapter are listed, so rows name their
+/// interface.
+fn multiple_interfaces(networks: &[AccessPoint]) -> bool {
+    let interfaces: HashSet<&str> = networks.iter()
+        .flat_map(|n| std::iter::once(n).chain(n.siblings.iter()))
+        .map(|ap| ap.interface.as_str())
+        .filter(|interface| !interface.is_empty())
+        .collect();
+    interfaces.len() > 1
+}
+
+/// Approximates RSSI from NetworkManager's strength percentage by inverting its
+/// linear mapping of -100..-40 dBm onto 0..100%.
+/// "BSSID · signal · band", plus the interface when several adapters scan.
+fn ap_label(ap: &AccessPoint, use_dbm: bool, show_interface: bool) -> String {
+    let mut label = match network_manager::frequency_label(ap.frequency) {
+        Some(band) => format!("{} · {} · {}", ap.bssid, format_signal(ap.signal_strength, use_dbm), band),
+        None => format!("{} · {}", ap.bssid, format_signal(ap.signal_strength, use_dbm)),
+    };
+    if show_interface && !ap.interface.is_empty() {
+        label.push_str(&format!(" · {}", ap.interface));
+    }
+    label
+}
+
+/// Strength as text; 0 means the AP has not been measured yet, not that it is dead.
+fn format_signal(strength: u8, use_dbm: bool) -> String {
+    if strength == 0 {
+        "—".to_string()
+    } else if use_dbm {
+        format!("{} dBm", network_manager::strength_to_dbm(strength))
+    } else {
+        format!("{}%", strength)
+    }
 }
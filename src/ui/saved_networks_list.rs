@@ -2,15 +2,30 @@ use gtk4::prelude::*;
 use gtk4::{self as gtk, glib, Orientation};
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::dbus::network_manager::SavedNetwork;
+use std::collections::HashSet;
+use super::section::{collapsible_section, CollapsedSections, SectionToggled};
+use crate::dbus::network_manager::{MacRandomMode, SavedNetwork};
+use crate::dbus::Feature;
 
 #[derive(Clone)]
 pub struct SavedNetworksList {
     container: gtk::Box,
     list_box: gtk::Box,
+    add_button: gtk::Button,
     networks: Rc<RefCell<Vec<SavedNetwork>>>,
     on_autoconnect_toggle: Rc<RefCell<Option<Rc<dyn Fn(String, bool)>>>>,
     on_forget: Rc<RefCell<Option<Rc<dyn Fn(String)>>>>,
+    on_edit_ipv4: Rc<RefCell<Option<Rc<dyn Fn(String, String)>>>>,
+    on_change_password: Rc<RefCell<Option<Rc<dyn Fn(String, String)>>>>,
+    on_mac_randomization: Rc<RefCell<Option<Rc<dyn Fn(String, MacRandomMode)>>>>,
+    on_metered_toggle: Rc<RefCell<Option<Rc<dyn Fn(String, bool)>>>>,
+    on_priority_change: Rc<RefCell<Option<Rc<dyn Fn(Vec<(String, i32)>)>>>>,
+    on_add_network: Rc<RefCell<Option<Rc<dyn Fn()>>>>,
+    readonly: Rc<RefCell<bool>>,
+    /// What the WiFi backend implements; rows leave out the rest.
+    features: Rc<RefCell<HashSet<Feature>>>,
+    collapsed_sections: CollapsedSections,
+    on_section_toggled: SectionToggled,
 }
 
 impl SavedNetworksList {
@@ -37,14 +52,47 @@ impl SavedNetworksList {
         scrolled.set_child(Some(&list_box));
         container.append(&scrolled);
         
+        let footer = gtk::Box::builder()
+            .css_classes(["orbit-footer"])
+            .margin_top(8)
+            .build();
+        
+        let add_button = gtk::Button::builder()
+            .label(" Add Network")
+            .tooltip_text("Save a network to join once it is in range")
+            .css_classes(["orbit-button", "flat"])
+            .hexpand(true)
+            .build();
+        
+        footer.append(&add_button);
+        container.append(&footer);
+        
         let list = Self {
             container,
             list_box,
+            add_button: add_button.clone(),
             networks: Rc::new(RefCell::new(Vec::new())),
             on_autoconnect_toggle: Rc::new(RefCell::new(None)),
             on_forget: Rc::new(RefCell::new(None)),
+            on_edit_ipv4: Rc::new(RefCell::new(None)),
+            on_change_password: Rc::new(RefCell::new(None)),
+            on_mac_randomization: Rc::new(RefCell::new(None)),
+            on_metered_toggle: Rc::new(RefCell::new(None)),
+            on_priority_change: Rc::new(RefCell::new(None)),
+            on_add_network: Rc::new(RefCell::new(None)),
+            readonly: Rc::new(RefCell::new(false)),
+            features: Rc::new(RefCell::new(Feature::ALL.into_iter().collect())),
+            collapsed_sections: Rc::new(RefCell::new(HashSet::new())),
+            on_section_toggled: Rc::new(RefCell::new(None)),
         };
         
+        let on_add_network = list.on_add_network.clone();
+        add_button.connect_clicked(move |_| {
+            if let Some(callback) = on_add_network.borrow().as_ref() {
+                callback();
+            }
+        });
+        
         list.show_loading();
         list
     }
@@ -57,6 +105,17 @@ impl SavedNetworksList {
         self.list_box.append(&placeholder);
     }
     
+    pub fn show_unavailable(&self) {
+        while let Some(child) = self.list_box.first_child() {
+            self.list_box.remove(&child);
+        }
+        let placeholder = gtk::Label::builder()
+            .label("No WiFi backend is running")
+            .css_classes(["orbit-placeholder"])
+            .build();
+        self.list_box.append(&placeholder);
+    }
+    
     fn show_placeholder(&self) {
         let placeholder = gtk::Label::builder()
             .label("No saved networks")
@@ -65,6 +124,29 @@ impl SavedNetworksList {
         self.list_box.append(&placeholder);
     }
     
+    pub fn set_readonly(&self, readonly: bool) {
+        *self.readonly.borrow_mut() = readonly;
+        self.add_button.set_sensitive(!readonly);
+        let networks = self.networks.borrow().clone();
+        if !networks.is_empty() {
+            self.set_networks(networks);
+        }
+    }
+    
+    /// Hides the controls of backend features that aren't in `features`.
+    pub fn set_features(&self, features: &[Feature]) {
+        let features: HashSet<Feature> = features.iter().copied().collect();
+        if *self.features.borrow() == features {
+            return;
+        }
+        self.add_button.set_visible(features.contains(&Feature::AddNetwork));
+        *self.features.borrow_mut() = features;
+        let networks = self.networks.borrow().clone();
+        if !networks.is_empty() {
+            self.set_networks(networks);
+        }
+    }
+    
     pub fn set_networks(&self, networks: Vec<SavedNetwork>) {
         *self.networks.borrow_mut() = networks.clone();
         
@@ -81,30 +163,18 @@ impl SavedNetworksList {
         let saved_networks: Vec<&SavedNetwork> = networks.iter().filter(|n| !n.is_active).collect();
         
         if !active_networks.is_empty() {
-            let section_header = gtk::Label::builder()
-                .label("CURRENTLY CONNECTED")
-                .css_classes(["orbit-section-header"])
-                .halign(gtk::Align::Start)
-                .build();
-            self.list_box.append(&section_header);
-            
+            let section = collapsible_section(&self.list_box, "CURRENTLY CONNECTED", "saved-active", &self.collapsed_sections, &self.on_section_toggled);
             for network in active_networks {
                 let row = self.create_network_row(network);
-                self.list_box.append(&row);
+                section.append(&row);
             }
         }
         
         if !saved_networks.is_empty() {
-            let section_header = gtk::Label::builder()
-                .label("SAVED NETWORKS")
-                .css_classes(["orbit-section-header"])
-                .halign(gtk::Align::Start)
-                .build();
-            self.list_box.append(&section_header);
-            
+            let section = collapsible_section(&self.list_box, "SAVED NETWORKS", "saved-saved", &self.collapsed_sections, &self.on_section_toggled);
             for network in saved_networks {
                 let row = self.create_network_row(network);
-                self.list_box.append(&row);
+                section.append(&row);
             }
         }
     }
@@ -117,12 +187,17 @@ impl SavedNetworksList {
         };
         
         let row = gtk::Box::builder()
-            .orientation(Orientation::Horizontal)
-            .spacing(12)
+            .orientation(Orientation::Vertical)
             .css_classes(css_classes)
             .focusable(true)
             .build();
         
+        let main_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(12)
+            .build();
+        row.append(&main_row);
+        
         let row_focus = row.clone();
         let focus_in = gtk::EventControllerFocus::new();
         focus_in.connect_enter(move |_| {
@@ -149,14 +224,14 @@ impl SavedNetworksList {
                 .css_classes(["orbit-icon-accent"])
                 .build();
             icon_container.append(&wifi_icon);
-            row.append(&icon_container);
+            main_row.append(&icon_container);
         } else {
             let wifi_icon = gtk::Image::builder()
                 .icon_name("network-wireless-symbolic")
                 .pixel_size(20)
                 .css_classes(["orbit-signal-icon"])
                 .build();
-            row.append(&wifi_icon);
+            main_row.append(&wifi_icon);
         }
         
         let info_box = gtk::Box::builder()
@@ -166,12 +241,29 @@ impl SavedNetworksList {
             .valign(gtk::Align::Center)
             .build();
         
+        let ssid_box = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .build();
         let ssid = gtk::Label::builder()
             .label(&network.ssid)
+            .tooltip_text(&network.ssid)
             .css_classes(["orbit-ssid"])
             .halign(gtk::Align::Start)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
             .build();
-        info_box.append(&ssid);
+        ssid_box.append(&ssid);
+        // Hidden networks never show up in a scan, so say why
+        if network.is_hidden {
+            let badge = gtk::Label::builder()
+                .label("hidden")
+                .css_classes(["orbit-badge"])
+                .valign(gtk::Align::Center)
+                .tooltip_text("This network does not broadcast its name, so it never appears in scans")
+                .build();
+            ssid_box.append(&badge);
+        }
+        info_box.append(&ssid_box);
         
         let status_text = if network.is_active {
             "Connected"
@@ -188,7 +280,7 @@ impl SavedNetworksList {
             .build();
         info_box.append(&status);
         
-        row.append(&info_box);
+        main_row.append(&info_box);
         
         let autoconnect_box = gtk::Box::builder()
             .orientation(Orientation::Horizontal)
@@ -201,22 +293,174 @@ impl SavedNetworksList {
             .css_classes(["orbit-status"])
             .build();
         
+        let readonly = *self.readonly.borrow();
+        
         let autoconnect_switch = gtk::Switch::builder()
             .active(network.autoconnect)
             .css_classes(["orbit-toggle-switch"])
+            .sensitive(!readonly)
             .build();
         
         autoconnect_box.append(&auto_label);
         autoconnect_box.append(&autoconnect_switch);
-        row.append(&autoconnect_box);
+        main_row.append(&autoconnect_box);
+        
+        // Up/down move the network in autoconnect order, the connected one included
+        if !readonly && self.features.borrow().contains(&Feature::Priority) {
+            let (first, last) = {
+                let networks = self.networks.borrow();
+                let order = priority_order(&networks);
+                (
+                    order.first().map(|n| n.path == network.path).unwrap_or(true),
+                    order.last().map(|n| n.path == network.path).unwrap_or(true),
+                )
+            };
+            
+            let order_box = gtk::Box::builder()
+                .orientation(Orientation::Vertical)
+                .valign(gtk::Align::Center)
+                .build();
+            
+            for (icon, up, enabled) in [("go-up-symbolic", true, !first), ("go-down-symbolic", false, !last)] {
+                let btn = gtk::Button::builder()
+                    .icon_name(icon)
+                    .css_classes(["orbit-button", "flat"])
+                    .tooltip_text(if up { "Prefer this network" } else { "Prefer other networks" })
+                    .sensitive(enabled)
+                    .build();
+                
+                let path_move = network.path.clone();
+                let networks = self.networks.clone();
+                let on_priority_change = self.on_priority_change.clone();
+                btn.connect_clicked(move |_| {
+                    let changes = reorder_priorities(&networks.borrow(), &path_move, up);
+                    if changes.is_empty() {
+                        return;
+                    }
+                    if let Some(callback) = on_priority_change.borrow().as_ref() {
+                        callback(changes);
+                    }
+                });
+                order_box.append(&btn);
+            }
+            main_row.append(&order_box);
+        }
         
         // Forget Button
         let forget_btn = gtk::Button::builder()
             .label("Forget")
             .css_classes(["orbit-button", "destructive", "flat"])
             .valign(gtk::Align::Center)
+            .visible(!readonly)
+            .build();
+        main_row.append(&forget_btn);
+        
+        // Per-network settings, tucked behind a toggle to keep the row compact
+        let settings_btn = gtk::Button::builder()
+            .icon_name("emblem-system-symbolic")
+            .css_classes(["orbit-button", "flat"])
+            .valign(gtk::Align::Center)
+            .tooltip_text("Network settings")
+            .visible(!readonly && self.features.borrow().contains(&Feature::ProfileSettings))
+            .build();
+        main_row.append(&settings_btn);
+        
+        let settings_box = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .margin_top(8)
+            .build();
+        
+        let ipv4_btn = gtk::Button::builder()
+            .label("IPv4…")
+            .css_classes(["orbit-button", "flat"])
+            .build();
+        settings_box.append(&ipv4_btn);
+        
+        let password_btn = gtk::Button::builder()
+            .label("Password…")
+            .css_classes(["orbit-button", "flat"])
+            .tooltip_text("Change the saved password")
             .build();
-        row.append(&forget_btn);
+        settings_box.append(&password_btn);
+        
+        let mac_labels: Vec<&str> = MacRandomMode::ALL.iter().map(|m| m.label()).collect();
+        let mac_dropdown = gtk::DropDown::from_strings(&mac_labels);
+        mac_dropdown.set_tooltip_text(Some("MAC address used on this network"));
+        mac_dropdown.set_valign(gtk::Align::Center);
+        let current_mode = MacRandomMode::ALL.iter()
+            .position(|m| *m == network.mac_randomization)
+            .unwrap_or(0);
+        mac_dropdown.set_selected(current_mode as u32);
+        settings_box.append(&mac_dropdown);
+        
+        let metered_box = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(4)
+            .valign(gtk::Align::Center)
+            .build();
+        
+        let metered_label = gtk::Label::builder()
+            .label("Metered")
+            .css_classes(["orbit-status"])
+            .build();
+        
+        let metered_switch = gtk::Switch::builder()
+            .active(network.metered)
+            .css_classes(["orbit-toggle-switch"])
+            .build();
+        
+        metered_box.append(&metered_label);
+        metered_box.append(&metered_switch);
+        settings_box.append(&metered_box);
+        
+        let settings_revealer = gtk::Revealer::builder()
+            .child(&settings_box)
+            .reveal_child(false)
+            .transition_type(gtk::RevealerTransitionType::SlideDown)
+            .transition_duration(200)
+            .build();
+        row.append(&settings_revealer);
+        
+        settings_btn.connect_clicked(move |_| {
+            settings_revealer.set_reveal_child(!settings_revealer.reveals_child());
+        });
+        
+        let path_ipv4 = network.path.clone();
+        let ssid_ipv4 = network.ssid.clone();
+        let on_edit_ipv4 = self.on_edit_ipv4.clone();
+        ipv4_btn.connect_clicked(move |_| {
+            if let Some(callback) = on_edit_ipv4.borrow().as_ref() {
+                callback(path_ipv4.clone(), ssid_ipv4.clone());
+            }
+        });
+        
+        let path_psk = network.path.clone();
+        let ssid_psk = network.ssid.clone();
+        let on_change_password = self.on_change_password.clone();
+        password_btn.connect_clicked(move |_| {
+            if let Some(callback) = on_change_password.borrow().as_ref() {
+                callback(path_psk.clone(), ssid_psk.clone());
+            }
+        });
+        
+        let path_mac = network.path.clone();
+        let on_mac_randomization = self.on_mac_randomization.clone();
+        mac_dropdown.connect_selected_notify(move |dropdown| {
+            if let Some(mode) = MacRandomMode::ALL.get(dropdown.selected() as usize) {
+                if let Some(callback) = on_mac_randomization.borrow().as_ref() {
+                    callback(path_mac.clone(), *mode);
+                }
+            }
+        });
+        
+        let path_metered = network.path.clone();
+        let on_metered_toggle = self.on_metered_toggle.clone();
+        metered_switch.connect_active_notify(move |switch| {
+            if let Some(callback) = on_metered_toggle.borrow().as_ref() {
+                callback(path_metered.clone(), switch.is_active());
+            }
+        });
         
         let path_toggle = network.path.clone();
         let on_toggle = self.on_autoconnect_toggle.clone();
@@ -247,10 +491,28 @@ impl SavedNetworksList {
         row
     }
     
+    /// Sections to show folded; takes effect on the next render.
+    pub fn set_collapsed_sections(&self, keys: &[String]) {
+        *self.collapsed_sections.borrow_mut() = keys.iter().cloned().collect();
+    }
+    
+    pub fn set_on_section_toggled<F: Fn(String, bool) + 'static>(&self, callback: F) {
+        *self.on_section_toggled.borrow_mut() = Some(Rc::new(callback));
+    }
+    
     pub fn widget(&self) -> &gtk::Box {
         &self.container
     }
     
+    pub fn network(&self, path: &str) -> Option<SavedNetwork> {
+        self.networks.borrow().iter().find(|n| n.path == path).cloned()
+    }
+    
+    /// Every saved profile for `ssid`; there can be more than one.
+    pub fn networks_for_ssid(&self, ssid: &str) -> Vec<SavedNetwork> {
+        self.networks.borrow().iter().filter(|n| n.ssid == ssid).cloned().collect()
+    }
+    
     pub fn set_on_autoconnect_toggle<F: Fn(String, bool) + 'static>(&self, callback: F) {
         *self.on_autoconnect_toggle.borrow_mut() = Some(Rc::new(callback));
     }
@@ -258,4 +520,69 @@ impl SavedNetworksList {
     pub fn set_on_forget<F: Fn(String) + 'static>(&self, callback: F) {
         *self.on_forget.borrow_mut() = Some(Rc::new(callback));
     }
+
+    pub fn set_on_add_network<F: Fn() + 'static>(&self, callback: F) {
+        *self.on_add_network.borrow_mut() = Some(Rc::new(callback));
+    }
+
+    pub fn set_on_edit_ipv4<F: Fn(String, String) + 'static>(&self, callback: F) {
+        *self.on_edit_ipv4.borrow_mut() = Some(Rc::new(callback));
+    }
+
+    /// Called with the connection path and SSID when "Password…" is clicked.
+    pub fn set_on_change_password<F: Fn(String, String) + 'static>(&self, callback: F) {
+        *self.on_change_password.borrow_mut() = Some(Rc::new(callback));
+    }
+
+    pub fn set_on_metered_toggle<F: Fn(String, bool) + 'static>(&self, callback: F) {
+        *self.on_metered_toggle.borrow_mut() = Some(Rc::new(callback));
+    }
+
+    /// Called with the (path, priority) pairs that changed after a reorder.
+    pub fn set_on_priority_change<F: Fn(Vec<(String, i32)>) + 'static>(&self, callback: F) {
+        *self.on_priority_change.borrow_mut() = Some(Rc::new(callback));
+    }
+
+    pub fn set_on_mac_randomization<F: Fn(String, MacRandomMode) + 'static>(&self, callback: F) {
+        *self.on_mac_randomization.borrow_mut() = Some(Rc::new(callback));
+    }
+}
+
+/// Saved networks from most to least preferred for autoconnect, in the order the list
+/// shows networks of equal priority.
+fn priority_order(networks: &[SavedNetwork]) -> Vec<&SavedNetwork> {
+    let mut order: Vec<&SavedNetwork> = networks.iter().collect();
+    order.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.ssid.cmp(&b.ssid)));
+    order
+}
+
+/// Moves `path` one step up or down in autoconnect order. Walking up from the bottom,
+/// only networks that would no longer sort above the one below them get a new priority,
+/// which is usually just the one moved.
+fn reorder_priorities(networks: &[SavedNetwork], path: &str, up: bool) -> Vec<(String, i32)> {
+    let mut order = priority_order(networks);
+    let index = match order.iter().position(|n| n.path == path) {
+        Some(i) => i,
+        None => return Vec::new(),
+    };
+    let target = if up { index.checked_sub(1) } else { Some(index + 1).filter(|i| *i < order.len()) };
+    match target {
+        Some(target) => order.swap(index, target),
+        None => return Vec::new(),
+    }
+    
+    let mut changes = Vec::new();
+    let mut below: Option<(i32, &str)> = None;
+    for network in order.iter().rev() {
+        let mut priority = network.priority;
+        if let Some((below_priority, below_ssid)) = below {
+            // Equal priorities sort by SSID
+            if priority < below_priority || (priority == below_priority && network.ssid.as_str() >= below_ssid) {
+                priority = below_priority + 1;
+                changes.push((network.path.clone(), priority));
+            }
+        }
+        below = Some((priority, network.ssid.as_str()));
+    }
+    changes
 }
@@ -0,0 +1,82 @@
+use gtk4::prelude::*;
+use gtk4::{self as gtk, Orientation};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Keys of the sections the user folded away.
+pub type CollapsedSections = Rc<RefCell<HashSet<String>>>;
+
+/// Called with a section key and whether it is now collapsed.
+pub type SectionToggled = Rc<RefCell<Option<Rc<dyn Fn(String, bool)>>>>;
+
+/// Appends a clickable section header to `list_box` and returns the box its rows go in.
+/// Clicking the header folds the rows away and reports the new state through `on_toggled`.
+pub fn collapsible_section(
+    list_box: &gtk::Box,
+    title: &str,
+    key: &str,
+    collapsed: &CollapsedSections,
+    on_toggled: &SectionToggled,
+) -> gtk::Box {
+    let is_collapsed = collapsed.borrow().contains(key);
+
+    let arrow = gtk::Image::builder()
+        .icon_name(arrow_icon(is_collapsed))
+        .pixel_size(10)
+        .css_classes(["orbit-section-header"])
+        .build();
+    let label = gtk::Label::builder()
+        .label(title)
+        .css_classes(["orbit-section-header"])
+        .halign(gtk::Align::Start)
+        .build();
+    let header_box = gtk::Box::builder()
+        .orientation(Orientation::Horizontal)
+        .build();
+    header_box.append(&label);
+    header_box.append(&arrow);
+
+    let header = gtk::Button::builder()
+        .child(&header_box)
+        .css_classes(["orbit-section-toggle", "flat"])
+        .halign(gtk::Align::Start)
+        .tooltip_text(if is_collapsed { "Show section" } else { "Hide section" })
+        .build();
+
+    let rows = gtk::Box::builder()
+        .orientation(Orientation::Vertical)
+        .visible(!is_collapsed)
+        .build();
+
+    let rows_toggle = rows.clone();
+    let key = key.to_string();
+    let collapsed = collapsed.clone();
+    let on_toggled = on_toggled.clone();
+    header.connect_clicked(move |btn| {
+        let now_collapsed = rows_toggle.is_visible();
+        rows_toggle.set_visible(!now_collapsed);
+        arrow.set_icon_name(Some(arrow_icon(now_collapsed)));
+        btn.set_tooltip_text(Some(if now_collapsed { "Show section" } else { "Hide section" }));
+        if now_collapsed {
+            collapsed.borrow_mut().insert(key.clone());
+        } else {
+            collapsed.borrow_mut().remove(&key);
+        }
+        if let Some(callback) = on_toggled.borrow().as_ref() {
+            callback(key.clone(), now_collapsed);
+        }
+    });
+
+    list_box.append(&header);
+    list_box.append(&rows);
+    rows
+}
+
+fn arrow_icon(collapsed: bool) -> &'static str {
+    if collapsed {
+        "pan-end-symbolic"
+    } else {
+        "pan-down-symbolic"
+    }
+}
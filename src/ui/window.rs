@@ -2,15 +2,61 @@ use gtk4::{ApplicationWindow, Application, prelude::*, Overlay};
 use gtk4::{self as gtk, Orientation};
 use gtk4_layer_shell::{LayerShell, Layer, KeyboardMode, Edge};
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
 
 use crate::config::Config;
 use crate::theme::Theme;
+use crate::app::daemon::Margins;
+use crate::dbus::Feature;
+use crate::dbus::network_manager::{EnterpriseCredentials, ExportedNetwork, Ipv4Method, SecurityType};
 use super::header::Header;
 use super::network_list::NetworkList;
 use super::device_list::DeviceList;
 use super::saved_networks_list::SavedNetworksList;
 
+/// EAP methods offered for enterprise networks: (label, NetworkManager value).
+const EAP_METHODS: [(&str, &str); 2] = [("PEAP", "peap"), ("TTLS", "ttls")];
+/// Phase 2 (inner) authentication methods: (label, NetworkManager value).
+const PHASE2_METHODS: [(&str, &str); 3] = [("MSCHAPv2", "mschapv2"), ("PAP", "pap"), ("GTC", "gtc")];
+/// Security choices in the "Add network" form; WPA/WPA2 is preselected.
+const ADD_SECURITY_LABELS: [&str; 4] = ["Open", "WEP", "WPA/WPA2 Personal", "WPA3 Personal"];
+const ADD_SECURITY_DEFAULT: u32 = 2;
+/// Number of one-second samples kept for the throughput graph.
+const THROUGHPUT_SAMPLES: usize = 60;
+
+/// Rolling download/upload rates derived from cumulative device byte counters.
+#[derive(Default)]
+struct ThroughputHistory {
+    last: Option<(u64, u64, std::time::Instant)>,
+    rates: VecDeque<(f64, f64)>,
+}
+
+impl ThroughputHistory {
+    /// Records a counter sample and returns the (down, up) rate in bytes/s, if one can be computed yet.
+    fn push(&mut self, rx: u64, tx: u64) -> Option<(f64, f64)> {
+        let now = std::time::Instant::now();
+        let rate = self.last.and_then(|(last_rx, last_tx, at)| {
+            let secs = now.duration_since(at).as_secs_f64();
+            if secs <= 0.0 {
+                return None;
+            }
+            Some((
+                rx.saturating_sub(last_rx) as f64 / secs,
+                tx.saturating_sub(last_tx) as f64 / secs,
+            ))
+        });
+        self.last = Some((rx, tx, now));
+        if let Some(r) = rate {
+            if self.rates.len() == THROUGHPUT_SAMPLES {
+                self.rates.pop_front();
+            }
+            self.rates.push_back(r);
+        }
+        rate
+    }
+}
+
 pub struct OrbitWindow {
     window: ApplicationWindow,
     config: Rc<RefCell<Config>>,
@@ -22,23 +68,73 @@ pub struct OrbitWindow {
     details_revealer: gtk::Revealer,
     details_box: gtk::Box,
     details_content: gtk::Box,
+    details_share_btn: gtk::Button,
+    details_ssid: Rc<RefCell<String>>,
+    on_share: Rc<RefCell<Option<Rc<dyn Fn(String)>>>>,
+    details_dns_btn: gtk::Button,
+    details_dns: Rc<RefCell<Vec<String>>>,
+    details_device_path: Rc<RefCell<String>>,
+    on_dns_override: Rc<RefCell<Option<Rc<dyn Fn(String, String, Option<Vec<String>>)>>>>,
+    throughput_box: gtk::Box,
+    throughput_label: gtk::Label,
+    throughput_graph: gtk::DrawingArea,
+    throughput_history: Rc<RefCell<ThroughputHistory>>,
+    throughput_timer: Rc<RefCell<Option<gtk4::glib::SourceId>>>,
+    on_stats_tick: Rc<RefCell<Option<Rc<dyn Fn(String)>>>>,
     password_revealer: gtk::Revealer,
     password_box: gtk::Box,
     password_entry: gtk::PasswordEntry,
     password_label: gtk::Label,
     password_error_label: gtk::Label,
+    caps_lock_label: gtk::Label,
+    autoconnect_check: gtk::CheckButton,
+    password_is_psk: Rc<RefCell<bool>>,
+    /// Submit button label while idle and while busy, e.g. ("Save", "Saving...").
+    password_labels: Rc<RefCell<(&'static str, &'static str)>>,
     password_connect_btn: gtk::Button,
-    password_callback: Rc<RefCell<Option<Rc<dyn Fn(Option<String>)>>>>,
+    password_callback: Rc<RefCell<Option<Rc<dyn Fn(Option<(String, bool)>)>>>>,
+    enterprise_box: gtk::Box,
+    identity_entry: gtk::Entry,
+    anonymous_identity_entry: gtk::Entry,
+    eap_method_dropdown: gtk::DropDown,
+    phase2_dropdown: gtk::DropDown,
+    enterprise_callback: Rc<RefCell<Option<Rc<dyn Fn(Option<EnterpriseCredentials>)>>>>,
     hidden_revealer: gtk::Revealer,
     hidden_ssid_entry: gtk::Entry,
     hidden_password_entry: gtk::PasswordEntry,
     hidden_callback: Rc<RefCell<Option<Rc<dyn Fn(Option<(String, String)>)>>>>,
+    add_revealer: gtk::Revealer,
+    add_ssid_entry: gtk::Entry,
+    add_security_dropdown: gtk::DropDown,
+    add_password_entry: gtk::PasswordEntry,
+    add_hidden_check: gtk::CheckButton,
+    add_error_label: gtk::Label,
+    add_callback: Rc<RefCell<Option<Rc<dyn Fn(ExportedNetwork)>>>>,
+    ipv4_revealer: gtk::Revealer,
+    ipv4_label: gtk::Label,
+    ipv4_address_entry: gtk::Entry,
+    ipv4_prefix_entry: gtk::Entry,
+    ipv4_gateway_entry: gtk::Entry,
+    ipv4_dns_entry: gtk::Entry,
+    ipv4_error_label: gtk::Label,
+    ipv4_callback: Rc<RefCell<Option<Rc<dyn Fn(Ipv4Method)>>>>,
     error_revealer: gtk::Revealer,
     error_box: gtk::Box,
     error_label: gtk::Label,
+    transfer_revealer: gtk::Revealer,
+    transfer_label: gtk::Label,
+    transfer_progress: gtk::ProgressBar,
+    wps_revealer: gtk::Revealer,
+    wps_label: gtk::Label,
+    wps_progress: gtk::ProgressBar,
+    wps_timer: Rc<RefCell<Option<gtk4::glib::SourceId>>>,
+    wps_cancel_callback: Rc<RefCell<Option<Rc<dyn Fn()>>>>,
     theme: Rc<RefCell<Theme>>,
     css_provider: gtk4::CssProvider,
     user_css_provider: gtk4::CssProvider,
+    /// False on X11 and compositors without wlr-layer-shell, where the panel is an
+    /// ordinary top-level window.
+    layer_shell: bool,
 }
 
 impl Clone for OrbitWindow {
@@ -54,23 +150,70 @@ impl Clone for OrbitWindow {
             details_revealer: self.details_revealer.clone(),
             details_box: self.details_box.clone(),
             details_content: self.details_content.clone(),
+            details_share_btn: self.details_share_btn.clone(),
+            details_ssid: self.details_ssid.clone(),
+            on_share: self.on_share.clone(),
+            details_dns_btn: self.details_dns_btn.clone(),
+            details_dns: self.details_dns.clone(),
+            details_device_path: self.details_device_path.clone(),
+            on_dns_override: self.on_dns_override.clone(),
+            throughput_box: self.throughput_box.clone(),
+            throughput_label: self.throughput_label.clone(),
+            throughput_graph: self.throughput_graph.clone(),
+            throughput_history: self.throughput_history.clone(),
+            throughput_timer: self.throughput_timer.clone(),
+            on_stats_tick: self.on_stats_tick.clone(),
             password_revealer: self.password_revealer.clone(),
             password_box: self.password_box.clone(),
             password_entry: self.password_entry.clone(),
             password_label: self.password_label.clone(),
             password_error_label: self.password_error_label.clone(),
+            caps_lock_label: self.caps_lock_label.clone(),
+            autoconnect_check: self.autoconnect_check.clone(),
+            password_is_psk: self.password_is_psk.clone(),
+            password_labels: self.password_labels.clone(),
             password_connect_btn: self.password_connect_btn.clone(),
             password_callback: self.password_callback.clone(),
+            enterprise_box: self.enterprise_box.clone(),
+            identity_entry: self.identity_entry.clone(),
+            anonymous_identity_entry: self.anonymous_identity_entry.clone(),
+            eap_method_dropdown: self.eap_method_dropdown.clone(),
+            phase2_dropdown: self.phase2_dropdown.clone(),
+            enterprise_callback: self.enterprise_callback.clone(),
             hidden_revealer: self.hidden_revealer.clone(),
             hidden_ssid_entry: self.hidden_ssid_entry.clone(),
             hidden_password_entry: self.hidden_password_entry.clone(),
             hidden_callback: self.hidden_callback.clone(),
+            add_revealer: self.add_revealer.clone(),
+            add_ssid_entry: self.add_ssid_entry.clone(),
+            add_security_dropdown: self.add_security_dropdown.clone(),
+            add_password_entry: self.add_password_entry.clone(),
+            add_hidden_check: self.add_hidden_check.clone(),
+            add_error_label: self.add_error_label.clone(),
+            add_callback: self.add_callback.clone(),
+            ipv4_revealer: self.ipv4_revealer.clone(),
+            ipv4_label: self.ipv4_label.clone(),
+            ipv4_address_entry: self.ipv4_address_entry.clone(),
+            ipv4_prefix_entry: self.ipv4_prefix_entry.clone(),
+            ipv4_gateway_entry: self.ipv4_gateway_entry.clone(),
+            ipv4_dns_entry: self.ipv4_dns_entry.clone(),
+            ipv4_error_label: self.ipv4_error_label.clone(),
+            ipv4_callback: self.ipv4_callback.clone(),
             error_revealer: self.error_revealer.clone(),
             error_box: self.error_box.clone(),
             error_label: self.error_label.clone(),
+            transfer_revealer: self.transfer_revealer.clone(),
+            transfer_label: self.transfer_label.clone(),
+            transfer_progress: self.transfer_progress.clone(),
+            wps_revealer: self.wps_revealer.clone(),
+            wps_label: self.wps_label.clone(),
+            wps_progress: self.wps_progress.clone(),
+            wps_timer: self.wps_timer.clone(),
+            wps_cancel_callback: self.wps_cancel_callback.clone(),
             theme: self.theme.clone(),
             css_provider: self.css_provider.clone(),
             user_css_provider: self.user_css_provider.clone(),
+            layer_shell: self.layer_shell,
 
         }
     }
@@ -80,18 +223,27 @@ impl OrbitWindow {
     pub fn new(app: &Application, config: Config, theme: Rc<RefCell<Theme>>) -> Self {
         let window = ApplicationWindow::builder()
             .application(app)
-            .default_width(420)
-            .default_height(500)
+            .default_width(config.width)
+            .default_height(config.height)
             .resizable(false)
             .decorated(false)
             .build();
         
-        window.init_layer_shell();
-        window.set_namespace("orbit");
-        window.set_layer(Layer::Overlay);
-        window.set_keyboard_mode(KeyboardMode::None);
-        window.set_exclusive_zone(0);
-        window.set_default_size(420, 500);
+        let layer_shell = gtk4_layer_shell::is_supported();
+        if layer_shell {
+            window.init_layer_shell();
+            window.set_namespace("orbit");
+            window.set_layer(Layer::Overlay);
+            window.set_keyboard_mode(KeyboardMode::None);
+        } else {
+            log::info!("Layer shell not supported, showing Orbit as a normal window");
+            window.set_title(Some("Orbit"));
+            window.set_decorated(true);
+            // The title bar's close button hides, like Escape, so the daemon keeps the window
+            window.set_hide_on_close(true);
+        }
+        window.set_default_size(config.width, config.height);
+        window.set_size_request(config.width, config.height);
         
         window.add_css_class("background");
         
@@ -159,15 +311,34 @@ impl OrbitWindow {
             .spacing(4)
             .build();
         
+        let details_btn_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .halign(gtk::Align::Center)
+            .margin_top(4)
+            .build();
+        
+        let details_share_btn = gtk::Button::builder()
+            .label("Share")
+            .css_classes(["orbit-button", "flat"])
+            .build();
+        
+        let details_dns_btn = gtk::Button::builder()
+            .label("DNS")
+            .css_classes(["orbit-button", "flat"])
+            .build();
+        
         let close_btn = gtk::Button::builder()
             .label("Close")
             .css_classes(["orbit-button", "primary", "flat"])
-            .halign(gtk::Align::Center)
-            .margin_top(4)
             .build();
         
+        details_btn_row.append(&details_share_btn);
+        details_btn_row.append(&details_dns_btn);
+        details_btn_row.append(&close_btn);
+        
         details_box.append(&details_content);
-        details_box.append(&close_btn);
+        details_box.append(&details_btn_row);
         
         let details_revealer = gtk::Revealer::builder()
             .child(&details_box)
@@ -185,6 +356,75 @@ impl OrbitWindow {
         
         overlay.add_overlay(&details_revealer);
         
+        let details_ssid = Rc::new(RefCell::new(String::new()));
+        let on_share: Rc<RefCell<Option<Rc<dyn Fn(String)>>>> = Rc::new(RefCell::new(None));
+        
+        let throughput_box = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(4)
+            .css_classes(["orbit-details-row"])
+            .build();
+        
+        let throughput_label = gtk::Label::builder()
+            .label("↓ -  ↑ -")
+            .css_classes(["orbit-detail-value"])
+            .halign(gtk::Align::Start)
+            .build();
+        
+        let throughput_graph = gtk::DrawingArea::builder()
+            .content_height(48)
+            .hexpand(true)
+            .build();
+        
+        throughput_box.append(&throughput_label);
+        throughput_box.append(&throughput_graph);
+        
+        let throughput_history = Rc::new(RefCell::new(ThroughputHistory::default()));
+        let throughput_timer: Rc<RefCell<Option<gtk4::glib::SourceId>>> = Rc::new(RefCell::new(None));
+        let on_stats_tick: Rc<RefCell<Option<Rc<dyn Fn(String)>>>> = Rc::new(RefCell::new(None));
+        
+        let history_draw = throughput_history.clone();
+        let theme_draw = theme.clone();
+        throughput_graph.set_draw_func(move |_, cr, width, height| {
+            let history = history_draw.borrow();
+            if history.rates.len() < 2 {
+                return;
+            }
+            let peak = history.rates.iter()
+                .map(|(down, up)| down.max(*up))
+                .fold(1024.0_f64, f64::max);
+            let step = width as f64 / (THROUGHPUT_SAMPLES - 1) as f64;
+            let offset = (THROUGHPUT_SAMPLES - history.rates.len()) as f64 * step;
+            let theme = theme_draw.borrow();
+            let series: [(&str, fn(&(f64, f64)) -> f64); 2] = [
+                (theme.accent_primary.as_str(), |r| r.0),
+                (theme.accent_secondary.as_str(), |r| r.1),
+            ];
+            for (color, pick) in series {
+                let rgba = gtk4::gdk::RGBA::parse(color).unwrap_or(gtk4::gdk::RGBA::WHITE);
+                cr.set_source_rgba(rgba.red() as f64, rgba.green() as f64, rgba.blue() as f64, 0.9);
+                cr.set_line_width(1.5);
+                for (i, rate) in history.rates.iter().enumerate() {
+                    let x = offset + i as f64 * step;
+                    let y = height as f64 - (pick(rate) / peak) * (height as f64 - 2.0) - 1.0;
+                    if i == 0 {
+                        cr.move_to(x, y);
+                    } else {
+                        cr.line_to(x, y);
+                    }
+                }
+                let _ = cr.stroke();
+            }
+        });
+        
+        let details_ssid_share = details_ssid.clone();
+        let on_share_clone = on_share.clone();
+        details_share_btn.connect_clicked(move |_| {
+            if let Some(callback) = on_share_clone.borrow().as_ref() {
+                callback(details_ssid_share.borrow().clone());
+            }
+        });
+        
         let password_box = gtk::Box::builder()
             .orientation(Orientation::Vertical)
             .spacing(12)
@@ -207,6 +447,67 @@ impl OrbitWindow {
             .hexpand(true)
             .build();
         
+        let caps_lock_label = gtk::Label::builder()
+            .label("Caps Lock is on")
+            .css_classes(["orbit-caps-lock-warning"])
+            .halign(gtk::Align::Start)
+            .visible(caps_lock_on())
+            .build();
+        
+        // Follow the keyboard's lock state rather than key events, so toggling
+        // Caps Lock before the dialog opens is picked up too
+        if let Some(keyboard) = gtk4::gdk::Display::default()
+            .and_then(|display| display.default_seat())
+            .and_then(|seat| seat.keyboard())
+        {
+            let caps_lock_label = caps_lock_label.clone();
+            keyboard.connect_caps_lock_state_notify(move |device| {
+                caps_lock_label.set_visible(device.is_caps_locked());
+            });
+        }
+        
+        let autoconnect_check = gtk::CheckButton::builder()
+            .label("Connect automatically")
+            .active(true)
+            .css_classes(["orbit-detail-label"])
+            .build();
+        
+        // WPA-Enterprise fields, only shown for 802.1X networks
+        let enterprise_box = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(8)
+            .visible(false)
+            .build();
+        
+        let identity_entry = gtk::Entry::builder()
+            .placeholder_text("Identity (username)")
+            .hexpand(true)
+            .build();
+        
+        let anonymous_identity_entry = gtk::Entry::builder()
+            .placeholder_text("Anonymous identity (optional)")
+            .hexpand(true)
+            .build();
+        
+        let eap_labels: Vec<&str> = EAP_METHODS.iter().map(|(label, _)| *label).collect();
+        let eap_method_dropdown = gtk::DropDown::from_strings(&eap_labels);
+        eap_method_dropdown.set_hexpand(true);
+        
+        let phase2_labels: Vec<&str> = PHASE2_METHODS.iter().map(|(label, _)| *label).collect();
+        let phase2_dropdown = gtk::DropDown::from_strings(&phase2_labels);
+        phase2_dropdown.set_hexpand(true);
+        
+        let eap_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
+        eap_row.append(&eap_method_dropdown);
+        eap_row.append(&phase2_dropdown);
+        
+        enterprise_box.append(&identity_entry);
+        enterprise_box.append(&eap_row);
+        enterprise_box.append(&anonymous_identity_entry);
+        
         let password_error_label = gtk::Label::builder()
             .label("")
             .css_classes(["orbit-password-error"])
@@ -237,7 +538,10 @@ impl OrbitWindow {
         password_btn_row.append(&password_connect_btn);
         
         password_box.append(&password_label);
+        password_box.append(&enterprise_box);
         password_box.append(&password_entry);
+        password_box.append(&caps_lock_label);
+        password_box.append(&autoconnect_check);
         password_box.append(&password_error_label);
         password_box.append(&password_btn_row);
         
@@ -314,6 +618,186 @@ impl OrbitWindow {
         
         overlay.add_overlay(&hidden_revealer);
         
+        // "Add network" form: saves a profile for a network that isn't in range yet
+        let add_box = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(12)
+            .css_classes(["orbit-password-overlay"])
+            .margin_start(16)
+            .margin_end(16)
+            .margin_top(16)
+            .margin_bottom(16)
+            .build();
+        
+        let add_label = gtk::Label::builder()
+            .label("Save a network to join when in range:")
+            .css_classes(["orbit-detail-label"])
+            .halign(gtk::Align::Start)
+            .build();
+        
+        let add_ssid_entry = gtk::Entry::builder()
+            .placeholder_text("Network SSID")
+            .hexpand(true)
+            .build();
+        
+        let add_security_dropdown = gtk::DropDown::from_strings(&ADD_SECURITY_LABELS);
+        add_security_dropdown.set_selected(ADD_SECURITY_DEFAULT);
+        add_security_dropdown.set_hexpand(true);
+        
+        let add_password_entry = gtk::PasswordEntry::builder()
+            .placeholder_text("Password")
+            .show_peek_icon(true)
+            .hexpand(true)
+            .build();
+        
+        let add_hidden_check = gtk::CheckButton::builder()
+            .label("Hidden network (SSID not broadcast)")
+            .css_classes(["orbit-detail-label"])
+            .build();
+        
+        let add_error_label = gtk::Label::builder()
+            .label("")
+            .css_classes(["orbit-password-error"])
+            .halign(gtk::Align::Start)
+            .visible(false)
+            .build();
+        
+        let add_btn_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .halign(gtk::Align::End)
+            .build();
+        
+        let add_cancel_btn = gtk::Button::builder()
+            .label("Cancel")
+            .css_classes(["orbit-button", "flat"])
+            .build();
+        
+        let add_save_btn = gtk::Button::builder()
+            .label("Save")
+            .css_classes(["orbit-button", "primary", "flat"])
+            .build();
+        
+        add_btn_row.append(&add_cancel_btn);
+        add_btn_row.append(&add_save_btn);
+        
+        add_box.append(&add_label);
+        add_box.append(&add_ssid_entry);
+        add_box.append(&add_security_dropdown);
+        add_box.append(&add_password_entry);
+        add_box.append(&add_hidden_check);
+        add_box.append(&add_error_label);
+        add_box.append(&add_btn_row);
+        
+        let add_revealer = gtk::Revealer::builder()
+            .child(&add_box)
+            .reveal_child(false)
+            .transition_type(gtk::RevealerTransitionType::SlideUp)
+            .transition_duration(250)
+            .valign(gtk::Align::End)
+            .can_target(true)
+            .build();
+        
+        overlay.add_overlay(&add_revealer);
+        
+        let ipv4_box = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(12)
+            .css_classes(["orbit-password-overlay"])
+            .margin_start(16)
+            .margin_end(16)
+            .margin_top(16)
+            .margin_bottom(16)
+            .build();
+        
+        let ipv4_label = gtk::Label::builder()
+            .label("Static IPv4:")
+            .css_classes(["orbit-detail-label"])
+            .halign(gtk::Align::Start)
+            .build();
+        
+        let ipv4_address_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
+        
+        let ipv4_address_entry = gtk::Entry::builder()
+            .placeholder_text("Address (192.168.1.50)")
+            .hexpand(true)
+            .build();
+        
+        let ipv4_prefix_entry = gtk::Entry::builder()
+            .placeholder_text("Prefix")
+            .text("24")
+            .width_chars(4)
+            .max_length(2)
+            .input_purpose(gtk::InputPurpose::Digits)
+            .build();
+        
+        ipv4_address_row.append(&ipv4_address_entry);
+        ipv4_address_row.append(&ipv4_prefix_entry);
+        
+        let ipv4_gateway_entry = gtk::Entry::builder()
+            .placeholder_text("Gateway (optional)")
+            .hexpand(true)
+            .build();
+        
+        let ipv4_dns_entry = gtk::Entry::builder()
+            .placeholder_text("DNS servers, comma separated (optional)")
+            .hexpand(true)
+            .build();
+        
+        let ipv4_error_label = gtk::Label::builder()
+            .label("")
+            .css_classes(["orbit-password-error"])
+            .halign(gtk::Align::Start)
+            .wrap(true)
+            .visible(false)
+            .build();
+        
+        let ipv4_btn_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .halign(gtk::Align::End)
+            .build();
+        
+        let ipv4_dhcp_btn = gtk::Button::builder()
+            .label("Use DHCP")
+            .css_classes(["orbit-button", "flat"])
+            .build();
+        
+        let ipv4_cancel_btn = gtk::Button::builder()
+            .label("Cancel")
+            .css_classes(["orbit-button", "flat"])
+            .build();
+        
+        let ipv4_save_btn = gtk::Button::builder()
+            .label("Save")
+            .css_classes(["orbit-button", "primary", "flat"])
+            .build();
+        
+        ipv4_btn_row.append(&ipv4_dhcp_btn);
+        ipv4_btn_row.append(&ipv4_cancel_btn);
+        ipv4_btn_row.append(&ipv4_save_btn);
+        
+        ipv4_box.append(&ipv4_label);
+        ipv4_box.append(&ipv4_address_row);
+        ipv4_box.append(&ipv4_gateway_entry);
+        ipv4_box.append(&ipv4_dns_entry);
+        ipv4_box.append(&ipv4_error_label);
+        ipv4_box.append(&ipv4_btn_row);
+        
+        let ipv4_revealer = gtk::Revealer::builder()
+            .child(&ipv4_box)
+            .reveal_child(false)
+            .transition_type(gtk::RevealerTransitionType::SlideUp)
+            .transition_duration(250)
+            .valign(gtk::Align::End)
+            .can_target(true)
+            .build();
+        
+        overlay.add_overlay(&ipv4_revealer);
+        
         let error_box = gtk::Box::builder()
             .orientation(Orientation::Horizontal)
             .spacing(12)
@@ -362,19 +846,172 @@ impl OrbitWindow {
         
         overlay.add_overlay(&error_revealer);
         
+        // Bluetooth file transfer progress; informational only, so it takes no input
+        let transfer_box = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(8)
+            .css_classes(["orbit-transfer-overlay"])
+            .margin_start(16)
+            .margin_end(16)
+            .margin_top(16)
+            .margin_bottom(16)
+            .build();
+        
+        let transfer_label = gtk::Label::builder()
+            .label("")
+            .css_classes(["orbit-status"])
+            .ellipsize(gtk::pango::EllipsizeMode::Middle)
+            .halign(gtk::Align::Start)
+            .build();
+        
+        let transfer_progress = gtk::ProgressBar::builder()
+            .hexpand(true)
+            .build();
+        
+        transfer_box.append(&transfer_label);
+        transfer_box.append(&transfer_progress);
+        
+        let transfer_revealer = gtk::Revealer::builder()
+            .child(&transfer_box)
+            .reveal_child(false)
+            .transition_type(gtk::RevealerTransitionType::SlideUp)
+            .transition_duration(250)
+            .valign(gtk::Align::End)
+            .can_target(false)
+            .build();
+        
+        overlay.add_overlay(&transfer_revealer);
+        
+        // WPS push-button prompt, counting down while the router's button can be pressed
+        let wps_box = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(8)
+            .css_classes(["orbit-transfer-overlay"])
+            .margin_start(16)
+            .margin_end(16)
+            .margin_top(16)
+            .margin_bottom(16)
+            .build();
+        
+        let wps_label = gtk::Label::builder()
+            .label("")
+            .css_classes(["orbit-status"])
+            .wrap(true)
+            .halign(gtk::Align::Start)
+            .build();
+        
+        let wps_progress = gtk::ProgressBar::builder()
+            .hexpand(true)
+            .build();
+        
+        let wps_cancel_btn = gtk::Button::builder()
+            .label("Cancel")
+            .css_classes(["orbit-button", "flat"])
+            .halign(gtk::Align::End)
+            .build();
+        
+        wps_box.append(&wps_label);
+        wps_box.append(&wps_progress);
+        wps_box.append(&wps_cancel_btn);
+        
+        let wps_revealer = gtk::Revealer::builder()
+            .child(&wps_box)
+            .reveal_child(false)
+            .transition_type(gtk::RevealerTransitionType::SlideUp)
+            .transition_duration(250)
+            .valign(gtk::Align::End)
+            .build();
+        
+        let wps_cancel_callback: Rc<RefCell<Option<Rc<dyn Fn()>>>> = Rc::new(RefCell::new(None));
+        let wps_cancel_cb = wps_cancel_callback.clone();
+        wps_cancel_btn.connect_clicked(move |_| {
+            if let Some(callback) = wps_cancel_cb.borrow().as_ref() {
+                callback();
+            }
+        });
+        
+        overlay.add_overlay(&wps_revealer);
+        
         window.set_child(Some(&overlay));
         
-        let password_callback: Rc<RefCell<Option<Rc<dyn Fn(Option<String>)>>>> = Rc::new(RefCell::new(None));
+        let password_callback: Rc<RefCell<Option<Rc<dyn Fn(Option<(String, bool)>)>>>> = Rc::new(RefCell::new(None));
+        let password_is_psk = Rc::new(RefCell::new(false));
+        let password_labels = Rc::new(RefCell::new(("Connect", "Connecting...")));
+        let enterprise_callback: Rc<RefCell<Option<Rc<dyn Fn(Option<EnterpriseCredentials>)>>>> = Rc::new(RefCell::new(None));
         let hidden_callback: Rc<RefCell<Option<Rc<dyn Fn(Option<(String, String)>)>>>> = Rc::new(RefCell::new(None));
+        let add_callback: Rc<RefCell<Option<Rc<dyn Fn(ExportedNetwork)>>>> = Rc::new(RefCell::new(None));
 
-        let hidden_ssid_entry_clone = hidden_ssid_entry.clone();
-        let hidden_password_entry_clone = hidden_password_entry.clone();
-        let hidden_callback_clone = hidden_callback.clone();
-        let hidden_revealer_clone = hidden_revealer.clone();
-        hidden_connect_btn.connect_clicked(move |_| {
-            let ssid = hidden_ssid_entry_clone.text().to_string();
-            let pw = hidden_password_entry_clone.text().to_string();
-            if ssid.is_empty() {
+        let ipv4_callback: Rc<RefCell<Option<Rc<dyn Fn(Ipv4Method)>>>> = Rc::new(RefCell::new(None));
+
+        let ipv4_revealer_save = ipv4_revealer.clone();
+        let ipv4_address_save = ipv4_address_entry.clone();
+        let ipv4_prefix_save = ipv4_prefix_entry.clone();
+        let ipv4_gateway_save = ipv4_gateway_entry.clone();
+        let ipv4_dns_save = ipv4_dns_entry.clone();
+        let ipv4_error_save = ipv4_error_label.clone();
+        let ipv4_callback_save = ipv4_callback.clone();
+        ipv4_save_btn.connect_clicked(move |_| {
+            let address = ipv4_address_save.text().trim().to_string();
+            let gateway = ipv4_gateway_save.text().trim().to_string();
+            let dns: Vec<String> = ipv4_dns_save.text()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            let prefix = ipv4_prefix_save.text().trim().parse::<u32>().ok().filter(|p| (1..=32).contains(p));
+            
+            let error = if address.parse::<std::net::Ipv4Addr>().is_err() {
+                Some("Enter a valid IPv4 address")
+            } else if prefix.is_none() {
+                Some("Prefix must be between 1 and 32")
+            } else if !gateway.is_empty() && gateway.parse::<std::net::Ipv4Addr>().is_err() {
+                Some("Enter a valid gateway address")
+            } else if dns.iter().any(|d| d.parse::<std::net::Ipv4Addr>().is_err()) {
+                Some("DNS servers must be IPv4 addresses")
+            } else {
+                None
+            };
+            if let Some(msg) = error {
+                ipv4_error_save.set_label(msg);
+                ipv4_error_save.set_visible(true);
+                return;
+            }
+            
+            ipv4_revealer_save.set_reveal_child(false);
+            if let Some(cb) = ipv4_callback_save.borrow_mut().take() {
+                cb(Ipv4Method::Manual {
+                    address,
+                    prefix: prefix.unwrap_or(24),
+                    gateway,
+                    dns,
+                });
+            }
+        });
+
+        let ipv4_revealer_dhcp = ipv4_revealer.clone();
+        let ipv4_callback_dhcp = ipv4_callback.clone();
+        ipv4_dhcp_btn.connect_clicked(move |_| {
+            ipv4_revealer_dhcp.set_reveal_child(false);
+            if let Some(cb) = ipv4_callback_dhcp.borrow_mut().take() {
+                cb(Ipv4Method::Auto);
+            }
+        });
+
+        let ipv4_revealer_cancel = ipv4_revealer.clone();
+        let ipv4_callback_cancel = ipv4_callback.clone();
+        ipv4_cancel_btn.connect_clicked(move |_| {
+            ipv4_revealer_cancel.set_reveal_child(false);
+            *ipv4_callback_cancel.borrow_mut() = None;
+        });
+
+        let hidden_ssid_entry_clone = hidden_ssid_entry.clone();
+        let hidden_password_entry_clone = hidden_password_entry.clone();
+        let hidden_callback_clone = hidden_callback.clone();
+        let hidden_revealer_clone = hidden_revealer.clone();
+        hidden_connect_btn.connect_clicked(move |_| {
+            let ssid = hidden_ssid_entry_clone.text().to_string();
+            let pw = hidden_password_entry_clone.text().to_string();
+            if ssid.is_empty() {
                 return;
             }
             hidden_revealer_clone.set_reveal_child(false);
@@ -396,6 +1033,67 @@ impl OrbitWindow {
             }
         });
 
+        // Open networks have no password to ask for
+        let add_password_security = add_password_entry.clone();
+        add_security_dropdown.connect_selected_notify(move |dropdown| {
+            add_password_security.set_visible(add_security(dropdown.selected()) != SecurityType::None);
+        });
+        
+        let submit_add: Rc<dyn Fn()> = {
+            let add_ssid_entry = add_ssid_entry.clone();
+            let add_security_dropdown = add_security_dropdown.clone();
+            let add_password_entry = add_password_entry.clone();
+            let add_hidden_check = add_hidden_check.clone();
+            let add_error_label = add_error_label.clone();
+            let add_revealer = add_revealer.clone();
+            let add_callback = add_callback.clone();
+            Rc::new(move || {
+                let ssid = add_ssid_entry.text().trim().to_string();
+                let security = add_security(add_security_dropdown.selected());
+                let pw = add_password_entry.text().to_string();
+                let problem = if ssid.is_empty() {
+                    Some("SSID cannot be empty")
+                } else if security != SecurityType::None && pw.is_empty() {
+                    Some("Password cannot be empty")
+                } else if matches!(security, SecurityType::WPA | SecurityType::WPA2) {
+                    psk_length_hint(&pw)
+                } else {
+                    None
+                };
+                if let Some(problem) = problem {
+                    add_error_label.set_label(problem);
+                    add_error_label.set_visible(true);
+                    return;
+                }
+                add_revealer.set_reveal_child(false);
+                add_password_entry.set_text("");
+                if let Some(cb) = add_callback.borrow_mut().take() {
+                    cb(ExportedNetwork {
+                        ssid,
+                        psk: if security == SecurityType::None { None } else { Some(pw) },
+                        security,
+                        hidden: add_hidden_check.is_active(),
+                        autoconnect: true,
+                    });
+                }
+            })
+        };
+        
+        let submit_add_click = submit_add.clone();
+        add_save_btn.connect_clicked(move |_| submit_add_click());
+        let submit_add_ssid = submit_add.clone();
+        add_ssid_entry.connect_activate(move |_| submit_add_ssid());
+        add_password_entry.connect_activate(move |_| submit_add());
+        
+        let add_revealer_cancel = add_revealer.clone();
+        let add_password_cancel = add_password_entry.clone();
+        let add_callback_cancel = add_callback.clone();
+        add_cancel_btn.connect_clicked(move |_| {
+            add_revealer_cancel.set_reveal_child(false);
+            add_password_cancel.set_text("");
+            add_callback_cancel.borrow_mut().take();
+        });
+
         // Enter-to-submit in hidden network entries
         let hidden_ssid_activate = hidden_ssid_entry.clone();
         let hidden_password_activate = hidden_password_entry.clone();
@@ -425,24 +1123,66 @@ impl OrbitWindow {
             }
         });
 
-        let password_entry_clone = password_entry.clone();
-        let password_callback_clone = password_callback.clone();
-        let password_connect_btn_clone = password_connect_btn.clone();
-        let password_error_label_clone = password_error_label.clone();
+        let submit_password: Rc<dyn Fn()> = {
+            let password_entry = password_entry.clone();
+            let password_callback = password_callback.clone();
+            let password_is_psk = password_is_psk.clone();
+            let password_labels = password_labels.clone();
+            let autoconnect_check = autoconnect_check.clone();
+            let password_connect_btn = password_connect_btn.clone();
+            let password_error_label = password_error_label.clone();
+            let enterprise_box = enterprise_box.clone();
+            let identity_entry = identity_entry.clone();
+            let anonymous_identity_entry = anonymous_identity_entry.clone();
+            let eap_method_dropdown = eap_method_dropdown.clone();
+            let phase2_dropdown = phase2_dropdown.clone();
+            let enterprise_callback = enterprise_callback.clone();
+            Rc::new(move || {
+                let pw = password_entry.text().to_string();
+                let is_enterprise = enterprise_box.is_visible();
+                let identity = identity_entry.text().trim().to_string();
+                if is_enterprise && identity.is_empty() {
+                    password_error_label.set_label("Identity cannot be empty");
+                    password_error_label.set_visible(true);
+                    return;
+                }
+                if pw.is_empty() {
+                    password_error_label.set_label("Password cannot be empty");
+                    password_error_label.set_visible(true);
+                    return;
+                }
+                if !is_enterprise && *password_is_psk.borrow() {
+                    if let Some(hint) = psk_length_hint(&pw) {
+                        password_error_label.set_label(hint);
+                        password_error_label.set_visible(true);
+                        return;
+                    }
+                }
+                // Set connecting state - don't close dialog
+                password_connect_btn.set_label(password_labels.borrow().1);
+                password_connect_btn.set_sensitive(false);
+                password_error_label.set_visible(false);
+                if is_enterprise {
+                    let anonymous = anonymous_identity_entry.text().trim().to_string();
+                    let credentials = EnterpriseCredentials {
+                        identity,
+                        password: pw,
+                        eap_method: EAP_METHODS[eap_method_dropdown.selected() as usize % EAP_METHODS.len()].1.to_string(),
+                        phase2_auth: PHASE2_METHODS[phase2_dropdown.selected() as usize % PHASE2_METHODS.len()].1.to_string(),
+                        anonymous_identity: if anonymous.is_empty() { None } else { Some(anonymous) },
+                    };
+                    if let Some(ref cb) = *enterprise_callback.borrow() {
+                        cb(Some(credentials));
+                    }
+                } else if let Some(ref cb) = *password_callback.borrow() {
+                    cb(Some((pw, autoconnect_check.is_active())));
+                }
+            })
+        };
+        
+        let submit_password_click = submit_password.clone();
         password_connect_btn.connect_clicked(move |_| {
-            let pw = password_entry_clone.text().to_string();
-            if pw.is_empty() {
-                password_error_label_clone.set_label("Password cannot be empty");
-                password_error_label_clone.set_visible(true);
-                return;
-            }
-            // Set connecting state - don't close dialog
-            password_connect_btn_clone.set_label("Connecting...");
-            password_connect_btn_clone.set_sensitive(false);
-            password_error_label_clone.set_visible(false);
-            if let Some(ref cb) = *password_callback_clone.borrow() {
-                cb(Some(pw));
-            }
+            submit_password_click();
         });
         
         let password_revealer_clone2 = password_revealer.clone();
@@ -450,7 +1190,13 @@ impl OrbitWindow {
         let password_callback_clone2 = password_callback.clone();
         let password_error_label_clone2 = password_error_label.clone();
         let password_connect_btn_clone2 = password_connect_btn.clone();
+        let enterprise_callback_cancel = enterprise_callback.clone();
+        let network_list_cancel = network_list.clone();
         password_cancel_btn.connect_clicked(move |_| {
+            // Cancelling while "Connecting..." also aborts the pending activation
+            if !password_connect_btn_clone2.is_sensitive() {
+                network_list_cancel.cancel_connect();
+            }
             password_entry_clone2.set_text("");
             password_revealer_clone2.set_reveal_child(false);
             password_error_label_clone2.set_visible(false);
@@ -459,26 +1205,15 @@ impl OrbitWindow {
             if let Some(cb) = password_callback_clone2.borrow_mut().take() {
                 cb(None);
             }
+            if let Some(cb) = enterprise_callback_cancel.borrow_mut().take() {
+                cb(None);
+            }
         });
         
         // Enter-to-submit in password entry
-        let password_entry_activate = password_entry.clone();
-        let password_callback_activate = password_callback.clone();
-        let password_connect_btn_activate = password_connect_btn.clone();
-        let password_error_label_activate = password_error_label.clone();
+        let submit_password_activate = submit_password.clone();
         password_entry.connect_activate(move |_| {
-            let pw = password_entry_activate.text().to_string();
-            if pw.is_empty() {
-                password_error_label_activate.set_label("Password cannot be empty");
-                password_error_label_activate.set_visible(true);
-                return;
-            }
-            password_connect_btn_activate.set_label("Connecting...");
-            password_connect_btn_activate.set_sensitive(false);
-            password_error_label_activate.set_visible(false);
-            if let Some(ref cb) = *password_callback_activate.borrow() {
-                cb(Some(pw));
-            }
+            submit_password_activate();
         });
         
         let win = Self {
@@ -492,30 +1227,90 @@ impl OrbitWindow {
             details_revealer,
             details_box,
             details_content,
+            details_share_btn,
+            details_ssid,
+            on_share,
+            details_dns_btn: details_dns_btn.clone(),
+            details_dns: Rc::new(RefCell::new(Vec::new())),
+            details_device_path: Rc::new(RefCell::new(String::new())),
+            on_dns_override: Rc::new(RefCell::new(None)),
+            throughput_box,
+            throughput_label,
+            throughput_graph,
+            throughput_history,
+            throughput_timer,
+            on_stats_tick,
             password_revealer,
             password_box,
             password_entry,
             password_label,
             password_error_label,
+            caps_lock_label,
+            autoconnect_check,
+            password_is_psk,
+            password_labels,
             password_connect_btn: password_connect_btn.clone(),
             password_callback,
+            enterprise_box,
+            identity_entry,
+            anonymous_identity_entry,
+            eap_method_dropdown,
+            phase2_dropdown,
+            enterprise_callback,
             hidden_revealer,
             hidden_ssid_entry,
             hidden_password_entry,
             hidden_callback,
+            add_revealer,
+            add_ssid_entry,
+            add_security_dropdown,
+            add_password_entry,
+            add_hidden_check,
+            add_error_label,
+            add_callback,
+            ipv4_revealer,
+            ipv4_label,
+            ipv4_address_entry,
+            ipv4_prefix_entry,
+            ipv4_gateway_entry,
+            ipv4_dns_entry,
+            ipv4_error_label,
+            ipv4_callback,
             error_revealer,
             error_box,
             error_label,
+            transfer_revealer,
+            transfer_label,
+            transfer_progress,
+            wps_revealer,
+            wps_label,
+            wps_progress,
+            wps_timer: Rc::new(RefCell::new(None)),
+            wps_cancel_callback,
             theme,
             css_provider,
             user_css_provider,
-
+            layer_shell,
         };
 
         // Add Escape key shortcut to hide the window
         let key_controller = gtk::EventControllerKey::new();
         let win_clone = win.clone();
-        key_controller.connect_key_pressed(move |_, key, _, _| {
+        key_controller.connect_key_pressed(move |_, key, _, modifiers| {
+            let tab_index = match key {
+                gtk4::gdk::Key::_1 | gtk4::gdk::Key::KP_1 => Some(0),
+                gtk4::gdk::Key::_2 | gtk4::gdk::Key::KP_2 => Some(1),
+                gtk4::gdk::Key::_3 | gtk4::gdk::Key::KP_3 => Some(2),
+                _ => None,
+            };
+            if let Some(index) = tab_index.filter(|_| modifiers.contains(gtk4::gdk::ModifierType::CONTROL_MASK)) {
+                win_clone.switch_tab(index);
+                return gtk4::glib::Propagation::Stop;
+            }
+            if (key == gtk4::gdk::Key::Left || key == gtk4::gdk::Key::Right) && win_clone.header_tab_focused() {
+                win_clone.cycle_tab(if key == gtk4::gdk::Key::Left { -1 } else { 1 });
+                return gtk4::glib::Propagation::Stop;
+            }
             if key == gtk4::gdk::Key::Escape {
                 // Hide overlays first if they are visible, otherwise hide window
                 if win_clone.details_revealer.reveals_child() {
@@ -524,6 +1319,11 @@ impl OrbitWindow {
                     win_clone.hide_password_dialog();
                 } else if win_clone.hidden_revealer.reveals_child() {
                     win_clone.hidden_revealer.set_reveal_child(false);
+                } else if win_clone.add_revealer.reveals_child() {
+                    win_clone.hide_add_network_dialog();
+                } else if win_clone.ipv4_revealer.reveals_child() {
+                    win_clone.ipv4_revealer.set_reveal_child(false);
+                    *win_clone.ipv4_callback.borrow_mut() = None;
                 } else if win_clone.error_revealer.reveals_child() {
                     win_clone.error_revealer.set_reveal_child(false);
                 } else {
@@ -542,13 +1342,73 @@ impl OrbitWindow {
         });
         window.add_controller(key_controller);
         
+        let win_dns = win.clone();
+        details_dns_btn.connect_clicked(move |_| {
+            win_dns.show_dns_editor();
+        });
+        
+        // Remember the peek icon's state for the next password prompt
+        for entry in [&win.password_entry, &win.hidden_password_entry, &win.add_password_entry] {
+            if let Some(text) = peek_text(entry) {
+                let win_peek = win.clone();
+                text.connect_visibility_notify(move |text| {
+                    win_peek.set_show_password(text.property::<bool>("visibility"));
+                });
+            }
+        }
         
         win.apply_position();
         win.apply_theme();
+        win.apply_list_config();
+        
+        let win_sections = win.clone();
+        let on_section_toggled = move |key: String, collapsed: bool| win_sections.set_section_collapsed(&key, collapsed);
+        win.network_list.set_on_section_toggled(on_section_toggled.clone());
+        win.saved_networks_list.set_on_section_toggled(on_section_toggled.clone());
+        win.device_list.set_on_section_toggled(on_section_toggled);
         
         win
     }
     
+    fn tab_buttons(&self) -> [&gtk::Button; 3] {
+        [self.header.wifi_tab(), self.header.saved_tab(), self.header.bluetooth_tab()]
+    }
+    
+    fn header_tab_focused(&self) -> bool {
+        self.tab_buttons().iter().any(|tab| tab.has_focus())
+    }
+    
+    /// Activates the tab at `index` (WiFi, Saved, Bluetooth) through its header button,
+    /// so the same refresh runs as for a click.
+    fn switch_tab(&self, index: usize) {
+        if let Some(tab) = self.tab_buttons().get(index) {
+            tab.emit_clicked();
+        }
+    }
+    
+    /// Switches to the tab with this stack name. Returns false for unknown names.
+    pub fn open_tab(&self, name: &str) -> bool {
+        match ["wifi", "saved", "bluetooth"].iter().position(|tab| *tab == name) {
+            Some(index) => {
+                self.switch_tab(index);
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Moves to the neighbouring tab, wrapping around, and keeps focus on the header.
+    fn cycle_tab(&self, delta: i32) {
+        let current = match self.stack.visible_child_name().as_deref() {
+            Some("saved") => 1,
+            Some("bluetooth") => 2,
+            _ => 0,
+        };
+        let next = (current + delta).rem_euclid(3) as usize;
+        self.switch_tab(next);
+        self.tab_buttons()[next].grab_focus();
+    }
+    
     pub fn apply_theme(&self) {
         let css = self.theme.borrow().generate_css();
         self.css_provider.load_from_data(&css);
@@ -568,12 +1428,22 @@ impl OrbitWindow {
     pub fn show(&self) {
         self.window.set_visible(true);
         self.window.present();
-        self.window.set_keyboard_mode(KeyboardMode::OnDemand);
+        if self.layer_shell {
+            self.window.set_keyboard_mode(KeyboardMode::OnDemand);
+        }
     }
     
     pub fn hide(&self) {
         self.window.set_visible(false);
-        self.window.set_keyboard_mode(KeyboardMode::None);
+        if self.layer_shell {
+            self.window.set_keyboard_mode(KeyboardMode::None);
+        }
+    }
+    
+    /// Whether the panel is a layer-shell surface; position, margins and monitor only
+    /// apply when it is.
+    pub fn is_layer_shell(&self) -> bool {
+        self.layer_shell
     }
     
     pub fn network_list(&self) -> &NetworkList {
@@ -601,6 +1471,9 @@ impl OrbitWindow {
     }
 
     pub fn apply_position(&self) {
+        if !self.layer_shell {
+            return;
+        }
         // Reset all anchors and margins
         self.window.set_anchor(Edge::Top, false);
         self.window.set_anchor(Edge::Bottom, false);
@@ -612,6 +1485,12 @@ impl OrbitWindow {
         self.window.set_margin(Edge::Right, 0);
 
         let config = self.config.borrow();
+        self.window.set_exclusive_zone(config.exclusive_zone);
+        // Moving a mapped surface to another output remaps it, so skip no-op changes
+        let monitor = find_monitor(config.monitor.as_deref());
+        if self.window.monitor() != monitor {
+            self.window.set_monitor(monitor.as_ref());
+        }
         let (col, row) = config.position_tuple();
 
         match (col, row) {
@@ -635,6 +1514,7 @@ impl OrbitWindow {
                 self.window.set_anchor(Edge::Left, true);
                 self.window.set_margin(Edge::Left, config.margin_left);
             }
+            // Centered: no anchors, the compositor centers the surface
             (1, 1) => {}
             (2, 1) => {
                 self.window.set_anchor(Edge::Right, true);
@@ -661,16 +1541,186 @@ impl OrbitWindow {
     }
 
     pub fn set_position(&self, position: &str) {
+        if Config::parse_position(position).is_none() {
+            log::warn!("Ignoring unknown position '{}' (expected one of: {})", position, crate::config::POSITIONS.join(", "));
+            return;
+        }
+        let changed = self.config.borrow().position != position;
         self.config.borrow_mut().position = position.to_string();
         self.apply_position();
+        if changed {
+            if let Err(e) = self.config.borrow().save() {
+                log::warn!("Failed to persist position: {}", e);
+            }
+        }
+    }
+
+    /// Overrides the configured margins for the edges that are set, e.g. to clear a
+    /// taller bar on another output.
+    pub fn set_margins(&self, margins: &Margins) {
+        let changed = {
+            let mut config = self.config.borrow_mut();
+            let before = (config.margin_top, config.margin_right, config.margin_bottom, config.margin_left);
+            config.margin_top = margins.top.unwrap_or(config.margin_top);
+            config.margin_right = margins.right.unwrap_or(config.margin_right);
+            config.margin_bottom = margins.bottom.unwrap_or(config.margin_bottom);
+            config.margin_left = margins.left.unwrap_or(config.margin_left);
+            before != (config.margin_top, config.margin_right, config.margin_bottom, config.margin_left)
+        };
+        self.apply_position();
+        if changed {
+            if let Err(e) = self.config.borrow().save() {
+                log::warn!("Failed to persist margins: {}", e);
+            }
+        }
+    }
+
+    /// The configured `default-position`, applied when a toggle names no position.
+    pub fn default_position(&self) -> Option<String> {
+        self.config.borrow().default_position.clone()
+    }
+
+    pub fn set_monitor_name(&self, monitor: &str) {
+        let changed = self.config.borrow().monitor.as_deref() != Some(monitor);
+        self.config.borrow_mut().monitor = Some(monitor.to_string());
+        self.apply_position();
+        if changed {
+            if let Err(e) = self.config.borrow().save() {
+                log::warn!("Failed to persist monitor: {}", e);
+            }
+        }
     }
 
+    pub fn set_show_password(&self, show: bool) {
+        let changed = self.config.borrow().show_password != show;
+        if changed {
+            self.config.borrow_mut().show_password = show;
+            if let Err(e) = self.config.borrow().save() {
+                log::warn!("Failed to persist password visibility: {}", e);
+            }
+        }
+    }
+
+    pub fn set_section_collapsed(&self, key: &str, collapsed: bool) {
+        {
+            let mut config = self.config.borrow_mut();
+            let sections = &mut config.collapsed_sections;
+            match (collapsed, sections.iter().position(|s| s == key)) {
+                (true, None) => sections.push(key.to_string()),
+                (false, Some(index)) => {
+                    sections.remove(index);
+                }
+                _ => return,
+            }
+        }
+        if let Err(e) = self.config.borrow().save() {
+            log::warn!("Failed to persist collapsed sections: {}", e);
+        }
+    }
+
+    fn apply_password_peek(&self) {
+        let show = self.config.borrow().show_password;
+        for entry in [&self.password_entry, &self.hidden_password_entry, &self.add_password_entry] {
+            if let Some(text) = peek_text(entry) {
+                text.set_visibility(show);
+            }
+        }
+    }
+
+    /// Re-reads the config file and applies it in place. An open panel stays open and keeps
+    /// its keyboard mode, so margins can be tuned live without the window blinking.
     pub fn reload_config(&self) {
+        let was_visible = self.window.is_visible();
         *self.config.borrow_mut() = Config::load();
+        let (width, height) = {
+            let config = self.config.borrow();
+            (config.width, config.height)
+        };
+        self.window.set_default_size(width, height);
+        self.window.set_size_request(width, height);
         self.apply_position();
+        self.apply_list_config();
+        if was_visible {
+            self.window.set_visible(true);
+            self.window.present();
+        }
+    }
+
+    pub fn is_readonly(&self) -> bool {
+        self.config.borrow().readonly
+    }
+
+    pub fn connect_retries(&self) -> u32 {
+        self.config.borrow().connect_retries
+    }
+
+    pub fn discoverable_timeout(&self) -> u32 {
+        self.config.borrow().discoverable_timeout
+    }
+
+    pub fn bt_scan_secs(&self) -> u32 {
+        self.config.borrow().bt_scan_secs
+    }
+
+    pub fn bt_connect_retry(&self) -> bool {
+        self.config.borrow().bt_connect_retry
+    }
+
+    pub fn scan_interval_secs(&self) -> u32 {
+        self.config.borrow().scan_interval_secs
+    }
+
+    pub fn idle_interval_secs(&self) -> u32 {
+        self.config.borrow().idle_interval_secs
+    }
+
+    pub fn captive_portal_url(&self) -> Option<String> {
+        self.config.borrow().captive_portal_url.clone()
+    }
+
+    pub fn notify_command(&self) -> String {
+        self.config.borrow().notify_command.clone()
+    }
+
+    pub fn global_shortcut(&self) -> Option<String> {
+        self.config.borrow().global_shortcut.clone()
+    }
+
+    pub fn tray_icon(&self) -> bool {
+        self.config.borrow().tray_icon
+    }
+
+    /// (notify, open browser) for a detected captive portal.
+    pub fn captive_portal_behavior(&self) -> (bool, bool) {
+        let config = self.config.borrow();
+        (config.captive_portal_notifies(), config.captive_portal_opens_browser())
+    }
+
+    fn apply_list_config(&self) {
+        let readonly = self.is_readonly();
+        self.header.set_readonly(readonly);
+        self.network_list.set_readonly(readonly);
+        self.saved_networks_list.set_readonly(readonly);
+        self.device_list.set_readonly(readonly);
+        let use_dbm = self.config.borrow().use_dbm();
+        self.network_list.set_signal_unit(use_dbm);
+        let accent_signal_bars = self.config.borrow().accent_signal_bars;
+        self.network_list.set_accent_signal_bars(accent_signal_bars);
+        let collapsed = self.config.borrow().collapsed_sections.clone();
+        self.network_list.set_collapsed_sections(&collapsed);
+        self.saved_networks_list.set_collapsed_sections(&collapsed);
+        self.device_list.set_collapsed_sections(&collapsed);
     }
     
-    pub fn show_password_dialog<F: Fn(Option<String>) + 'static>(&self, ssid: &str, callback: F) {
+    /// Hides WiFi controls for `Feature`s the running backend lacks.
+    pub fn set_wifi_features(&self, features: &[Feature]) {
+        self.network_list.set_features(features);
+        self.saved_networks_list.set_features(features);
+    }
+    
+    /// Asks for the password of `ssid`. The callback gets the password and whether the
+    /// new profile should connect automatically, or `None` when cancelled.
+    pub fn show_password_dialog<F: Fn(Option<(String, bool)>) + 'static>(&self, ssid: &str, security: &SecurityType, callback: F) {
         self.details_revealer.set_reveal_child(false);
         self.password_label.set_label(&format!("Enter password for {}:", ssid));
         self.password_entry.set_text("");
@@ -678,11 +1728,69 @@ impl OrbitWindow {
         self.password_error_label.set_visible(false);
         self.password_connect_btn.set_label("Connect");
         self.password_connect_btn.set_sensitive(true);
+        *self.password_labels.borrow_mut() = ("Connect", "Connecting...");
+        self.enterprise_box.set_visible(false);
+        self.caps_lock_label.set_visible(caps_lock_on());
+        self.autoconnect_check.set_active(true);
+        self.autoconnect_check.set_visible(true);
+        *self.password_is_psk.borrow_mut() = matches!(security, SecurityType::WPA | SecurityType::WPA2);
+        self.apply_password_peek();
+        *self.enterprise_callback.borrow_mut() = None;
         *self.password_callback.borrow_mut() = Some(Rc::new(callback));
         self.password_revealer.set_reveal_child(true);
         self.password_entry.grab_focus();
     }
     
+    /// Asks for a new password for the saved network `ssid`, reusing the password dialog.
+    pub fn show_change_password_dialog<F: Fn(String) + 'static>(&self, ssid: &str, callback: F) {
+        self.details_revealer.set_reveal_child(false);
+        self.password_label.set_label(&format!("New password for {}:", ssid));
+        self.password_entry.set_text("");
+        self.password_error_label.set_label("");
+        self.password_error_label.set_visible(false);
+        self.password_connect_btn.set_label("Save");
+        self.password_connect_btn.set_sensitive(true);
+        *self.password_labels.borrow_mut() = ("Save", "Saving...");
+        self.enterprise_box.set_visible(false);
+        self.caps_lock_label.set_visible(caps_lock_on());
+        self.autoconnect_check.set_visible(false);
+        // The saved profile may be WEP, so leave key validation to NetworkManager
+        *self.password_is_psk.borrow_mut() = false;
+        self.apply_password_peek();
+        *self.enterprise_callback.borrow_mut() = None;
+        *self.password_callback.borrow_mut() = Some(Rc::new(move |result: Option<(String, bool)>| {
+            if let Some((password, _)) = result {
+                callback(password);
+            }
+        }));
+        self.password_revealer.set_reveal_child(true);
+        self.password_entry.grab_focus();
+    }
+    
+    pub fn show_enterprise_dialog<F: Fn(Option<EnterpriseCredentials>) + 'static>(&self, ssid: &str, callback: F) {
+        self.details_revealer.set_reveal_child(false);
+        self.password_label.set_label(&format!("Sign in to {}:", ssid));
+        self.password_entry.set_text("");
+        self.identity_entry.set_text("");
+        self.anonymous_identity_entry.set_text("");
+        self.eap_method_dropdown.set_selected(0);
+        self.phase2_dropdown.set_selected(0);
+        self.password_error_label.set_label("");
+        self.password_error_label.set_visible(false);
+        self.password_connect_btn.set_label("Connect");
+        self.password_connect_btn.set_sensitive(true);
+        *self.password_labels.borrow_mut() = ("Connect", "Connecting...");
+        self.enterprise_box.set_visible(true);
+        self.caps_lock_label.set_visible(caps_lock_on());
+        self.autoconnect_check.set_visible(false);
+        *self.password_is_psk.borrow_mut() = false;
+        self.apply_password_peek();
+        *self.password_callback.borrow_mut() = None;
+        *self.enterprise_callback.borrow_mut() = Some(Rc::new(callback));
+        self.password_revealer.set_reveal_child(true);
+        self.identity_entry.grab_focus();
+    }
+    
     pub fn hide_password_dialog(&self) {
         self.password_entry.set_text("");
         self.password_error_label.set_label("");
@@ -691,6 +1799,7 @@ impl OrbitWindow {
         self.password_connect_btn.set_sensitive(true);
         self.password_revealer.set_reveal_child(false);
         *self.password_callback.borrow_mut() = None;
+        *self.enterprise_callback.borrow_mut() = None;
     }
 
     pub fn show_hidden_dialog<F: Fn(Option<(String, String)>) + 'static>(&self, callback: F) {
@@ -699,21 +1808,90 @@ impl OrbitWindow {
         self.error_revealer.set_reveal_child(false);
         self.hidden_ssid_entry.set_text("");
         self.hidden_password_entry.set_text("");
+        self.apply_password_peek();
         *self.hidden_callback.borrow_mut() = Some(Rc::new(callback));
         self.hidden_revealer.set_reveal_child(true);
         self.hidden_ssid_entry.grab_focus();
     }
     
+    /// Opens the form for saving a network that isn't in range yet. The callback gets the
+    /// profile to save; it is not called when the form is cancelled.
+    pub fn show_add_network_dialog<F: Fn(ExportedNetwork) + 'static>(&self, callback: F) {
+        self.details_revealer.set_reveal_child(false);
+        self.password_revealer.set_reveal_child(false);
+        self.hidden_revealer.set_reveal_child(false);
+        self.error_revealer.set_reveal_child(false);
+        self.add_ssid_entry.set_text("");
+        self.add_password_entry.set_text("");
+        self.add_security_dropdown.set_selected(ADD_SECURITY_DEFAULT);
+        self.add_password_entry.set_visible(true);
+        self.add_hidden_check.set_active(false);
+        self.add_error_label.set_visible(false);
+        self.apply_password_peek();
+        *self.add_callback.borrow_mut() = Some(Rc::new(callback));
+        self.add_revealer.set_reveal_child(true);
+        self.add_ssid_entry.grab_focus();
+    }
+    
+    pub fn hide_add_network_dialog(&self) {
+        self.add_revealer.set_reveal_child(false);
+        self.add_password_entry.set_text("");
+        *self.add_callback.borrow_mut() = None;
+    }
+    
+    /// Asks for a static IPv4 setup for `ssid`, starting from its `current` settings.
+    pub fn show_ipv4_dialog<F: Fn(Ipv4Method) + 'static>(&self, ssid: &str, current: &Ipv4Method, callback: F) {
+        self.details_revealer.set_reveal_child(false);
+        self.password_revealer.set_reveal_child(false);
+        self.hidden_revealer.set_reveal_child(false);
+        self.error_revealer.set_reveal_child(false);
+        self.ipv4_label.set_label(&format!("Static IPv4 for {}:", ssid));
+        match current {
+            Ipv4Method::Manual { address, prefix, gateway, dns } => {
+                self.ipv4_address_entry.set_text(address);
+                self.ipv4_prefix_entry.set_text(&prefix.to_string());
+                self.ipv4_gateway_entry.set_text(gateway);
+                self.ipv4_dns_entry.set_text(&dns.join(", "));
+            }
+            Ipv4Method::Auto => {
+                self.ipv4_address_entry.set_text("");
+                self.ipv4_prefix_entry.set_text("24");
+                self.ipv4_gateway_entry.set_text("");
+                self.ipv4_dns_entry.set_text("");
+            }
+        }
+        self.ipv4_error_label.set_visible(false);
+        *self.ipv4_callback.borrow_mut() = Some(Rc::new(callback));
+        self.ipv4_revealer.set_reveal_child(true);
+        self.ipv4_address_entry.grab_focus();
+    }
+    
     pub fn show_password_error(&self, message: &str) {
         let clean_msg = sanitize_error_message(message);
         self.password_error_label.set_label(&clean_msg);
         self.password_error_label.set_visible(true);
-        self.password_connect_btn.set_label("Connect");
+        self.password_connect_btn.set_label(self.password_labels.borrow().0);
         self.password_connect_btn.set_sensitive(true);
         self.password_entry.grab_focus();
     }
     
     
+    /// Reports a rejected password, inline when the password dialog is still open.
+    pub fn show_wrong_password(&self, ssid: &str) {
+        if self.password_revealer.reveals_child() {
+            self.password_error_label.set_label("Wrong password. Please try again.");
+            self.password_error_label.set_visible(true);
+            self.password_connect_btn.set_label("Connect");
+            self.password_connect_btn.set_sensitive(true);
+            self.password_entry.select_region(0, -1);
+            self.password_entry.grab_focus();
+            return;
+        }
+        self.details_revealer.set_reveal_child(false);
+        self.error_label.set_label(&format!("Wrong password for {}.", ssid));
+        self.error_revealer.set_reveal_child(true);
+    }
+    
     pub fn show_error(&self, message: &str) {
         // If password dialog is open, show error inline there instead
         if self.password_revealer.reveals_child() {
@@ -726,6 +1904,67 @@ impl OrbitWindow {
         self.error_revealer.set_reveal_child(true);
     }
     
+    /// Shows the file transfer overlay, or updates it while a transfer runs.
+    pub fn show_transfer_progress(&self, file_name: &str, transferred: u64, size: u64) {
+        if size > 0 {
+            let fraction = transferred as f64 / size as f64;
+            self.transfer_progress.set_fraction(fraction.clamp(0.0, 1.0));
+            self.transfer_label.set_label(&format!("Sending {} ({:.0}%)", file_name, fraction * 100.0));
+        } else {
+            self.transfer_progress.pulse();
+            self.transfer_label.set_label(&format!("Sending {}", file_name));
+        }
+        self.transfer_revealer.set_reveal_child(true);
+    }
+    
+    /// Asks the user to press the router's WPS button and counts down the time left.
+    /// `on_cancel` runs when the prompt's Cancel button is clicked.
+    pub fn show_wps_prompt<F: Fn() + 'static>(&self, ssid: &str, on_cancel: F) {
+        self.stop_wps_timer();
+        *self.wps_cancel_callback.borrow_mut() = Some(Rc::new(on_cancel));
+        
+        let total = crate::dbus::network_manager::WPS_TIMEOUT_SECS;
+        let started = std::time::Instant::now();
+        let ssid = ssid.to_string();
+        let label = self.wps_label.clone();
+        let progress = self.wps_progress.clone();
+        let update = move || {
+            let left = total.saturating_sub(started.elapsed().as_secs());
+            label.set_label(&format!("Press the WPS button on the {} router ({}s left)", ssid, left));
+            progress.set_fraction(left as f64 / total as f64);
+            left > 0
+        };
+        update();
+        let timer = self.wps_timer.clone();
+        let source = gtk4::glib::timeout_add_local(std::time::Duration::from_secs(1), move || {
+            if update() {
+                gtk4::glib::ControlFlow::Continue
+            } else {
+                *timer.borrow_mut() = None;
+                gtk4::glib::ControlFlow::Break
+            }
+        });
+        *self.wps_timer.borrow_mut() = Some(source);
+        self.wps_revealer.set_reveal_child(true);
+    }
+    
+    pub fn hide_wps_prompt(&self) {
+        self.stop_wps_timer();
+        *self.wps_cancel_callback.borrow_mut() = None;
+        self.wps_revealer.set_reveal_child(false);
+    }
+    
+    fn stop_wps_timer(&self) {
+        if let Some(source) = self.wps_timer.borrow_mut().take() {
+            source.remove();
+        }
+    }
+    
+    pub fn hide_transfer_progress(&self) {
+        self.transfer_revealer.set_reveal_child(false);
+        self.transfer_progress.set_fraction(0.0);
+    }
+    
     pub fn show_network_details(&self, details: &crate::dbus::network_manager::NetworkDetails) {
         while let Some(child) = self.details_content.first_child() {
             self.details_content.remove(&child);
@@ -741,17 +1980,33 @@ impl OrbitWindow {
         let gateway_text = if details.gateway.is_empty() { "N/A" } else { details.gateway.as_str() };
         let mac_text = if details.mac_address.is_empty() { "N/A" } else { details.mac_address.as_str() };
         let speed_text = if details.connection_speed.is_empty() { "N/A" } else { details.connection_speed.as_str() };
+        let frequency_text = match crate::dbus::network_manager::frequency_label(details.frequency) {
+            Some(label) => format!("{} MHz ({})", details.frequency, label),
+            None => "N/A".to_string(),
+        };
         
-        let rows: [(&str, &str, &str); 6] = [
-            ("SSID", details.ssid.as_str(), "network-wireless-symbolic"),
-            ("IP Address", ip_text, "network-server-symbolic"),
-            ("Gateway", gateway_text, "network-server-symbolic"),
-            ("DNS", dns_text.as_str(), "web-browser-symbolic"),
-            ("MAC Address", mac_text, "dialog-password-symbolic"),
-            ("Speed", speed_text, "network-transmit-receive-symbolic"),
+        // The last field marks values worth pasting into a terminal
+        let mut rows: Vec<(&str, &str, &str, bool)> = vec![
+            ("SSID", details.ssid.as_str(), "network-wireless-symbolic", false),
+            ("IP Address", ip_text, "network-server-symbolic", true),
+            ("Gateway", gateway_text, "network-server-symbolic", true),
+            ("DNS", dns_text.as_str(), "web-browser-symbolic", true),
+            ("MAC Address", mac_text, "dialog-password-symbolic", true),
+            ("Speed", speed_text, "network-transmit-receive-symbolic", false),
+            ("Frequency", frequency_text.as_str(), "network-wireless-signal-good-symbolic", false),
         ];
+        if !details.ip6_address.is_empty() {
+            rows.insert(2, ("IPv6 Address", details.ip6_address.as_str(), "network-server-symbolic", true));
+        }
+        let connected_text = match self.network_list.connected_for(&details.ssid) {
+            Some(age) if details.is_connected => format_connection_age(age),
+            _ => String::new(),
+        };
+        if !connected_text.is_empty() {
+            rows.push(("Connected For", connected_text.as_str(), "preferences-system-time-symbolic", false));
+        }
         
-        for (label, value, icon_name) in rows {
+        for (label, value, icon_name, copyable) in rows {
             let row = gtk::Box::builder()
                 .orientation(Orientation::Horizontal)
                 .css_classes(["orbit-details-row"])
@@ -780,17 +2035,369 @@ impl OrbitWindow {
             row.append(&icon);
             row.append(&label_widget);
             row.append(&value_widget);
+            
+            if copyable && value != "N/A" {
+                let copy_btn = gtk::Button::builder()
+                    .icon_name("edit-copy-symbolic")
+                    .css_classes(["orbit-button", "flat"])
+                    .tooltip_text(format!("Copy {}", label))
+                    .build();
+                let value = value.to_string();
+                copy_btn.connect_clicked(move |btn| {
+                    btn.display().clipboard().set_text(&value);
+                    btn.set_icon_name("object-select-symbolic");
+                    let btn = btn.clone();
+                    gtk4::glib::timeout_add_local_once(std::time::Duration::from_secs(1), move || {
+                        btn.set_icon_name("edit-copy-symbolic");
+                    });
+                });
+                row.append(&copy_btn);
+            }
             self.details_content.append(&row);
         }
         
+        if details.is_connected && !details.device_path.is_empty() {
+            self.details_content.append(&self.throughput_box);
+            self.start_throughput(&details.device_path);
+        } else {
+            self.stop_throughput();
+        }
+        
+        *self.details_ssid.borrow_mut() = details.ssid.clone();
+        *self.details_dns.borrow_mut() = details.dns_servers.clone();
+        *self.details_device_path.borrow_mut() = details.device_path.clone();
+        self.details_share_btn.set_visible(details.is_connected);
+        self.details_dns_btn.set_visible(details.is_connected && !self.is_readonly());
         self.password_revealer.set_reveal_child(false);
         self.error_revealer.set_reveal_child(false);
         self.details_revealer.set_reveal_child(true);
     }
+    
+    pub fn set_on_stats_tick<F: Fn(String) + 'static>(&self, callback: F) {
+        *self.on_stats_tick.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    /// Polls `device_path` statistics once a second until the details overlay is hidden.
+    fn start_throughput(&self, device_path: &str) {
+        self.stop_throughput();
+        *self.throughput_history.borrow_mut() = ThroughputHistory::default();
+        self.throughput_label.set_label("↓ -  ↑ -");
+        self.throughput_graph.queue_draw();
+        
+        if let Some(callback) = self.on_stats_tick.borrow().as_ref() {
+            callback(device_path.to_string());
+        }
+        
+        let device_path = device_path.to_string();
+        let revealer = self.details_revealer.clone();
+        let on_stats_tick = self.on_stats_tick.clone();
+        let timer = self.throughput_timer.clone();
+        let source = gtk4::glib::timeout_add_local(std::time::Duration::from_secs(1), move || {
+            if !revealer.reveals_child() {
+                *timer.borrow_mut() = None;
+                return gtk4::glib::ControlFlow::Break;
+            }
+            if let Some(callback) = on_stats_tick.borrow().as_ref() {
+                callback(device_path.clone());
+            }
+            gtk4::glib::ControlFlow::Continue
+        });
+        *self.throughput_timer.borrow_mut() = Some(source);
+    }
+    
+    fn stop_throughput(&self) {
+        if let Some(source) = self.throughput_timer.borrow_mut().take() {
+            source.remove();
+        }
+    }
+    
+    /// Feeds a cumulative (rx, tx) byte sample into the details throughput graph.
+    pub fn update_throughput(&self, rx: u64, tx: u64) {
+        if self.throughput_timer.borrow().is_none() {
+            return;
+        }
+        let rate = self.throughput_history.borrow_mut().push(rx, tx);
+        if let Some((down, up)) = rate {
+            self.throughput_label.set_label(&format!("↓ {}  ↑ {}", format_rate(down), format_rate(up)));
+            self.throughput_graph.queue_draw();
+        }
+    }
+    
+    /// Called with (ssid, device path, servers); `None` reverts to DNS from DHCP.
+    pub fn set_on_dns_override<F: Fn(String, String, Option<Vec<String>>) + 'static>(&self, callback: F) {
+        *self.on_dns_override.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    /// Replaces the details overlay content with an editor for the connection's DNS servers.
+    fn show_dns_editor(&self) {
+        self.stop_throughput();
+        while let Some(child) = self.details_content.first_child() {
+            self.details_content.remove(&child);
+        }
+        
+        let ssid = self.details_ssid.borrow().clone();
+        let label = gtk::Label::builder()
+            .label(&format!("DNS servers for {}:", ssid))
+            .css_classes(["orbit-detail-label"])
+            .halign(gtk::Align::Start)
+            .build();
+        
+        let entry = gtk::Entry::builder()
+            .placeholder_text("1.1.1.1, 9.9.9.9")
+            .text(&self.details_dns.borrow().join(", "))
+            .hexpand(true)
+            .build();
+        
+        let error_label = gtk::Label::builder()
+            .label("")
+            .css_classes(["orbit-password-error"])
+            .halign(gtk::Align::Start)
+            .wrap(true)
+            .visible(false)
+            .build();
+        
+        let btn_row = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .halign(gtk::Align::End)
+            .build();
+        
+        let revert_btn = gtk::Button::builder()
+            .label("Automatic")
+            .css_classes(["orbit-button", "flat"])
+            .build();
+        
+        let apply_btn = gtk::Button::builder()
+            .label("Apply")
+            .css_classes(["orbit-button", "primary", "flat"])
+            .build();
+        
+        btn_row.append(&revert_btn);
+        btn_row.append(&apply_btn);
+        
+        let device_path = self.details_device_path.borrow().clone();
+        
+        let on_dns = self.on_dns_override.clone();
+        let revealer = self.details_revealer.clone();
+        let ssid_revert = ssid.clone();
+        let device_revert = device_path.clone();
+        revert_btn.connect_clicked(move |_| {
+            if let Some(callback) = on_dns.borrow().as_ref() {
+                callback(ssid_revert.clone(), device_revert.clone(), None);
+            }
+            revealer.set_reveal_child(false);
+        });
+        
+        let on_dns = self.on_dns_override.clone();
+        let revealer = self.details_revealer.clone();
+        let entry_apply = entry.clone();
+        let submit = Rc::new(move || {
+            let servers: Vec<String> = entry_apply.text()
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect();
+            if servers.is_empty() {
+                error_label.set_label("Enter at least one server");
+                error_label.set_visible(true);
+                return;
+            }
+            if let Some(bad) = servers.iter().find(|s| s.parse::<std::net::Ipv4Addr>().is_err()) {
+                error_label.set_label(&format!("Invalid IPv4 address: {}", bad));
+                error_label.set_visible(true);
+                return;
+            }
+            if let Some(callback) = on_dns.borrow().as_ref() {
+                callback(ssid.clone(), device_path.clone(), Some(servers));
+            }
+            revealer.set_reveal_child(false);
+        });
+        
+        let submit_click = submit.clone();
+        apply_btn.connect_clicked(move |_| submit_click());
+        entry.connect_activate(move |_| submit());
+        
+        self.details_content.append(&label);
+        self.details_content.append(&entry);
+        self.details_content.append(&error_label);
+        self.details_content.append(&btn_row);
+        self.details_share_btn.set_visible(false);
+        self.details_dns_btn.set_visible(false);
+        entry.grab_focus();
+    }
+    
+    pub fn set_on_share<F: Fn(String) + 'static>(&self, callback: F) {
+        *self.on_share.borrow_mut() = Some(Rc::new(callback));
+    }
+    
+    /// Replaces the details overlay content with a WiFi QR code for `ssid`.
+    pub fn show_share_qr(&self, ssid: &str, psk: Option<&str>) {
+        let payload = wifi_qr_payload(ssid, psk);
+        let texture = match render_qr_texture(&payload) {
+            Some(t) => t,
+            None => {
+                self.show_error("Failed to generate QR code");
+                return;
+            }
+        };
+        
+        while let Some(child) = self.details_content.first_child() {
+            self.details_content.remove(&child);
+        }
+        
+        let picture = gtk::Picture::for_paintable(&texture);
+        picture.set_size_request(200, 200);
+        picture.set_halign(gtk::Align::Center);
+        
+        let caption = gtk::Label::builder()
+            .label(&format!("Scan to join {}", ssid))
+            .css_classes(["orbit-detail-label"])
+            .halign(gtk::Align::Center)
+            .build();
+        
+        self.stop_throughput();
+        self.details_content.append(&picture);
+        self.details_content.append(&caption);
+        self.details_share_btn.set_visible(false);
+        self.details_dns_btn.set_visible(false);
+        self.details_revealer.set_reveal_child(true);
+    }
+}
+
+/// Resolves a connector name such as "DP-1" to a monitor, logging when it isn't connected.
+fn find_monitor(connector: Option<&str>) -> Option<gtk4::gdk::Monitor> {
+    let connector = connector?;
+    let display = gtk4::gdk::Display::default()?;
+    let monitors = display.monitors();
+    for i in 0..monitors.n_items() {
+        if let Some(monitor) = monitors.item(i).and_downcast::<gtk4::gdk::Monitor>() {
+            if monitor.connector().as_deref() == Some(connector) {
+                return Some(monitor);
+            }
+        }
+    }
+    log::warn!("Monitor {} not found, using compositor default", connector);
+    None
+}
+
+/// Formats a byte rate as a short human-readable string, e.g. "1.2 MB/s".
+fn format_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec >= 1024.0 * 1024.0 {
+        format!("{:.1} MB/s", bytes_per_sec / (1024.0 * 1024.0))
+    } else if bytes_per_sec >= 1024.0 {
+        format!("{:.0} KB/s", bytes_per_sec / 1024.0)
+    } else {
+        format!("{:.0} B/s", bytes_per_sec)
+    }
+}
+
+/// Formats a connection's age as a short duration, e.g. "2h 13m".
+fn format_connection_age(age: std::time::Duration) -> String {
+    let minutes = age.as_secs() / 60;
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        "Less than a minute".to_string()
+    }
+}
+
+/// Builds a `WIFI:` URI as understood by phone cameras, escaping reserved characters.
+fn wifi_qr_payload(ssid: &str, psk: Option<&str>) -> String {
+    fn escape(value: &str) -> String {
+        let mut out = String::with_capacity(value.len());
+        for c in value.chars() {
+            if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out
+    }
+    
+    match psk {
+        Some(psk) => format!("WIFI:T:WPA;S:{};P:{};;", escape(ssid), escape(psk)),
+        None => format!("WIFI:T:nopass;S:{};;", escape(ssid)),
+    }
+}
+
+fn render_qr_texture(payload: &str) -> Option<gtk4::gdk::MemoryTexture> {
+    const SCALE: usize = 6;
+    const QUIET_ZONE: usize = 4;
+    
+    let code = qrcode::QrCode::new(payload.as_bytes()).ok()?;
+    let modules = code.width();
+    let size = (modules + QUIET_ZONE * 2) * SCALE;
+    let mut pixels = vec![255u8; size * size * 3];
+    
+    for (i, color) in code.to_colors().iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+        let x = (i % modules + QUIET_ZONE) * SCALE;
+        let y = (i / modules + QUIET_ZONE) * SCALE;
+        for dy in 0..SCALE {
+            let start = ((y + dy) * size + x) * 3;
+            pixels[start..start + SCALE * 3].fill(0);
+        }
+    }
+    
+    let bytes = gtk4::glib::Bytes::from_owned(pixels);
+    Some(gtk4::gdk::MemoryTexture::new(
+        size as i32,
+        size as i32,
+        gtk4::gdk::MemoryFormat::R8g8b8,
+        &bytes,
+        size * 3,
+    ))
 }
 
 /// Sanitize D-Bus/system error messages into user-friendly text.
-fn sanitize_error_message(message: &str) -> String {
+/// The `GtkText` inside a password entry, which owns the visibility the peek icon toggles.
+/// Security type for an entry of `ADD_SECURITY_LABELS`.
+fn add_security(index: u32) -> SecurityType {
+    match index {
+        0 => SecurityType::None,
+        1 => SecurityType::WEP,
+        3 => SecurityType::WPA3,
+        _ => SecurityType::WPA2,
+    }
+}
+
+/// Whether Caps Lock is currently on for the default seat's keyboard.
+fn caps_lock_on() -> bool {
+    gtk4::gdk::Display::default()
+        .and_then(|display| display.default_seat())
+        .and_then(|seat| seat.keyboard())
+        .map(|keyboard| keyboard.is_caps_locked())
+        .unwrap_or(false)
+}
+
+/// Explains why `password` can't be a WPA pre-shared key: passphrases are 8-63
+/// characters, or exactly 64 hex digits for a raw key.
+fn psk_length_hint(password: &str) -> Option<&'static str> {
+    let len = password.chars().count();
+    if len == 64 && password.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    if len < 8 {
+        Some("WPA passwords are at least 8 characters")
+    } else if len > 63 {
+        Some("WPA passwords are at most 63 characters")
+    } else {
+        None
+    }
+}
+
+fn peek_text(entry: &gtk::PasswordEntry) -> Option<gtk::Text> {
+    entry.delegate().and_then(|editable| editable.downcast::<gtk::Text>().ok())
+}
+
+pub fn sanitize_error_message(message: &str) -> String {
     let msg_lower = message.to_lowercase();
     
     if msg_lower.contains("secret") || msg_lower.contains("password") 